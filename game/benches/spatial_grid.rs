@@ -0,0 +1,76 @@
+//! Compares radius queries over the spatial grid with a linear scan over every entry, which is
+//! what splash damage, noise propagation and doors were doing before the grid was added.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use fyrox::{
+    core::{algebra::Vector3, pool::Handle},
+    scene::node::Node,
+};
+use station_iapetus::level::spatial::SpatialGrid;
+
+/// Size (in meters) of the square area over which the entries are scattered.
+const AREA_SIZE: f32 = 200.0;
+const QUERY_RADIUS: f32 = 5.0;
+
+fn make_entries(count: usize) -> Vec<(Handle<Node>, Vector3<f32>)> {
+    // Simple LCG, so every run uses the same layout.
+    let mut seed = 0x2545_f491u32;
+    let mut next = move || {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        (seed >> 8) as f32 / (1 << 24) as f32 * AREA_SIZE
+    };
+
+    (0..count)
+        .map(|i| {
+            (
+                Handle::new(i as u32 + 1, 1),
+                Vector3::new(next(), 0.0, next()),
+            )
+        })
+        .collect()
+}
+
+fn query_linear(
+    entries: &[(Handle<Node>, Vector3<f32>)],
+    center: Vector3<f32>,
+    radius: f32,
+) -> usize {
+    entries
+        .iter()
+        .filter(|(_, position)| position.metric_distance(&center) <= radius)
+        .count()
+}
+
+fn radius_query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("radius_query");
+    let center = Vector3::new(AREA_SIZE * 0.5, 0.0, AREA_SIZE * 0.5);
+
+    for count in [100, 200, 1_000, 10_000] {
+        let entries = make_entries(count);
+        let mut grid = SpatialGrid::default();
+        grid.rebuild(entries.iter().cloned());
+
+        group.bench_with_input(BenchmarkId::new("linear", count), &entries, |b, entries| {
+            b.iter(|| query_linear(entries, black_box(center), black_box(QUERY_RADIUS)))
+        });
+        group.bench_with_input(BenchmarkId::new("grid", count), &grid, |b, grid| {
+            b.iter(|| {
+                grid.query_radius(black_box(center), black_box(QUERY_RADIUS))
+                    .count()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn rebuild(c: &mut Criterion) {
+    let entries = make_entries(1_000);
+    let mut grid = SpatialGrid::default();
+    c.bench_function("grid_rebuild_1000", |b| {
+        b.iter(|| grid.rebuild(black_box(&entries).iter().cloned()))
+    });
+}
+
+criterion_group!(benches, radius_query, rebuild);
+criterion_main!(benches);
@@ -105,26 +105,46 @@ impl AimOnTarget {
             self.yaw_random_smooth_angle.update(dt);
         }
 
-        if yaw.angle.is_nan() {
-            let local_look = scene.graph[model].look_vector();
-            yaw.angle = local_look.x.atan2(local_look.z);
-        }
-
-        yaw.set_target(
-            look_dir.x.atan2(look_dir.z) + angle_hack + self.yaw_random_smooth_angle.angle(),
+        turn_towards(
+            yaw,
+            look_dir,
+            scene,
+            model,
+            body,
+            dt,
+            angle_hack + self.yaw_random_smooth_angle.angle(),
         )
-        .update(dt);
+    }
+}
 
-        if let Some(body) = scene.graph.try_get_mut(body) {
-            body.local_transform_mut()
-                .set_rotation(UnitQuaternion::from_axis_angle(
-                    &Vector3::y_axis(),
-                    yaw.angle(),
-                ));
-        }
+/// Smoothly rotates the body of a bot around vertical axis, so it will face the given direction.
+/// Returns `true` if the body is facing the direction.
+pub fn turn_towards(
+    yaw: &mut SmoothAngle,
+    look_dir: Vector3<f32>,
+    scene: &mut Scene,
+    model: Handle<Node>,
+    body: Handle<Node>,
+    dt: f32,
+    angle_offset: f32,
+) -> bool {
+    if yaw.angle.is_nan() {
+        let local_look = scene.graph[model].look_vector();
+        yaw.angle = local_look.x.atan2(local_look.z);
+    }
 
-        yaw.at_target()
+    yaw.set_target(look_dir.x.atan2(look_dir.z) + angle_offset)
+        .update(dt);
+
+    if let Some(body) = scene.graph.try_get_mut(body) {
+        body.local_transform_mut()
+            .set_rotation(UnitQuaternion::from_axis_angle(
+                &Vector3::y_axis(),
+                yaw.angle(),
+            ));
     }
+
+    yaw.at_target()
 }
 
 impl<'a> Behavior<'a> for AimOnTarget {
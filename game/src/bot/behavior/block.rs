@@ -0,0 +1,79 @@
+//! Heavily armored bots can raise a guard that reduces damage from the front.
+
+use crate::{
+    bot::behavior::{aim::turn_towards, BehaviorContext},
+    utils,
+};
+use fyrox::{
+    core::visitor::prelude::*,
+    utils::behavior::{Behavior, Status},
+};
+
+#[derive(Default, Debug, PartialEq, Visit, Clone)]
+pub struct Block {
+    /// Max distance to the target at which the bot considers blocking. The bot also blocks when
+    /// it is under fire.
+    pub max_distance: f32,
+}
+
+impl Block {
+    fn is_target_close(&self, ctx: &BehaviorContext) -> bool {
+        ctx.target.as_ref().is_some_and(|target| {
+            target
+                .position
+                .metric_distance(&ctx.character.position(&ctx.scene.graph))
+                <= self.max_distance
+        })
+    }
+
+    /// Keeps the guard towards the target.
+    fn track_target(ctx: &mut BehaviorContext) {
+        let Some(target_position) = ctx.target.as_ref().map(|t| t.position) else {
+            return;
+        };
+
+        let look_dir = target_position - ctx.character.position(&ctx.scene.graph);
+
+        turn_towards(
+            ctx.yaw,
+            look_dir,
+            ctx.scene,
+            ctx.model,
+            ctx.character.body,
+            ctx.dt,
+            ctx.h_aim_angle_hack.to_radians(),
+        );
+    }
+}
+
+impl<'a> Behavior<'a> for Block {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        if *ctx.block_timer <= 0.0 {
+            if ctx.block_chance <= 0.0
+                || *ctx.block_cooldown_timer > 0.0
+                || !(ctx.is_under_fire || self.is_target_close(ctx))
+            {
+                return Status::Failure;
+            }
+
+            // Every cooldown period gives just a single chance to block.
+            *ctx.block_cooldown_timer = ctx.block_cooldown;
+
+            if !utils::is_probability_event_occurred(ctx.block_chance) {
+                return Status::Failure;
+            }
+
+            *ctx.block_timer = ctx.block_duration;
+            // The cooldown starts when the guard is lowered.
+            *ctx.block_cooldown_timer += ctx.block_duration;
+        }
+
+        ctx.character.stand_still(&mut ctx.scene.graph);
+        Self::track_target(ctx);
+        ctx.is_blocking = true;
+
+        Status::Running
+    }
+}
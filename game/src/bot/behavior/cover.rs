@@ -0,0 +1,309 @@
+//! Bots can hide behind obstacles when they're under fire, this mod has behavior nodes for this.
+//! A bot in cover periodically peeks out of it to shoot at the threat and then hides again.
+
+use crate::{
+    bot::behavior::{aim::turn_towards, movement::move_towards, BehaviorContext},
+    level::hit_box::HitBox,
+    weapon::{weapon_mut, Weapon},
+};
+use fyrox::{
+    core::{
+        algebra::{Point3, Vector3},
+        arrayvec::ArrayVec,
+        visitor::prelude::*,
+    },
+    graph::{BaseSceneGraph, SceneGraph},
+    scene::{
+        collider::{Collider, ColliderShape},
+        graph::{
+            physics::{Intersection, RayCastOptions},
+            Graph,
+        },
+        navmesh::NavigationalMesh,
+    },
+    utils::behavior::{Behavior, Status},
+};
+
+/// Height of a cover point above the navmesh at which occlusion will be checked.
+const COVER_HEIGHT: f32 = 1.0;
+const COVER_SEARCH_RADII: [f32; 3] = [3.0, 6.0, 9.0];
+const COVER_SEARCH_DIRECTIONS: usize = 12;
+/// Max distance (in meters) from a cover point to a point from which a bot can shoot.
+const PEEK_SEARCH_RADIUS: f32 = 1.5;
+/// Time (in seconds) a bot stays in cover before peeking out of it.
+const COVER_HIDE_TIME: f32 = 1.5;
+/// Time (in seconds) a bot shoots from a peek point before hiding again.
+const COVER_PEEK_TIME: f32 = 2.0;
+
+/// Checks whether there is some static geometry between the two points. Actors are ignored.
+fn is_line_of_sight_blocked(graph: &Graph, from: Vector3<f32>, to: Vector3<f32>) -> bool {
+    let ray_direction = to - from;
+
+    let mut query_buffer = ArrayVec::<Intersection, 64>::new();
+    graph.physics.cast_ray(
+        RayCastOptions {
+            ray_origin: Point3::from(from),
+            ray_direction,
+            max_len: ray_direction.norm(),
+            groups: Default::default(),
+            sort_results: true,
+        },
+        &mut query_buffer,
+    );
+
+    query_buffer.iter().any(|intersection| {
+        graph
+            .try_get_of_type::<Collider>(intersection.collider)
+            .is_some_and(|collider| {
+                !collider.is_sensor()
+                    && !matches!(collider.shape(), ColliderShape::Capsule(_))
+                    && graph
+                        .try_get_script_of::<HitBox>(intersection.collider)
+                        .is_none()
+            })
+    })
+}
+
+#[derive(Default, Debug, PartialEq, Visit, Eq, Clone)]
+pub struct NeedsCover;
+
+impl<'a> Behavior<'a> for NeedsCover {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        let Some(target) = ctx.target.as_ref() else {
+            *ctx.is_peeking_from_cover = false;
+            return Status::Failure;
+        };
+
+        // A bot that peeks out of its cover has a clean shot, but it still needs the cover.
+        if *ctx.is_peeking_from_cover {
+            return Status::Success;
+        }
+
+        if !ctx.is_under_fire {
+            return Status::Failure;
+        }
+
+        let is_reloading = ctx
+            .scene
+            .graph
+            .try_get_script_component_of::<Weapon>(ctx.character.current_weapon())
+            .is_some_and(|weapon| weapon.is_reloading() || weapon.is_magazine_empty());
+
+        let has_clean_shot = !is_line_of_sight_blocked(
+            &ctx.scene.graph,
+            ctx.character.position(&ctx.scene.graph),
+            target.position,
+        );
+
+        if is_reloading || !has_clean_shot {
+            Status::Success
+        } else {
+            Status::Failure
+        }
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Visit, Clone)]
+pub struct SeekCover {
+    cover_point: Option<Vector3<f32>>,
+    peek_point: Option<Vector3<f32>>,
+    // Time left until the bot peeks out of the cover, or until it hides again while peeking.
+    timer: f32,
+}
+
+impl SeekCover {
+    fn is_cover_valid(graph: &Graph, point: Vector3<f32>, threat: Vector3<f32>) -> bool {
+        is_line_of_sight_blocked(graph, point + Vector3::new(0.0, COVER_HEIGHT, 0.0), threat)
+    }
+
+    /// Samples a number of points around the bot on the navmesh and selects the closest one that
+    /// is not visible from the threat position.
+    fn find_cover(
+        graph: &Graph,
+        navmesh: &NavigationalMesh,
+        self_position: Vector3<f32>,
+        threat: Vector3<f32>,
+    ) -> Option<Vector3<f32>> {
+        let navmesh = navmesh.navmesh_ref();
+
+        let mut closest = None;
+        let mut closest_distance = f32::MAX;
+        for radius in COVER_SEARCH_RADII {
+            for i in 0..COVER_SEARCH_DIRECTIONS {
+                let angle = i as f32 / COVER_SEARCH_DIRECTIONS as f32 * std::f32::consts::TAU;
+                let candidate =
+                    self_position + Vector3::new(angle.cos() * radius, 0.0, angle.sin() * radius);
+
+                let Some((point, _)) = navmesh.query_closest(candidate) else {
+                    continue;
+                };
+
+                let distance = point.metric_distance(&self_position);
+                if distance < closest_distance && Self::is_cover_valid(graph, point, threat) {
+                    closest = Some(point);
+                    closest_distance = distance;
+                }
+            }
+
+            // Prefer closer covers.
+            if closest.is_some() {
+                break;
+            }
+        }
+
+        closest
+    }
+
+    /// Selects the closest point near the cover from which the threat is visible.
+    fn find_peek_point(
+        graph: &Graph,
+        navmesh: &NavigationalMesh,
+        cover_point: Vector3<f32>,
+        threat: Vector3<f32>,
+    ) -> Option<Vector3<f32>> {
+        let navmesh = navmesh.navmesh_ref();
+
+        let mut closest = None;
+        let mut closest_distance = f32::MAX;
+        for i in 0..COVER_SEARCH_DIRECTIONS {
+            let angle = i as f32 / COVER_SEARCH_DIRECTIONS as f32 * std::f32::consts::TAU;
+            let candidate = cover_point
+                + Vector3::new(
+                    angle.cos() * PEEK_SEARCH_RADIUS,
+                    0.0,
+                    angle.sin() * PEEK_SEARCH_RADIUS,
+                );
+
+            let Some((point, _)) = navmesh.query_closest(candidate) else {
+                continue;
+            };
+
+            let distance = point.metric_distance(&cover_point);
+            if distance < closest_distance && !Self::is_cover_valid(graph, point, threat) {
+                closest = Some(point);
+                closest_distance = distance;
+            }
+        }
+
+        closest
+    }
+
+    fn is_weapon_ready(ctx: &BehaviorContext) -> bool {
+        ctx.scene
+            .graph
+            .try_get_script_component_of::<Weapon>(ctx.character.current_weapon())
+            .is_some_and(|weapon| !weapon.is_reloading() && !weapon.is_magazine_empty())
+    }
+
+    fn face(ctx: &mut BehaviorContext, point: Vector3<f32>) {
+        let position = ctx.character.position(&ctx.scene.graph);
+        turn_towards(
+            ctx.yaw,
+            point - position,
+            ctx.scene,
+            ctx.model,
+            ctx.character.body,
+            ctx.dt,
+            ctx.h_aim_angle_hack.to_radians(),
+        );
+    }
+
+    /// Moves the bot to the given point facing the direction of movement. Returns `true` if the
+    /// point is reached.
+    fn move_to(ctx: &mut BehaviorContext, point: Vector3<f32>, threshold: f32) -> bool {
+        if move_towards(ctx, Some(point), threshold) {
+            ctx.character.stand_still(&mut ctx.scene.graph);
+            true
+        } else {
+            if let Some(steering_target) = ctx.agent.steering_target() {
+                Self::face(ctx, steering_target);
+            }
+            false
+        }
+    }
+
+    fn hide(&mut self, ctx: &mut BehaviorContext) {
+        *ctx.is_peeking_from_cover = false;
+        self.peek_point = None;
+        self.timer = COVER_HIDE_TIME;
+    }
+}
+
+impl<'a> Behavior<'a> for SeekCover {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        let Some(threat) = ctx.target.as_ref().map(|t| t.position) else {
+            return Status::Failure;
+        };
+
+        if *ctx.is_peeking_from_cover {
+            self.timer -= ctx.dt;
+            match self.peek_point {
+                Some(peek_point) if self.timer > 0.0 && Self::is_weapon_ready(ctx) => {
+                    // Let the rest of the sequence aim and shoot, once the bot is in position.
+                    return if Self::move_to(ctx, peek_point, 0.3) {
+                        Status::Success
+                    } else {
+                        Status::Running
+                    };
+                }
+                _ => self.hide(ctx),
+            }
+        }
+
+        let self_position = ctx.character.position(&ctx.scene.graph);
+
+        if !self
+            .cover_point
+            .is_some_and(|point| Self::is_cover_valid(&ctx.scene.graph, point, threat))
+        {
+            self.cover_point = ctx
+                .scene
+                .graph
+                .try_get_of_type::<NavigationalMesh>(ctx.navmesh)
+                .and_then(|navmesh| {
+                    Self::find_cover(&ctx.scene.graph, navmesh, self_position, threat)
+                });
+            self.timer = COVER_HIDE_TIME;
+        }
+
+        let Some(cover_point) = self.cover_point else {
+            return Status::Failure;
+        };
+
+        // Use the time in cover to reload the weapon.
+        if let Some(&weapon) = ctx.character.weapons.get(ctx.character.current_weapon) {
+            let weapon = weapon_mut(weapon, &mut ctx.scene.graph);
+            if weapon.is_magazine_empty() && !weapon.is_reloading() {
+                weapon.reload(&ctx.character.inventory);
+            }
+        }
+
+        if Self::move_to(ctx, cover_point, 0.5) {
+            Self::face(ctx, threat);
+
+            self.timer -= ctx.dt;
+            if self.timer <= 0.0 && Self::is_weapon_ready(ctx) {
+                self.peek_point = ctx
+                    .scene
+                    .graph
+                    .try_get_of_type::<NavigationalMesh>(ctx.navmesh)
+                    .and_then(|navmesh| {
+                        Self::find_peek_point(&ctx.scene.graph, navmesh, cover_point, threat)
+                    });
+
+                if self.peek_point.is_some() {
+                    *ctx.is_peeking_from_cover = true;
+                    self.timer = COVER_PEEK_TIME;
+                } else {
+                    self.timer = COVER_HIDE_TIME;
+                }
+            }
+        }
+
+        Status::Running
+    }
+}
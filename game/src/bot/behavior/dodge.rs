@@ -0,0 +1,119 @@
+//! Agile bots can dodge projectiles that are about to hit them.
+
+use crate::{
+    bot::behavior::{movement::is_walkable, BehaviorContext},
+    utils,
+    weapon::projectile::Projectile,
+    Game,
+};
+use fyrox::{
+    core::{algebra::Vector3, visitor::prelude::*},
+    graph::BaseSceneGraph,
+    scene::rigidbody::RigidBody,
+    utils::behavior::{Behavior, Status},
+};
+
+/// Max time (in seconds) before an impact at which the bot reacts to an incoming projectile.
+const MAX_TIME_TO_IMPACT: f32 = 0.5;
+/// Max distance (in meters) between the bot and the flight path of a projectile at which the
+/// projectile is considered dangerous.
+const DANGER_RADIUS: f32 = 1.0;
+/// Max horizontal distance between the dodge destination and the navmesh.
+const DODGE_NAVMESH_TOLERANCE: f32 = 0.2;
+
+#[derive(Default, Debug, PartialEq, Visit, Clone)]
+pub struct Dodge {
+    time_left: f32,
+    direction: Vector3<f32>,
+}
+
+impl Dodge {
+    /// Searches for a projectile of someone else that is about to hit the bot and returns the
+    /// horizontal direction at which the bot should dodge it.
+    fn find_dodge_direction(ctx: &BehaviorContext) -> Option<Vector3<f32>> {
+        let graph = &ctx.scene.graph;
+        let level = ctx.plugins.get::<Game>().level.as_ref()?;
+        let self_position = ctx.character.position(graph);
+
+        level.projectiles.iter().find_map(|&projectile_handle| {
+            let projectile_node = graph.try_get(projectile_handle)?;
+            if !projectile_node.is_globally_enabled() {
+                return None;
+            }
+
+            let projectile = projectile_node.try_get_script::<Projectile>()?;
+            if projectile.owner_character(graph) == ctx.bot_handle {
+                return None;
+            }
+
+            let velocity = projectile.velocity();
+            let speed_sqr = velocity.norm_squared();
+            if speed_sqr <= f32::EPSILON {
+                return None;
+            }
+
+            // Find the closest point of the flight path to the bot.
+            let to_bot = self_position - projectile_node.global_position();
+            let time_to_impact = to_bot.dot(&velocity) / speed_sqr;
+            if time_to_impact <= 0.0 || time_to_impact > MAX_TIME_TO_IMPACT {
+                return None;
+            }
+
+            let miss = to_bot - velocity.scale(time_to_impact);
+            if miss.norm() > DANGER_RADIUS {
+                return None;
+            }
+
+            // Dodge away from the flight path.
+            let side = Vector3::y().cross(&velocity).try_normalize(f32::EPSILON)?;
+            Some(if side.dot(&miss) >= 0.0 { side } else { -side })
+        })
+    }
+}
+
+impl<'a> Behavior<'a> for Dodge {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        if self.time_left <= 0.0 {
+            if ctx.dodge_chance <= 0.0 || *ctx.dodge_cooldown_timer > 0.0 {
+                return Status::Failure;
+            }
+
+            let Some(direction) = Self::find_dodge_direction(ctx) else {
+                return Status::Failure;
+            };
+
+            // Every incoming projectile gives just a single chance to dodge.
+            *ctx.dodge_cooldown_timer = ctx.dodge_cooldown;
+
+            let self_position = ctx.character.position(&ctx.scene.graph);
+            let destination = self_position + direction.scale(ctx.dodge_speed * ctx.dodge_duration);
+            if !utils::is_probability_event_occurred(ctx.dodge_chance)
+                || !is_walkable(ctx, destination, DODGE_NAVMESH_TOLERANCE)
+            {
+                return Status::Failure;
+            }
+
+            self.time_left = ctx.dodge_duration;
+            self.direction = direction;
+        }
+
+        self.time_left -= ctx.dt;
+
+        if let Some(body) = ctx
+            .scene
+            .graph
+            .try_get_mut_of_type::<RigidBody>(ctx.character.body)
+        {
+            let velocity = self.direction.scale(ctx.dodge_speed);
+            body.set_lin_vel(Vector3::new(velocity.x, body.lin_vel().y, velocity.z));
+        }
+
+        ctx.is_moving = true;
+        ctx.is_strafing = true;
+        ctx.is_dodging = true;
+
+        Status::Running
+    }
+}
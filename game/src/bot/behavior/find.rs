@@ -5,66 +5,211 @@ use crate::{
 };
 use fyrox::{
     core::{
-        algebra::{Matrix4, Point3, Vector3},
-        math::{frustum::Frustum, ray::Ray},
+        algebra::{Point3, Vector3},
+        color::Color,
+        math::ray::Ray,
         pool::Handle,
         visitor::prelude::*,
     },
     graph::BaseSceneGraph,
+    rand::{thread_rng, Rng},
     scene::{
         collider::{ColliderShape, InteractionGroups},
-        graph::{physics::RayCastOptions, Graph},
+        debug::{Line, SceneDrawingContext},
+        graph::{
+            physics::{Intersection, RayCastOptions},
+            Graph,
+        },
         node::Node,
     },
     utils::behavior::{Behavior, Status},
 };
 
-#[derive(Default, Debug, PartialEq, Visit, Clone)]
-pub struct FindTarget {
-    frustum: Frustum,
+/// Max distance at which a bot can hear footsteps of other actors, regardless of its vision cone.
+const HEARING_DISTANCE: f32 = 1.6;
+
+/// A cone in which a bot can see other actors.
+#[derive(Debug, Clone)]
+pub struct VisionCone {
+    pub origin: Vector3<f32>,
+    pub direction: Vector3<f32>,
+    pub half_angle: f32,
+    pub distance: f32,
 }
 
-impl FindTarget {
-    fn update_frustum(&mut self, position: Vector3<f32>, graph: &Graph, model: Handle<Node>) {
-        let head_pos = position + Vector3::new(0.0, 0.4, 0.0);
-        let up = graph[model].up_vector();
-        let look_at = head_pos + graph[model].look_vector();
-        let view_matrix = Matrix4::look_at_rh(&Point3::from(head_pos), &Point3::from(look_at), &up);
-        let projection_matrix =
-            Matrix4::new_perspective(16.0 / 9.0, 90.0f32.to_radians(), 0.1, 20.0);
-        let view_projection_matrix = projection_matrix * view_matrix;
-        self.frustum = Frustum::from_view_projection_matrix(view_projection_matrix).unwrap();
+impl VisionCone {
+    pub fn new(
+        position: Vector3<f32>,
+        graph: &Graph,
+        model: Handle<Node>,
+        fov_degrees: f32,
+        view_distance: f32,
+    ) -> Self {
+        Self {
+            origin: position + Vector3::new(0.0, 0.4, 0.0),
+            direction: graph[model]
+                .look_vector()
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(Vector3::z),
+            half_angle: (fov_degrees * 0.5).to_radians(),
+            distance: view_distance,
+        }
+    }
+
+    pub fn contains_point(&self, point: Vector3<f32>) -> bool {
+        let to_point = point - self.origin;
+        let distance = to_point.norm();
+        if distance > self.distance {
+            return false;
+        }
+        if distance <= f32::EPSILON {
+            return true;
+        }
+        to_point.angle(&self.direction) <= self.half_angle
     }
+
+    pub fn debug_draw(&self, context: &mut SceneDrawingContext, color: Color) {
+        const SEGMENTS: usize = 16;
+
+        let side = if self.direction.y.abs() < 0.99 {
+            Vector3::y()
+        } else {
+            Vector3::x()
+        };
+        let u = self.direction.cross(&side).normalize();
+        let v = self.direction.cross(&u);
+
+        let (sin, cos) = self.half_angle.sin_cos();
+        let points = (0..SEGMENTS)
+            .map(|i| {
+                let angle = i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+                let radial = u.scale(angle.cos()) + v.scale(angle.sin());
+                self.origin + (self.direction.scale(cos) + radial.scale(sin)).scale(self.distance)
+            })
+            .collect::<Vec<_>>();
+
+        for (i, point) in points.iter().enumerate() {
+            context.add_line(Line {
+                begin: self.origin,
+                end: *point,
+                color,
+            });
+            context.add_line(Line {
+                begin: *point,
+                end: points[(i + 1) % SEGMENTS],
+                color,
+            });
+        }
+    }
+}
+
+/// Checks if there's nothing but characters between the two given points.
+fn is_in_line_of_sight(
+    graph: &Graph,
+    from: Vector3<f32>,
+    to: Vector3<f32>,
+    query_buffer: &mut Vec<Intersection>,
+) -> bool {
+    let ray = Ray::from_two_points(from, to);
+    graph.physics.cast_ray(
+        RayCastOptions {
+            ray_origin: Point3::from(ray.origin),
+            ray_direction: ray.dir,
+            groups: InteractionGroups::default(),
+            max_len: ray.dir.norm(),
+            sort_results: true,
+        },
+        query_buffer,
+    );
+
+    query_buffer.iter().all(|hit| {
+        matches!(
+            graph[hit.collider].as_collider().shape(),
+            ColliderShape::Capsule(_)
+        )
+    })
 }
 
+#[derive(Default, Debug, PartialEq, Visit, Clone)]
+pub struct FindTarget;
+
 impl<'a> Behavior<'a> for FindTarget {
     type Context = BehaviorContext<'a>;
 
     fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        // The surroundings are checked periodically (more often on harder difficulties), the bot
+        // sticks to its previous decision in between and only follows the movement of its target.
+        if *ctx.target_check_timer > 0.0 {
+            return if let Some(target) = ctx.target.as_mut() {
+                if let Some(node) = ctx.scene.graph.try_get(target.handle) {
+                    target.position = node.global_position();
+                }
+                Status::Success
+            } else {
+                ctx.character.stand_still(&mut ctx.scene.graph);
+                Status::Failure
+            };
+        }
+        // The check might have been skipped for a while, when the bot was busy with something else.
+        let elapsed = (ctx.target_check_interval - *ctx.target_check_timer)
+            .min(ctx.target_check_interval + ctx.dt);
+        *ctx.target_check_timer = ctx.target_check_interval;
+
         let graph = &ctx.scene.graph;
 
         let position = ctx.character.position(graph);
 
-        self.update_frustum(position, graph, ctx.model);
+        let vision_cone = VisionCone::new(
+            position,
+            graph,
+            ctx.model,
+            ctx.fov_degrees,
+            ctx.view_distance,
+        );
 
-        // Check if existing target is valid.
+        let mut query_buffer = Vec::default();
+
+        // A bot that was not aware of any danger is startled by a newly spotted target.
+        let was_alert = ctx.target.is_some() || ctx.last_known_position.is_some();
+
+        // Check if existing target is valid. Once the target is acquired, the bot tracks it while
+        // the target stays in line of sight (or hearing range), regardless of the vision cone.
+        let mut tracked_target = None;
         if let Some(target) = ctx.target {
             for &actor_handle in ctx.actors {
                 if actor_handle != ctx.bot_handle && actor_handle == target.handle {
                     if let Some(character) = try_get_character_ref(actor_handle, graph) {
                         if !character.is_dead(graph) {
-                            target.position = character.position(graph);
-                            return Status::Success;
+                            let character_position = character.position(graph);
+                            if actor_handle == ctx.heard_actor
+                                || position.metric_distance(&character_position) < HEARING_DISTANCE
+                                || is_in_line_of_sight(
+                                    graph,
+                                    character_position,
+                                    position,
+                                    &mut query_buffer,
+                                )
+                            {
+                                target.position = character_position;
+                                tracked_target = Some(target.clone());
+                                break;
+                            }
+
+                            // The target has hidden, remember where it was seen the last time, so
+                            // the bot could search for it.
+                            *ctx.last_known_position = Some(target.position);
+                            *ctx.search_timer = ctx.search_duration;
                         }
                     }
                 }
             }
         }
 
-        // Reset target and try to find new one.
-        *ctx.target = None;
+        // Search for the most dangerous actor among the ones the bot is aware of. The closest one
+        // is preferred among the equally dangerous actors.
+        let mut best_target = None;
+        let mut best_threat = f32::MIN;
         let mut closest_distance = f32::MAX;
-        let mut query_buffer = Vec::default();
         'target_loop: for &actor_handle in ctx
             .actors
             .iter()
@@ -87,6 +232,11 @@ impl<'a> Behavior<'a> for FindTarget {
                 continue 'target_loop;
             }
 
+            // Never attack allies.
+            if actor_handle == ctx.leader || character.is_ally_of(ctx.character) {
+                continue 'target_loop;
+            }
+
             // Check hostility.
             match ctx.hostility {
                 BotHostility::OtherSpecies => {
@@ -95,56 +245,65 @@ impl<'a> Behavior<'a> for FindTarget {
                     }
                 }
                 BotHostility::Player => {
-                    if character_node.has_script::<Bot>() {
+                    // Escorts are fighting alongside the player, so they are enemies too.
+                    if character_node
+                        .try_get_script::<Bot>()
+                        .is_some_and(|bot| !bot.is_escort())
+                    {
                         continue 'target_loop;
                     }
                 }
                 BotHostility::Everyone => {}
             }
 
-            // Check each target for two criteria:
-            // 1) Is close enough to bot ("can hear")
-            // 2) Is visible to bot ("can see")
+            // Check each target for three criteria:
+            // 1) Has made a loud noise (shot) that the bot heard
+            // 2) Is close enough to bot ("can hear")
+            // 3) Is visible to bot ("can see")
             let distance = position.metric_distance(&character_position);
-            if distance != 0.0 && distance < 1.6
-                || self.frustum.is_contains_point(character_position)
-            {
-                let ray = Ray::from_two_points(character_position, position);
-                ctx.scene.graph.physics.cast_ray(
-                    RayCastOptions {
-                        ray_origin: Point3::from(ray.origin),
-                        ray_direction: ray.dir,
-                        groups: InteractionGroups::default(),
-                        max_len: ray.dir.norm(),
-                        sort_results: true,
-                    },
-                    &mut query_buffer,
-                );
-
-                'hit_loop: for hit in query_buffer.iter() {
-                    let collider = ctx.scene.graph[hit.collider].as_collider();
-
-                    if let ColliderShape::Capsule(_) = collider.shape() {
-                        // Prevent setting self as target.
-                        if ctx.character.capsule_collider == hit.collider {
-                            continue 'hit_loop;
-                        }
-                    } else {
-                        // Target is behind something.
-                        continue 'target_loop;
-                    }
+            if actor_handle != ctx.heard_actor {
+                if !(distance != 0.0 && distance < HEARING_DISTANCE
+                    || vision_cone.contains_point(character_position))
+                {
+                    continue 'target_loop;
                 }
 
-                if distance < closest_distance {
-                    *ctx.target = Some(Target {
-                        position: character_position,
-                        handle: actor_handle,
-                    });
-                    closest_distance = distance;
+                // Target is behind something.
+                if !is_in_line_of_sight(graph, character_position, position, &mut query_buffer) {
+                    continue 'target_loop;
                 }
             }
+
+            // Actors that are close to the bot are getting more dangerous over time.
+            let closeness = 1.0 - (distance / ctx.view_distance.max(f32::EPSILON)).min(1.0);
+            let threat = ctx.threat.entry(actor_handle).or_default();
+            *threat += ctx.proximity_threat * closeness * elapsed;
+            let threat = *threat;
+
+            if threat > best_threat || (threat == best_threat && distance < closest_distance) {
+                best_target = Some(Target {
+                    position: character_position,
+                    handle: actor_handle,
+                });
+                best_threat = threat;
+                closest_distance = distance;
+            }
         }
 
+        // Keep the current target, unless someone else is much more dangerous.
+        *ctx.target = match (tracked_target, best_target) {
+            (Some(tracked), Some(best))
+                if best.handle != tracked.handle
+                    && best_threat
+                        > ctx.threat.get(&tracked.handle).cloned().unwrap_or_default()
+                            + ctx.threat_switch_margin =>
+            {
+                Some(best)
+            }
+            (Some(tracked), _) => Some(tracked),
+            (None, best) => best,
+        };
+
         // Check points of interest.
         if ctx.target.is_none() {
             let level = ctx
@@ -165,12 +324,20 @@ impl<'a> Behavior<'a> for FindTarget {
         }
 
         if ctx.target.is_some() {
+            if !was_alert {
+                *ctx.reaction_timer = ctx.reaction_time * thread_rng().gen_range(0.75..=1.25);
+            }
+
+            // The bot has found something, there's no need to search anymore.
+            *ctx.last_known_position = None;
+            *ctx.search_timer = 0.0;
+
             Status::Success
         } else {
             ctx.character.stand_still(&mut ctx.scene.graph);
 
-            // Keep looking.
-            Status::Running
+            // Keep looking, the bot is free to do something else (patrol, for example) meanwhile.
+            Status::Failure
         }
     }
 }
@@ -0,0 +1,59 @@
+//! Escort bots follow their leader (usually the player) when there are no enemies around.
+
+use crate::{
+    bot::behavior::{aim::turn_towards, movement::move_towards, BehaviorContext},
+    character::try_get_character_ref,
+};
+use fyrox::{
+    core::visitor::prelude::*,
+    utils::behavior::{Behavior, Status},
+};
+
+#[derive(Default, Debug, PartialEq, Visit, Eq, Clone)]
+pub struct FollowLeader;
+
+impl<'a> Behavior<'a> for FollowLeader {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        let Some(leader_position) = try_get_character_ref(ctx.leader, &ctx.scene.graph)
+            .filter(|leader| !leader.is_dead(&ctx.scene.graph))
+            .map(|leader| leader.position(&ctx.scene.graph))
+        else {
+            return Status::Failure;
+        };
+
+        let position = ctx.character.position(&ctx.scene.graph);
+        let distance = position.metric_distance(&leader_position);
+
+        if ctx.follow_teleport_distance > 0.0 && distance > ctx.follow_teleport_distance {
+            // The leader has outrun the bot. Move the bot closer to the leader along the line to
+            // its current position, the leader has most likely just walked that way.
+            let direction = (position - leader_position)
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_default();
+            let new_position = leader_position + direction.scale(ctx.follow_distance);
+            ctx.scene.graph[ctx.character.body]
+                .local_transform_mut()
+                .set_position(new_position);
+            ctx.agent.set_position(new_position);
+            ctx.character.stand_still(&mut ctx.scene.graph);
+            return Status::Running;
+        }
+
+        if !move_towards(ctx, Some(leader_position), ctx.follow_distance) {
+            let look_target = ctx.agent.steering_target().unwrap_or(leader_position);
+            turn_towards(
+                ctx.yaw,
+                look_target - position,
+                ctx.scene,
+                ctx.model,
+                ctx.character.body,
+                ctx.dt,
+                ctx.h_aim_angle_hack.to_radians(),
+            );
+        }
+
+        Status::Running
+    }
+}
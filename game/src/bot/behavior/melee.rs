@@ -1,7 +1,6 @@
 use crate::bot::behavior::BehaviorContext;
 use fyrox::{
     core::visitor::prelude::*,
-    rand::Rng,
     utils::behavior::{Behavior, Status},
 };
 
@@ -9,6 +8,38 @@ use fyrox::{
 pub struct DoMeleeAttack {
     attack_timeout: f32,
     attack_animation_index: u32,
+    #[visit(optional)]
+    max_distance: f32,
+}
+
+impl DoMeleeAttack {
+    pub fn new(max_distance: f32) -> Self {
+        Self {
+            max_distance,
+            ..Default::default()
+        }
+    }
+
+    /// Selects an attack animation that suits the distance to the target. Attack animations are
+    /// expected to be sorted by their reach: the first one is a quick swipe for point-blank range
+    /// and the last one is a lunge for the far edge of the melee range.
+    fn select_attack_animation(&self, ctx: &BehaviorContext) -> u32 {
+        let animation_count = ctx.state_machine.attack_animations.len();
+        if animation_count <= 1 || self.max_distance <= 0.0 {
+            return 0;
+        }
+
+        let Some(target) = ctx.target.as_ref() else {
+            return 0;
+        };
+
+        let distance = target
+            .position
+            .metric_distance(&ctx.scene.graph[ctx.character.body].global_position());
+        let reach = (distance / self.max_distance).clamp(0.0, 1.0);
+
+        ((reach * animation_count as f32) as usize).min(animation_count - 1) as u32
+    }
 }
 
 impl<'a> Behavior<'a> for DoMeleeAttack {
@@ -21,11 +52,11 @@ impl<'a> Behavior<'a> for DoMeleeAttack {
             } else if self.attack_timeout <= 0.0 {
                 ctx.need_to_melee_attack = true;
 
-                self.attack_animation_index = fyrox::core::rand::thread_rng()
-                    .gen_range(0..ctx.state_machine.attack_animations.len())
-                    as u32;
+                self.attack_animation_index = self.select_attack_animation(ctx);
             }
 
+            ctx.attack_animation_index = self.attack_animation_index as usize;
+
             self.attack_timeout -= ctx.dt;
 
             Status::Success
@@ -2,16 +2,24 @@ use crate::{
     bot::{
         behavior::{
             aim::{AimOnTarget, AimTarget},
+            block::Block,
+            cover::{NeedsCover, SeekCover},
             death::{IsDead, StayDead},
+            dodge::Dodge,
             find::FindTarget,
+            follow::FollowLeader,
             melee::{CanMeleeAttack, DoMeleeAttack},
             movement::MoveToTarget,
+            patrol::Patrol,
             range::IsTargetCloseBy,
+            reaction::ReactToTarget,
+            reinforcements::CallReinforcements,
+            search::SearchLastKnown,
             shoot::{CanShootTarget, ShootTarget},
             threat::{NeedsThreatenTarget, ThreatenTarget},
         },
         state_machine::StateMachine,
-        BotHostility, Target,
+        BotHostility, PatrolMode, Target,
     },
     character::Character,
     sound::SoundManager,
@@ -19,18 +27,27 @@ use crate::{
     MessageSender,
 };
 use fyrox::{
-    core::{math::SmoothAngle, pool::Handle, visitor::prelude::*},
+    core::{algebra::Vector3, math::SmoothAngle, pool::Handle, visitor::prelude::*},
+    fxhash::FxHashMap,
     scene::{node::Node, Scene},
     script::{PluginsRefMut, ScriptMessageSender},
     utils::{behavior::*, navmesh::NavmeshAgent},
 };
 
 pub mod aim;
+pub mod block;
+pub mod cover;
 pub mod death;
+pub mod dodge;
 pub mod find;
+pub mod follow;
 pub mod melee;
 pub mod movement;
+pub mod patrol;
 pub mod range;
+pub mod reaction;
+pub mod reinforcements;
+pub mod search;
 pub mod shoot;
 pub mod threat;
 
@@ -50,6 +67,15 @@ pub enum Action {
     ShootTarget(ShootTarget),
     NeedsThreatenTarget(NeedsThreatenTarget),
     ThreatenTarget(ThreatenTarget),
+    NeedsCover(NeedsCover),
+    SeekCover(SeekCover),
+    CallReinforcements(CallReinforcements),
+    Patrol(Patrol),
+    SearchLastKnown(SearchLastKnown),
+    Dodge(Dodge),
+    Block(Block),
+    FollowLeader(FollowLeader),
+    ReactToTarget(ReactToTarget),
 }
 
 impl<'a> Behavior<'a> for Action {
@@ -70,6 +96,15 @@ impl<'a> Behavior<'a> for Action {
             Action::CanShootTarget(v) => v.tick(context),
             Action::NeedsThreatenTarget(v) => v.tick(context),
             Action::ThreatenTarget(v) => v.tick(context),
+            Action::NeedsCover(v) => v.tick(context),
+            Action::SeekCover(v) => v.tick(context),
+            Action::CallReinforcements(v) => v.tick(context),
+            Action::Patrol(v) => v.tick(context),
+            Action::SearchLastKnown(v) => v.tick(context),
+            Action::Dodge(v) => v.tick(context),
+            Action::Block(v) => v.tick(context),
+            Action::FollowLeader(v) => v.tick(context),
+            Action::ReactToTarget(v) => v.tick(context),
         }
     }
 }
@@ -88,10 +123,28 @@ pub struct BehaviorContext<'a> {
     pub impact_handler: &'a BodyImpactHandler,
     pub model: Handle<Node>,
     pub restoration_time: f32,
+    pub is_under_fire: bool,
+    pub is_peeking_from_cover: &'a mut bool,
     pub v_recoil: &'a mut SmoothAngle,
     pub h_recoil: &'a mut SmoothAngle,
     pub move_speed: f32,
+    pub use_root_motion: bool,
+    pub fov_degrees: f32,
+    pub view_distance: f32,
+    pub heard_actor: Handle<Node>,
+    pub threat: &'a mut FxHashMap<Handle<Node>, f32>,
+    pub proximity_threat: f32,
+    pub threat_switch_margin: f32,
+    pub strafe_speed: f32,
+    pub strafe_direction_change_interval: f32,
     pub threaten_timeout: &'a mut f32,
+    pub threaten_cooldown_min: f32,
+    pub threaten_cooldown_max: f32,
+    pub reinforcement_spawn_point: Handle<Node>,
+    pub reinforcement_budget: &'a mut u32,
+    pub reinforcement_timer: &'a mut f32,
+    pub reinforcement_cooldown: f32,
+    pub reinforcement_call_probability: f32,
     pub sound_manager: &'a SoundManager,
     pub script_message_sender: &'a ScriptMessageSender,
     pub navmesh: Handle<Node>,
@@ -102,14 +155,44 @@ pub struct BehaviorContext<'a> {
     pub yaw: &'a mut SmoothAngle,
     pub pitch: &'a mut SmoothAngle,
     pub plugins: &'a PluginsRefMut<'a>,
+    pub patrol_waypoints: &'a [Handle<Node>],
+    pub patrol_mode: PatrolMode,
+    pub patrol_speed_factor: f32,
+    pub search_duration: f32,
+    pub search_timer: &'a mut f32,
+    pub last_known_position: &'a mut Option<Vector3<f32>>,
+    pub reaction_time: f32,
+    pub reaction_timer: &'a mut f32,
+    pub target_check_interval: f32,
+    pub target_check_timer: &'a mut f32,
+    pub base_accuracy: f32,
+    pub distance_accuracy_penalty: f32,
+    pub speed_accuracy_penalty: f32,
+    pub dodge_chance: f32,
+    pub dodge_cooldown: f32,
+    pub dodge_speed: f32,
+    pub dodge_duration: f32,
+    pub dodge_cooldown_timer: &'a mut f32,
+    pub block_chance: f32,
+    pub block_cooldown: f32,
+    pub block_duration: f32,
+    pub block_timer: &'a mut f32,
+    pub block_cooldown_timer: &'a mut f32,
+    pub leader: Handle<Node>,
+    pub follow_distance: f32,
+    pub follow_teleport_distance: f32,
 
     // Output
     pub attack_animation_index: usize,
     pub movement_speed_factor: f32,
     pub is_moving: bool,
+    pub is_strafing: bool,
     pub need_to_melee_attack: bool,
     pub is_aiming_weapon: bool,
     pub is_screaming: bool,
+    pub is_dodging: bool,
+    pub is_blocking: bool,
+    pub is_path_blocked: bool,
 }
 
 #[derive(Default, Debug, Visit, Clone)]
@@ -133,6 +216,19 @@ impl BotBehavior {
             bt,
         );
 
+        let reinforcements_call = leaf(Action::CallReinforcements(CallReinforcements), bt);
+
+        // Seek cover succeeds only when the bot has peeked out of the cover, so it can shoot.
+        let cover_seq = sequence(
+            [
+                leaf(Action::NeedsCover(NeedsCover), bt),
+                leaf(Action::SeekCover(SeekCover::default()), bt),
+                leaf(AimOnTarget::new_action(spine, AimTarget::ActualTarget), bt),
+                leaf(Action::ShootTarget(ShootTarget::default()), bt),
+            ],
+            bt,
+        );
+
         let shooting_distance = 4.0;
         let shoot_seq = sequence(
             [
@@ -159,7 +255,7 @@ impl BotBehavior {
                     ],
                     bt,
                 ),
-                leaf(Action::ShootTarget(ShootTarget), bt),
+                leaf(Action::ShootTarget(ShootTarget::default()), bt),
             ],
             bt,
         );
@@ -197,7 +293,10 @@ impl BotBehavior {
                     bt,
                 ),
                 leaf(Action::CanMeleeAttack(CanMeleeAttack), bt),
-                leaf(Action::DoMeleeAttack(DoMeleeAttack::default()), bt),
+                leaf(
+                    Action::DoMeleeAttack(DoMeleeAttack::new(close_combat_distance)),
+                    bt,
+                ),
             ],
             bt,
         );
@@ -208,10 +307,39 @@ impl BotBehavior {
                 sequence(
                     [
                         leaf(Action::FindTarget(FindTarget::default()), bt),
-                        sequence([selector([threaten_seq, shoot_seq, melee_seq], bt)], bt),
+                        leaf(Action::ReactToTarget(ReactToTarget), bt),
+                        sequence(
+                            [selector(
+                                [
+                                    leaf(Action::Dodge(Dodge::default()), bt),
+                                    leaf(
+                                        Action::Block(Block {
+                                            max_distance: close_combat_distance,
+                                        }),
+                                        bt,
+                                    ),
+                                    threaten_seq,
+                                    reinforcements_call,
+                                    cover_seq,
+                                    shoot_seq,
+                                    melee_seq,
+                                ],
+                                bt,
+                            )],
+                            bt,
+                        ),
                     ],
                     bt,
                 ),
+                // Escorts return to their leader when there are no enemies around.
+                leaf(Action::FollowLeader(FollowLeader), bt),
+                // Bots that have lost their target are searching for it for a while.
+                leaf(
+                    Action::SearchLastKnown(SearchLastKnown { min_distance: 1.0 }),
+                    bt,
+                ),
+                // Bots without a target are walking along their patrol routes (if any).
+                leaf(Action::Patrol(Patrol::default()), bt),
             ],
             bt,
         );
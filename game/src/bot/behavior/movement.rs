@@ -1,5 +1,10 @@
 use crate::level::hit_box::HitBox;
-use crate::{bot::behavior::BehaviorContext, door::door_mut, utils::BodyImpactHandler, Game};
+use crate::{
+    bot::behavior::BehaviorContext,
+    door::{door_mut, Door},
+    utils::BodyImpactHandler,
+    Game,
+};
 use fyrox::core::pool::Handle;
 use fyrox::fxhash::FxHashSet;
 use fyrox::graph::BaseSceneGraph;
@@ -15,22 +20,40 @@ pub struct MoveToTarget {
     pub min_distance: f32,
 }
 
-impl MoveToTarget {
-    fn check_obstacles(&self, self_position: Vector3<f32>, ctx: &mut BehaviorContext) {
-        let doors = &ctx
-            .plugins
-            .get::<Game>()
-            .level
-            .as_ref()
-            .expect("Level must exist!")
-            .doors_container
-            .doors;
-        for &door in doors {
-            let door = door_mut(door, &mut ctx.scene.graph);
-            let close_enough = self_position.metric_distance(&door.initial_position()) < 1.25;
-            if close_enough {
-                door.try_open(Some(&ctx.character.inventory));
-            }
+/// Checks whether the given point is on the navmesh. `tolerance` defines max horizontal distance
+/// between the point and the navmesh, larger distances mean that the point is outside of the
+/// navmesh (a ledge or a wall).
+pub fn is_walkable(ctx: &BehaviorContext, point: Vector3<f32>, tolerance: f32) -> bool {
+    let Some(navmesh) = ctx
+        .scene
+        .graph
+        .try_get_of_type::<NavigationalMesh>(ctx.navmesh)
+    else {
+        return false;
+    };
+
+    navmesh
+        .navmesh_ref()
+        .query_closest(point)
+        .is_some_and(|(closest, _)| {
+            Vector3::new(closest.x - point.x, 0.0, closest.z - point.z).norm() <= tolerance
+        })
+}
+
+fn check_obstacles(self_position: Vector3<f32>, ctx: &mut BehaviorContext) {
+    let doors = &ctx
+        .plugins
+        .get::<Game>()
+        .level
+        .as_ref()
+        .expect("Level must exist!")
+        .doors_container
+        .doors;
+    for &door in doors {
+        let door = door_mut(door, &mut ctx.scene.graph);
+        let close_enough = self_position.metric_distance(&door.initial_position()) < 1.25;
+        if close_enough {
+            door.try_open(Some(&ctx.character.inventory));
         }
     }
 }
@@ -61,62 +84,116 @@ impl<'a> Behavior<'a> for MoveToTarget {
     type Context = BehaviorContext<'a>;
 
     fn tick(&mut self, ctx: &mut Self::Context) -> Status {
-        ctx.movement_speed_factor = calculate_movement_speed_factor(
-            &ctx.character.hit_boxes,
-            ctx.impact_handler,
-            ctx.scene,
-        );
-
-        let transform = &ctx.scene.graph[ctx.model].global_transform();
-
-        let delta_position = ctx
-            .state_machine
-            .lower_body_layer(&ctx.scene.graph)
-            .and_then(|layer| layer.pose().root_motion().map(|rm| rm.delta_position));
-
-        let multiborrow_context = ctx.scene.graph.begin_multi_borrow();
-
-        let mut body_ref = multiborrow_context.try_get_mut(ctx.character.body).unwrap();
-        let body = body_ref.as_rigid_body_mut();
-        let position = body.global_position();
-
-        ctx.agent.set_speed(ctx.move_speed);
-        if let Ok(navmesh) =
-            multiborrow_context.try_get_component_of_type::<NavigationalMesh>(ctx.navmesh)
-        {
-            ctx.agent.set_position(position);
-
-            if let Some(target) = ctx.target.as_ref() {
-                ctx.agent.set_target(target.position);
-                let _ = ctx.agent.update(ctx.dt, &navmesh.navmesh_ref());
-            }
+        let destination = ctx.target.as_ref().map(|t| t.position);
+        if move_towards(ctx, destination, self.min_distance) {
+            Status::Success
+        } else if ctx.is_path_blocked {
+            // There's no way to the target, let the bot do something else.
+            Status::Failure
+        } else {
+            Status::Running
         }
+    }
+}
 
-        let has_reached_destination =
-            ctx.agent.target().metric_distance(&position) <= self.min_distance;
-
-        if has_reached_destination {
-            body.set_lin_vel(Vector3::new(0.0, body.lin_vel().y, 0.0));
-        } else if let Some(delta_position) = delta_position {
+/// Moves the bot along a path on the navmesh towards the given destination. If the destination is
+/// not set, the bot will move to the previous destination of its navmesh agent. Returns `true` if
+/// the destination was reached. If the path goes through a locked door, that the bot cannot open,
+/// the bot stays in place and [`BehaviorContext::is_path_blocked`] is set.
+pub fn move_towards(
+    ctx: &mut BehaviorContext,
+    destination: Option<Vector3<f32>>,
+    min_distance: f32,
+) -> bool {
+    ctx.movement_speed_factor =
+        calculate_movement_speed_factor(&ctx.character.hit_boxes, ctx.impact_handler, ctx.scene)
+            * ctx.character.status_effects.movement_speed_factor();
+
+    let transform = &ctx.scene.graph[ctx.model].global_transform();
+
+    // Horizontal velocity from the root motion of the locomotion animation.
+    let root_motion_velocity = ctx
+        .state_machine
+        .lower_body_layer(&ctx.scene.graph)
+        .and_then(|layer| layer.pose().root_motion().map(|rm| rm.delta_position))
+        .filter(|_| ctx.use_root_motion && ctx.dt > 0.0)
+        .map(|delta_position| {
             let velocity = transform
                 .transform_vector(&delta_position)
                 .scale(1.0 / ctx.dt);
-
-            let velocity = Vector3::new(velocity.x, body.lin_vel().y, velocity.z);
-            body.set_lin_vel(velocity);
+            Vector3::new(velocity.x, 0.0, velocity.z)
+        });
+
+    // Locked doors, that could not be opened by the bot, make their doorways impassable.
+    let level = ctx
+        .plugins
+        .get::<Game>()
+        .level
+        .as_ref()
+        .expect("Level must exist!");
+    let impassable_doors = level
+        .doors_container
+        .doors
+        .iter()
+        .filter(|door| {
+            ctx.scene
+                .graph
+                .try_get_script_of::<Door>(**door)
+                .is_some_and(|door| !door.can_be_opened_with(&ctx.character.inventory))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let multiborrow_context = ctx.scene.graph.begin_multi_borrow();
+
+    let mut body_ref = multiborrow_context.try_get_mut(ctx.character.body).unwrap();
+    let body = body_ref.as_rigid_body_mut();
+    let position = body.global_position();
+
+    // The agent follows the animation when root motion is used, otherwise it moves the bot with a
+    // fixed speed.
+    ctx.agent.set_speed(match root_motion_velocity {
+        Some(velocity) => velocity.norm(),
+        None => ctx.move_speed * ctx.movement_speed_factor,
+    });
+    if let Ok(navmesh) =
+        multiborrow_context.try_get_component_of_type::<NavigationalMesh>(ctx.navmesh)
+    {
+        ctx.agent.set_position(position);
+
+        if let Some(destination) = destination {
+            ctx.agent.set_target(destination);
         }
+        let _ = ctx.agent.update(ctx.dt, &navmesh.navmesh_ref());
+    }
 
-        drop(body_ref);
-        drop(multiborrow_context);
+    // The agent is not aware of the blocked regions, so check its path every time. The path is
+    // rebuilt by the agent periodically, so the bot continues to move once the door is unlocked.
+    ctx.is_path_blocked = level
+        .navmesh_blockers
+        .is_path_blocked(ctx.agent.path(), |owner| impassable_doors.contains(&owner));
 
-        self.check_obstacles(position, ctx);
+    let has_reached_destination = ctx.agent.target().metric_distance(&position) <= min_distance;
 
-        if has_reached_destination {
-            ctx.is_moving = false;
-            Status::Success
-        } else {
-            ctx.is_moving = true;
-            Status::Running
-        }
-    }
+    let velocity = if has_reached_destination || ctx.is_path_blocked {
+        Vector3::default()
+    } else if let Some(velocity) = root_motion_velocity {
+        velocity
+    } else if ctx.dt > 0.0 {
+        // Follow the agent, it has moved along the path already.
+        let offset = ctx.agent.position() - position;
+        Vector3::new(offset.x, 0.0, offset.z).scale(1.0 / ctx.dt)
+    } else {
+        Vector3::default()
+    };
+    body.set_lin_vel(Vector3::new(velocity.x, body.lin_vel().y, velocity.z));
+
+    drop(body_ref);
+    drop(multiborrow_context);
+
+    check_obstacles(position, ctx);
+
+    ctx.is_moving = !has_reached_destination && !ctx.is_path_blocked;
+
+    has_reached_destination
 }
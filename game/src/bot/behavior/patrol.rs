@@ -0,0 +1,123 @@
+use crate::bot::{
+    behavior::{aim::turn_towards, movement::move_towards, BehaviorContext},
+    PatrolMode,
+};
+use fyrox::{
+    core::{algebra::Vector3, visitor::prelude::*},
+    graph::BaseSceneGraph,
+    utils::behavior::{Behavior, Status},
+};
+
+/// Distance (in meters) at which a waypoint is considered reached.
+const WAYPOINT_REACH_DISTANCE: f32 = 0.5;
+
+#[derive(Debug, PartialEq, Visit, Clone)]
+pub struct Patrol {
+    waypoint_index: usize,
+    forward: bool,
+    // Elapsed time of the previous tick, used to detect whether the patrol was interrupted.
+    last_tick_time: f32,
+}
+
+impl Default for Patrol {
+    fn default() -> Self {
+        Self {
+            waypoint_index: 0,
+            forward: true,
+            last_tick_time: f32::NEG_INFINITY,
+        }
+    }
+}
+
+impl Patrol {
+    fn nearest_waypoint(&self, ctx: &BehaviorContext, position: Vector3<f32>) -> Option<usize> {
+        ctx.patrol_waypoints
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &waypoint)| {
+                ctx.scene
+                    .graph
+                    .try_get(waypoint)
+                    .map(|node| (i, node.global_position().metric_distance(&position)))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    fn advance(&mut self, count: usize, mode: PatrolMode) {
+        if count < 2 {
+            self.waypoint_index = 0;
+            return;
+        }
+
+        match mode {
+            PatrolMode::Loop => {
+                self.waypoint_index = (self.waypoint_index + 1) % count;
+            }
+            PatrolMode::PingPong => {
+                if self.forward && self.waypoint_index + 1 >= count {
+                    self.forward = false;
+                } else if !self.forward && self.waypoint_index == 0 {
+                    self.forward = true;
+                }
+
+                if self.forward {
+                    self.waypoint_index += 1;
+                } else {
+                    self.waypoint_index -= 1;
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Behavior<'a> for Patrol {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        if ctx.patrol_waypoints.is_empty() {
+            return Status::Failure;
+        }
+
+        let position = ctx.character.position(&ctx.scene.graph);
+
+        // The patrol was interrupted (by a fight, for example), continue from the closest waypoint
+        // instead of walking back to the one that was visited before the interruption.
+        let was_interrupted = ctx.elapsed_time - self.last_tick_time > ctx.dt * 2.0;
+        self.last_tick_time = ctx.elapsed_time;
+        if was_interrupted || self.waypoint_index >= ctx.patrol_waypoints.len() {
+            match self.nearest_waypoint(ctx, position) {
+                Some(index) => self.waypoint_index = index,
+                None => return Status::Failure,
+            }
+        }
+
+        let Some(destination) = ctx
+            .scene
+            .graph
+            .try_get(ctx.patrol_waypoints[self.waypoint_index])
+            .map(|node| node.global_position())
+        else {
+            return Status::Failure;
+        };
+
+        if move_towards(ctx, Some(destination), WAYPOINT_REACH_DISTANCE) {
+            self.advance(ctx.patrol_waypoints.len(), ctx.patrol_mode);
+        } else {
+            ctx.movement_speed_factor *= ctx.patrol_speed_factor;
+
+            let look_target = ctx.agent.steering_target().unwrap_or(destination);
+            turn_towards(
+                ctx.yaw,
+                look_target - position,
+                ctx.scene,
+                ctx.model,
+                ctx.character.body,
+                ctx.dt,
+                ctx.h_aim_angle_hack.to_radians(),
+            );
+        }
+
+        Status::Running
+    }
+}
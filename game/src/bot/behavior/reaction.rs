@@ -0,0 +1,40 @@
+//! Bots are not reacting instantly on a newly spotted target, instead they freeze for a moment and
+//! only then start to fight.
+
+use crate::bot::behavior::{aim::turn_towards, BehaviorContext};
+use fyrox::{
+    core::visitor::prelude::*,
+    utils::behavior::{Behavior, Status},
+};
+
+#[derive(Default, Debug, PartialEq, Visit, Eq, Clone)]
+pub struct ReactToTarget;
+
+impl<'a> Behavior<'a> for ReactToTarget {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        if *ctx.reaction_timer <= 0.0 {
+            return Status::Success;
+        }
+
+        let Some(target) = ctx.target.as_ref() else {
+            return Status::Failure;
+        };
+
+        // Stay in place and turn to face the target while the bot is startled.
+        ctx.character.stand_still(&mut ctx.scene.graph);
+        let position = ctx.character.position(&ctx.scene.graph);
+        turn_towards(
+            ctx.yaw,
+            target.position - position,
+            ctx.scene,
+            ctx.model,
+            ctx.character.body,
+            ctx.dt,
+            ctx.h_aim_angle_hack.to_radians(),
+        );
+
+        Status::Running
+    }
+}
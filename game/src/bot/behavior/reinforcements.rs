@@ -0,0 +1,55 @@
+//! Some bots can call reinforcements when they see their target, this mod has behavior nodes for
+//! this.
+
+use crate::{
+    bot::behavior::BehaviorContext, character::try_get_character_ref,
+    level::spawn::SpawnPointMessage, utils,
+};
+use fyrox::{
+    core::visitor::prelude::*,
+    graph::BaseSceneGraph,
+    utils::behavior::{Behavior, Status},
+};
+
+#[derive(Default, Debug, PartialEq, Visit, Eq, Clone)]
+pub struct CallReinforcements;
+
+impl<'a> Behavior<'a> for CallReinforcements {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        if *ctx.reinforcement_budget == 0
+            || *ctx.reinforcement_timer > 0.0
+            || ctx
+                .scene
+                .graph
+                .try_get(ctx.reinforcement_spawn_point)
+                .is_none()
+        {
+            return Status::Failure;
+        }
+
+        // Reinforcements are called only when an actual enemy was spotted.
+        let spotted_enemy = ctx
+            .target
+            .as_ref()
+            .is_some_and(|target| try_get_character_ref(target.handle, &ctx.scene.graph).is_some());
+        if !spotted_enemy {
+            return Status::Failure;
+        }
+
+        // Do not call reinforcements on every engagement.
+        *ctx.reinforcement_timer = ctx.reinforcement_cooldown;
+        if !utils::is_probability_event_occurred(ctx.reinforcement_call_probability) {
+            return Status::Failure;
+        }
+
+        *ctx.reinforcement_budget -= 1;
+        ctx.is_screaming = true;
+
+        ctx.script_message_sender
+            .send_to_target(ctx.reinforcement_spawn_point, SpawnPointMessage::Spawn);
+
+        Status::Success
+    }
+}
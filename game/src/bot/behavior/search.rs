@@ -0,0 +1,47 @@
+use crate::bot::behavior::{aim::turn_towards, movement::move_towards, BehaviorContext};
+use fyrox::{
+    core::visitor::prelude::*,
+    utils::behavior::{Behavior, Status},
+};
+
+/// Makes the bot to investigate the position at which its target was seen the last time. The bot
+/// gives up when the search timer runs out.
+#[derive(Default, Debug, PartialEq, Visit, Clone)]
+pub struct SearchLastKnown {
+    pub min_distance: f32,
+}
+
+impl<'a> Behavior<'a> for SearchLastKnown {
+    type Context = BehaviorContext<'a>;
+
+    fn tick(&mut self, ctx: &mut Self::Context) -> Status {
+        let Some(last_known_position) = *ctx.last_known_position else {
+            return Status::Failure;
+        };
+
+        *ctx.search_timer -= ctx.dt;
+        if *ctx.search_timer <= 0.0 {
+            *ctx.search_timer = 0.0;
+            *ctx.last_known_position = None;
+            ctx.character.stand_still(&mut ctx.scene.graph);
+            return Status::Failure;
+        }
+
+        if !move_towards(ctx, Some(last_known_position), self.min_distance) {
+            let position = ctx.character.position(&ctx.scene.graph);
+            let look_target = ctx.agent.steering_target().unwrap_or(last_known_position);
+            turn_towards(
+                ctx.yaw,
+                look_target - position,
+                ctx.scene,
+                ctx.model,
+                ctx.character.body,
+                ctx.dt,
+                ctx.h_aim_angle_hack.to_radians(),
+            );
+        }
+
+        // Keep looking around at the spot until the timer runs out.
+        Status::Running
+    }
+}
@@ -1,22 +1,125 @@
 use crate::{
-    bot::behavior::BehaviorContext,
-    character::{CharacterMessage, CharacterMessageData},
+    bot::behavior::{movement::is_walkable, BehaviorContext},
+    character::{try_get_character_ref, CharacterMessage, CharacterMessageData},
     level::hit_box::LimbType,
-    weapon::{weapon_ref, Weapon, WeaponMessage, WeaponMessageData},
+    utils,
+    weapon::{weapon_mut, weapon_ref, Weapon, WeaponMessage, WeaponMessageData},
 };
 use fyrox::{
-    core::{some_or_return, visitor::prelude::*},
+    core::{algebra::Vector3, some_or_return, visitor::prelude::*},
     graph::BaseSceneGraph,
+    scene::rigidbody::RigidBody,
     utils::behavior::{Behavior, Status},
 };
 
-#[derive(Default, Debug, PartialEq, Visit, Eq, Clone)]
-pub struct ShootTarget;
+/// How far ahead (in meters) the navmesh will be checked when strafing.
+const STRAFE_PROBE_DISTANCE: f32 = 0.75;
+/// Max horizontal distance between a probe point and the navmesh. Larger distances mean that the
+/// probe point is outside of the navmesh (a ledge or a wall).
+const STRAFE_NAVMESH_TOLERANCE: f32 = 0.2;
+/// Max deviation (in degrees) of shots of a bot with zero base accuracy.
+const MAX_BASE_INACCURACY: f32 = 10.0;
+/// Max deviation (in degrees) of shots of a bot, regardless of distance and speed of the target.
+const MAX_INACCURACY: f32 = 30.0;
+
+#[derive(Default, Debug, PartialEq, Visit, Clone)]
+pub struct ShootTarget {
+    strafe_timer: f32,
+    strafe_left: bool,
+}
+
+impl ShootTarget {
+    /// Calculates the direction of a shot with deliberate inaccuracy, so bots miss plausibly. The
+    /// deviation grows with the distance to the target and its lateral speed.
+    fn shot_direction(context: &BehaviorContext, weapon: &Weapon) -> Vector3<f32> {
+        let graph = &context.scene.graph;
+        let shot_position = weapon.shot_position(graph);
+        let direction = weapon.shot_direction(graph);
+
+        let (distance, lateral_speed) = context.target.as_ref().map_or((0.0, 0.0), |target| {
+            let to_target = target.position - shot_position;
+            let lateral_speed = try_get_character_ref(target.handle, graph)
+                .and_then(|character| graph.try_get_of_type::<RigidBody>(character.body))
+                .and_then(|body| {
+                    let line_of_fire = to_target.try_normalize(f32::EPSILON)?;
+                    let velocity = body.lin_vel();
+                    Some((velocity - line_of_fire.scale(velocity.dot(&line_of_fire))).norm())
+                })
+                .unwrap_or_default();
+            (to_target.norm(), lateral_speed)
+        });
+
+        let inaccuracy = ((1.0 - context.base_accuracy.clamp(0.0, 1.0)) * MAX_BASE_INACCURACY
+            + distance * context.distance_accuracy_penalty
+            + lateral_speed * context.speed_accuracy_penalty)
+            .min(MAX_INACCURACY);
+
+        utils::random_direction_in_cone(direction, inaccuracy.to_radians())
+    }
+
+    /// Side-steps around the target, the direction is flipped periodically or when there's no
+    /// navmesh in the current direction.
+    fn strafe(&mut self, context: &mut BehaviorContext) {
+        if context.strafe_speed <= 0.0 {
+            return;
+        }
+
+        let Some(target_position) = context.target.as_ref().map(|t| t.position) else {
+            return;
+        };
+
+        self.strafe_timer -= context.dt;
+        if self.strafe_timer <= 0.0 {
+            self.strafe_left = !self.strafe_left;
+            self.strafe_timer = context.strafe_direction_change_interval;
+        }
+
+        let self_position = context.character.position(&context.scene.graph);
+        let Some(to_target) = Vector3::new(
+            target_position.x - self_position.x,
+            0.0,
+            target_position.z - self_position.z,
+        )
+        .try_normalize(f32::EPSILON) else {
+            return;
+        };
+
+        let mut side = Vector3::y().cross(&to_target);
+        if !self.strafe_left {
+            side = -side;
+        }
+
+        if !is_walkable(
+            context,
+            self_position + side.scale(STRAFE_PROBE_DISTANCE),
+            STRAFE_NAVMESH_TOLERANCE,
+        ) {
+            // Try the other side on the next frame.
+            self.strafe_left = !self.strafe_left;
+            self.strafe_timer = context.strafe_direction_change_interval;
+            return;
+        }
+
+        if let Some(body) = context
+            .scene
+            .graph
+            .try_get_mut_of_type::<RigidBody>(context.character.body)
+        {
+            let velocity = side.scale(context.strafe_speed);
+            body.set_lin_vel(Vector3::new(velocity.x, body.lin_vel().y, velocity.z));
+
+            context.is_moving = true;
+            context.is_strafing = true;
+        }
+    }
+}
 
 impl<'a> Behavior<'a> for ShootTarget {
     type Context = BehaviorContext<'a>;
 
     fn tick(&mut self, context: &mut Self::Context) -> Status {
+        self.strafe(context);
+
         if let Some(weapon) = context
             .character
             .weapons
@@ -26,38 +129,37 @@ impl<'a> Behavior<'a> for ShootTarget {
 
             context.is_aiming_weapon = true;
 
+            let weapon = weapon_mut(weapon_handle, &mut context.scene.graph);
+            if weapon.is_magazine_empty()
+                && !weapon.is_reloading()
+                && !weapon.reload(&context.character.inventory)
+            {
+                // Fallback to melee.
+                return Status::Failure;
+            }
+
             let weapon = weapon_ref(weapon_handle, &context.scene.graph);
             if weapon.can_shoot(context.elapsed_time)
                 && context.state_machine.is_in_aim_state(&context.scene.graph)
             {
-                let ammo_per_shot = *weapon.ammo_consumption_per_shot;
-
-                if let Some(ammo_item) = weapon.ammo_item.as_ref() {
-                    if context
-                        .character
-                        .inventory
-                        .try_extract_exact_items(ammo_item, ammo_per_shot)
-                        == ammo_per_shot
-                    {
-                        context.v_recoil.set_target(weapon.gen_v_recoil_angle());
-                        context.h_recoil.set_target(weapon.gen_h_recoil_angle());
-
-                        context.script_message_sender.send_to_target(
-                            weapon_handle,
-                            WeaponMessage {
-                                weapon: weapon_handle,
-                                data: WeaponMessageData::Shoot {
-                                    direction: Default::default(),
-                                },
-                            },
-                        );
-
-                        return Status::Success;
-                    } else {
-                        // Fallback to melee.
-                        return Status::Failure;
-                    }
-                }
+                context.v_recoil.set_target(weapon.gen_v_recoil_angle());
+                context.h_recoil.set_target(weapon.gen_h_recoil_angle());
+
+                let direction = Self::shot_direction(context, weapon);
+
+                context.script_message_sender.send_to_target(
+                    weapon_handle,
+                    WeaponMessage {
+                        weapon: weapon_handle,
+                        data: WeaponMessageData::Shoot {
+                            direction: Some(direction),
+                            // The inaccuracy of the bot is already applied to the direction.
+                            spread_factor: 0.0,
+                        },
+                    },
+                );
+
+                return Status::Success;
             }
         }
         Status::Running
@@ -107,15 +209,11 @@ impl<'a> Behavior<'a> for CanShootTarget {
 
         let weapon_script =
             some_or_return!(weapon_node.try_get_script::<Weapon>(), Status::Failure);
-        let ammo_per_shot = *weapon_script.ammo_consumption_per_shot;
-        if let Some(ammo_item) = weapon_script.ammo_item.as_ref() {
-            if context.restoration_time <= 0.0
-                && context.character.inventory.item_count(ammo_item) >= ammo_per_shot
-            {
-                Status::Success
-            } else {
-                Status::Failure
-            }
+        if context.restoration_time <= 0.0
+            && (!weapon_script.is_magazine_empty()
+                || weapon_script.reserve_rounds(&context.character.inventory) > 0)
+        {
+            Status::Success
         } else {
             Status::Failure
         }
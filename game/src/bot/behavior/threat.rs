@@ -1,6 +1,9 @@
 //! Bots can threaten the player before attack, this mod has behavior nodes for this.
 
-use crate::{bot::behavior::BehaviorContext, utils};
+use crate::{
+    bot::behavior::{aim::turn_towards, BehaviorContext},
+    utils,
+};
 use fyrox::{
     core::{rand::Rng, visitor::prelude::*},
     rand::{self},
@@ -12,6 +15,27 @@ pub struct ThreatenTarget {
     in_progress: bool,
 }
 
+impl ThreatenTarget {
+    /// Keeps the bot facing its target, so the threaten is always directed at the target.
+    fn track_target(ctx: &mut BehaviorContext) {
+        let Some(target_position) = ctx.target.as_ref().map(|t| t.position) else {
+            return;
+        };
+
+        let look_dir = target_position - ctx.character.position(&ctx.scene.graph);
+
+        turn_towards(
+            ctx.yaw,
+            look_dir,
+            ctx.scene,
+            ctx.model,
+            ctx.character.body,
+            ctx.dt,
+            ctx.h_aim_angle_hack.to_radians(),
+        );
+    }
+}
+
 impl<'a> Behavior<'a> for ThreatenTarget {
     type Context = BehaviorContext<'a>;
 
@@ -24,13 +48,16 @@ impl<'a> Behavior<'a> for ThreatenTarget {
 
                 self.in_progress = true;
                 ctx.character.stand_still(&mut ctx.scene.graph);
+                Self::track_target(ctx);
                 Status::Running
             } else if self.in_progress {
                 self.in_progress = false;
-                *ctx.threaten_timeout = rand::thread_rng().gen_range(20.0..60.0);
+                *ctx.threaten_timeout = rand::thread_rng()
+                    .gen_range(ctx.threaten_cooldown_min..=ctx.threaten_cooldown_max);
                 Status::Success
             } else {
                 ctx.is_screaming = true;
+                Self::track_target(ctx);
                 Status::Running
             }
         } else {
@@ -0,0 +1,176 @@
+//! Bosses are bots with discrete phases. Every phase begins when the health of the boss drops
+//! below a threshold and changes the behavior of the bot (makes it faster, gives it new abilities,
+//! summons adds, etc.). The script must be attached to the same node as the [`Bot`] script.
+
+use crate::{bot::Bot, level::spawn::SpawnPointMessage};
+use fyrox::{
+    core::{
+        pool::Handle, reflect::prelude::*, some_or_return, stub_uuid_provider,
+        type_traits::prelude::*, visitor::prelude::*,
+    },
+    graph::BaseSceneGraph,
+    scene::{
+        animation::{absm::prelude::*, prelude::*},
+        graph::Graph,
+        node::Node,
+    },
+    script::{ScriptContext, ScriptTrait},
+};
+
+/// Settings of a single boss phase.
+#[derive(Debug, Clone, Visit, Reflect)]
+pub struct BossPhase {
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Fraction of the max health of the boss at which the phase begins. Phases \
+    must be sorted by this value in descending order."
+    )]
+    pub health_threshold: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Multiplier for the initial walk speed of the bot."
+    )]
+    pub walk_speed_factor: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Playback speed of melee attack animations, higher values mean faster \
+    attacks."
+    )]
+    pub attack_speed: f32,
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub block_chance: f32,
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub dodge_chance: f32,
+    #[reflect(description = "Spawn points that will spawn adds when the phase begins.")]
+    pub summon_spawn_points: Vec<Handle<Node>>,
+}
+
+impl Default for BossPhase {
+    fn default() -> Self {
+        Self {
+            health_threshold: 0.5,
+            walk_speed_factor: 1.0,
+            attack_speed: 1.0,
+            block_chance: 0.0,
+            dodge_chance: 0.0,
+            summon_spawn_points: Default::default(),
+        }
+    }
+}
+
+stub_uuid_provider!(BossPhase);
+
+/// A global event that is sent when a boss enters a new phase.
+#[derive(Debug, Clone)]
+pub struct BossPhaseEvent {
+    pub boss: Handle<Node>,
+    /// Index of the phase in the list of phases of the boss.
+    pub phase: usize,
+}
+
+#[derive(Visit, Reflect, Default, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "8f0e5a0c-7d5e-4b7c-a2c1-52a3f1d6e4b9")]
+#[visit(optional)]
+pub struct Boss {
+    #[reflect(
+        description = "An ordered list of phases of the boss. Initial phase is defined by \
+    the settings of the bot itself."
+    )]
+    phases: Vec<BossPhase>,
+    // Amount of phases the boss went through.
+    #[reflect(hidden)]
+    passed_phases: u32,
+    #[reflect(hidden)]
+    max_health: f32,
+    #[reflect(hidden)]
+    base_walk_speed: f32,
+}
+
+impl Boss {
+    /// Index of the current phase, `None` means that the boss is in its initial phase.
+    pub fn current_phase(&self) -> Option<usize> {
+        (self.passed_phases as usize).checked_sub(1)
+    }
+
+    fn set_attack_speed(
+        graph: &mut Graph,
+        absm: Handle<Node>,
+        attack_animations: &[Handle<Animation>],
+        speed: f32,
+    ) {
+        let animation_player = some_or_return!(graph
+            .try_get_of_type::<AnimationBlendingStateMachine>(absm)
+            .map(|absm| absm.animation_player()));
+        let animation_player =
+            some_or_return!(graph.try_get_mut_of_type::<AnimationPlayer>(animation_player));
+        let animations = animation_player.animations_mut().get_value_mut_silent();
+        for attack_animation in attack_animations {
+            if let Some(animation) = animations.try_get_mut(*attack_animation) {
+                animation.set_speed(speed);
+            }
+        }
+    }
+
+    fn enter_phase(&self, index: usize, ctx: &mut ScriptContext) {
+        let phase = &self.phases[index];
+
+        let bot =
+            some_or_return!(ctx.scene.graph[ctx.handle].try_get_script_component_mut::<Bot>());
+        bot.walk_speed = self.base_walk_speed * phase.walk_speed_factor;
+        bot.block_chance = phase.block_chance;
+        bot.dodge_chance = phase.dodge_chance;
+        let absm = bot.state_machine.absm;
+        let attack_animations = bot.state_machine.attack_animations.clone();
+
+        Self::set_attack_speed(
+            &mut ctx.scene.graph,
+            absm,
+            &attack_animations,
+            phase.attack_speed,
+        );
+
+        for spawn_point in phase.summon_spawn_points.iter() {
+            ctx.message_sender
+                .send_to_target(*spawn_point, SpawnPointMessage::Spawn);
+        }
+
+        ctx.message_sender.send_global(BossPhaseEvent {
+            boss: ctx.handle,
+            phase: index,
+        });
+    }
+}
+
+impl ScriptTrait for Boss {
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        let graph = &ctx.scene.graph;
+        let bot = some_or_return!(graph[ctx.handle].try_get_script_component::<Bot>());
+        if bot.is_dead(graph) {
+            return;
+        }
+
+        let health = bot.combined_health(graph);
+        if self.max_health <= 0.0 {
+            self.max_health = health;
+            self.base_walk_speed = bot.walk_speed;
+        }
+        if self.max_health <= 0.0 {
+            return;
+        }
+
+        let health_fraction = health / self.max_health;
+
+        // A single heavy hit could skip a few phases at once, every one of them must be entered
+        // anyway so all the adds are summoned.
+        while let Some(phase) = self.phases.get(self.passed_phases as usize) {
+            if health_fraction > phase.health_threshold {
+                break;
+            }
+
+            let index = self.passed_phases as usize;
+            self.passed_phases += 1;
+            self.enter_phase(index, ctx);
+        }
+    }
+}
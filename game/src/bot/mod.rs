@@ -1,15 +1,20 @@
 use crate::level::hit_box::HitBoxDamage;
 use crate::{
     bot::{
-        behavior::{BehaviorContext, BotBehavior},
+        behavior::{find::VisionCone, BehaviorContext, BotBehavior},
         state_machine::{StateMachine, StateMachineInput},
     },
-    character::{Character, CharacterMessage, CharacterMessageData},
+    character::{Character, CharacterMessage, CharacterMessageData, MeleeAttackContext},
+    difficulty::Difficulty,
     door::{door_mut, door_ref, DoorContainer},
     level::{
+        alarm::AlarmEvent,
+        decal::Decal,
         hit_box::LimbType,
-        hit_box::{HitBox, HitBoxMessage},
+        hit_box::{HitBox, HitBoxMessage, KillEvent},
+        item::Item,
     },
+    message::Message,
     sound::SoundManager,
     utils::{self, BodyImpactHandler},
     weapon::Weapon,
@@ -32,14 +37,20 @@ use fyrox::{
         visitor::{Visit, VisitResult, Visitor},
         TypeUuidProvider,
     },
-    graph::SceneGraph,
+    fxhash::FxHashMap,
+    graph::{BaseSceneGraph, SceneGraph},
+    material::{MaterialProperty, MaterialResourceExtension},
     resource::model::{ModelResource, ModelResourceExtension},
     scene::sound::Sound,
     scene::{
         self,
         animation::{absm::prelude::*, prelude::*},
         debug::SceneDrawingContext,
-        graph::physics::{Intersection, RayCastOptions},
+        graph::{
+            physics::{Intersection, RayCastOptions},
+            Graph,
+        },
+        mesh::{Mesh, RenderPath},
         node::Node,
         ragdoll::Ragdoll,
         rigidbody::RigidBody,
@@ -55,6 +66,7 @@ use std::ops::{Deref, DerefMut};
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 mod behavior;
+pub mod boss;
 mod state_machine;
 
 #[derive(
@@ -80,6 +92,32 @@ pub enum BotHostility {
     Player = 2,
 }
 
+#[derive(
+    Deserialize,
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Ord,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+#[repr(u32)]
+pub enum PatrolMode {
+    /// Goes back to the first waypoint after reaching the last one.
+    #[default]
+    Loop = 0,
+    /// Walks the route in reverse order after reaching the last waypoint.
+    PingPong = 1,
+}
+
 #[derive(
     Deserialize,
     Copy,
@@ -103,6 +141,29 @@ pub enum MovementType {
 }
 
 stub_uuid_provider!(BotHostility);
+stub_uuid_provider!(PatrolMode);
+
+/// An item that could be dropped by a bot on death with some probability.
+#[derive(Default, Debug, Clone, Visit, Reflect)]
+pub struct LootEntry {
+    pub item: Option<ModelResource>,
+    pub amount: u32,
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    pub probability: f32,
+}
+
+stub_uuid_provider!(LootEntry);
+
+/// A loud noise (a shot, for example) made by an actor. Bots that are within the hearing radius of
+/// the noise will be alerted, even if the source of the noise is out of their sight.
+#[derive(Debug, Clone)]
+pub struct NoiseMessage {
+    pub source: Handle<Node>,
+    pub position: Vector3<f32>,
+    /// Effective hearing radius of the noise, it already includes all modifiers (a suppressor, for
+    /// example).
+    pub radius: f32,
+}
 
 #[derive(Debug, Visit, Default, Clone)]
 pub struct Target {
@@ -125,6 +186,10 @@ pub struct Bot {
     state_machine: StateMachine,
     pub restoration_time: f32,
     #[reflect(hidden)]
+    under_fire_timer: f32,
+    #[reflect(hidden)]
+    is_peeking_from_cover: bool,
+    #[reflect(hidden)]
     agent: NavmeshAgent,
     #[visit(skip)]
     #[reflect(hidden)]
@@ -139,9 +204,97 @@ pub struct Bot {
     yaw: SmoothAngle,
     pitch: SmoothAngle,
     pub walk_speed: f32,
+    #[reflect(
+        description = "Whether the horizontal movement of the bot is driven by the root motion of \
+    the locomotion animation, otherwise the bot moves with the walk speed. Root motion prevents \
+    foot sliding, but requires animations with root motion."
+    )]
+    pub use_root_motion: bool,
     pub v_aim_angle_hack: f32,
     pub h_aim_angle_hack: f32,
     pub close_combat_distance: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Min time (in seconds) between two threatens of the bot."
+    )]
+    pub threaten_cooldown_min: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Max time (in seconds) between two threatens of the bot. Must be greater \
+    or equal than min cooldown."
+    )]
+    pub threaten_cooldown_max: f32,
+    #[reflect(
+        description = "A handle of a spawn point that will be used to spawn reinforcements called \
+    by the bot."
+    )]
+    pub reinforcement_spawn_point: Handle<Node>,
+    #[reflect(
+        description = "Max amount of reinforcements that could be called by the bot. Zero means \
+    that the bot cannot call reinforcements."
+    )]
+    pub reinforcement_budget: u32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Min time (in seconds) between two attempts to call reinforcements."
+    )]
+    pub reinforcement_cooldown: f32,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Probability of calling reinforcements on every attempt."
+    )]
+    pub reinforcement_call_probability: f32,
+    #[reflect(hidden)]
+    reinforcement_timer: f32,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 360.0,
+        description = "Field of view (in degrees) of the vision cone of the bot."
+    )]
+    pub fov_degrees: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Max distance (in meters) at which the bot can see other actors."
+    )]
+    pub view_distance: f32,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    heard_actor: Handle<Node>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of threat (per second) that is gained by an actor, that stays right \
+    next to the bot. The gain fades out linearly up to the view distance."
+    )]
+    pub proximity_threat: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of threat (per second) that every actor loses over time. Must be \
+    less than the proximity threat, otherwise nearby actors never accumulate any threat."
+    )]
+    pub threat_decay: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Minimal excess of threat of another actor over the threat of the current \
+    target, that makes the bot switch to the other actor."
+    )]
+    pub threat_switch_margin: f32,
+    // Threat of every actor that has attacked the bot or came close to it. Damage adds the same
+    // amount of threat to the dealer.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    threat: FxHashMap<Handle<Node>, f32>,
+    #[reflect(
+        description = "Speed of lateral movement (in m/s) of the bot when it shoots its target. \
+    Zero disables strafing."
+    )]
+    pub strafe_speed: f32,
+    #[reflect(
+        description = "Time (in seconds) after which the bot changes its strafing direction."
+    )]
+    pub strafe_direction_change_interval: f32,
+    #[reflect(hidden)]
+    is_strafing: bool,
     pub pain_sounds: Vec<Handle<Node>>,
     pub scream_sounds: Vec<Handle<Node>>,
     pub idle_sounds: Vec<Handle<Node>>,
@@ -149,7 +302,205 @@ pub struct Bot {
     prev_is_dead: bool,
     despawn_asset: Option<ModelResource>,
     despawn_timeout: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) before the removal of the corpse during which it fades \
+    out. Zero means that the corpse disappears instantly."
+    )]
+    pub corpse_fade_duration: f32,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    corpse_fade_started: bool,
+    // Render paths of the meshes of the corpse before the fade, they're restored on revival.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    corpse_render_paths: FxHashMap<Handle<Node>, RenderPath>,
     last_position: Vector3<f32>,
+    #[reflect(description = "A list of items that could be dropped by the bot on death.")]
+    loot_table: Vec<LootEntry>,
+    #[reflect(
+        description = "An ordered list of waypoints the bot walks between when it has no target. \
+    Empty list means that the bot stays in place."
+    )]
+    pub patrol_waypoints: Vec<Handle<Node>>,
+    #[reflect(description = "Defines what the bot does after reaching the last waypoint.")]
+    pub patrol_mode: PatrolMode,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Movement speed multiplier of the bot while it is patrolling."
+    )]
+    pub patrol_speed_factor: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) during which the bot searches for its target at the \
+    position where the target was seen the last time."
+    )]
+    pub search_duration: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) during which the bot stays in place after it has spotted \
+    a target being unaware of any danger. The actual time is randomized a bit and is shorter on \
+    harder difficulties."
+    )]
+    pub reaction_time: f32,
+    #[reflect(hidden)]
+    reaction_timer: f32,
+    #[reflect(hidden)]
+    target_check_timer: f32,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Accuracy of shots of the bot at point-blank range against a standing \
+    target. One means perfect accuracy, zero - deviation of shots up to 10 degrees."
+    )]
+    pub base_accuracy: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Additional deviation (in degrees) of shots of the bot per every meter \
+    of distance to the target."
+    )]
+    pub distance_accuracy_penalty: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Additional deviation (in degrees) of shots of the bot per every m/s of \
+    lateral speed of the target."
+    )]
+    pub speed_accuracy_penalty: f32,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Probability of the bot to dodge a projectile that is about to hit it. \
+    Zero disables dodging."
+    )]
+    pub dodge_chance: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) after a dodge attempt, during which the bot cannot \
+    dodge again."
+    )]
+    pub dodge_cooldown: f32,
+    #[reflect(min_value = 0.0, description = "Speed (in m/s) of the dodge movement.")]
+    pub dodge_speed: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Duration (in seconds) of the dodge movement."
+    )]
+    pub dodge_duration: f32,
+    #[reflect(hidden)]
+    dodge_cooldown_timer: f32,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Probability of the bot to raise a guard when its target is close or when \
+    it is under fire. Zero disables blocking."
+    )]
+    pub block_chance: f32,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Fraction of frontal damage that is absorbed by the guard."
+    )]
+    pub block_damage_reduction: f32,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 360.0,
+        description = "Width (in degrees) of the frontal arc protected by the guard."
+    )]
+    pub block_arc: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Duration (in seconds) during which the guard is kept raised."
+    )]
+    pub block_duration: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) after a block attempt, during which the bot cannot \
+    block again."
+    )]
+    pub block_cooldown: f32,
+    #[reflect(hidden)]
+    block_timer: f32,
+    #[reflect(hidden)]
+    block_cooldown_timer: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Min damage of a single hit that staggers the bot. A staggered bot cannot \
+    act and its current attack is interrupted. Zero disables staggering."
+    )]
+    pub stagger_damage_threshold: f32,
+    #[reflect(min_value = 0.0, description = "Duration (in seconds) of a stagger.")]
+    pub stagger_duration: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) after a stagger during which the bot cannot be staggered \
+    again."
+    )]
+    pub stagger_cooldown: f32,
+    #[reflect(hidden)]
+    stagger_timer: f32,
+    #[reflect(hidden)]
+    stagger_cooldown_timer: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Defines how strong the corpse of the bot is pushed by the killing blow. \
+    The impulse is the damage of the blow multiplied by this scale."
+    )]
+    pub death_impulse_scale: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Max impulse that can be applied to the corpse of the bot."
+    )]
+    pub max_death_impulse: f32,
+    // An impulse of the killing blow, that will be applied to the ragdoll as soon as it is
+    // activated.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    death_impulse: Option<Vector3<f32>>,
+    #[reflect(
+        description = "Damage multipliers of consecutive hits of a melee combo attack. The last \
+    multiplier is used for the rest of hits, empty list means that every hit deals full damage."
+    )]
+    pub combo_damage_scales: Vec<f32>,
+    // Amount of hits made during the current melee attack.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    combo_hit_count: usize,
+    #[reflect(
+        description = "Makes the bot an escort that fights alongside the player and follows the \
+    player when there are no enemies around. The team of the bot should match the team of the \
+    player, hostility should be set to Everyone."
+    )]
+    pub escort: bool,
+    #[reflect(
+        min_value = 0.0,
+        description = "Distance (in meters) at which an escort follows the player."
+    )]
+    pub follow_distance: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "If the player gets farther than this distance (in meters) from an escort, \
+    the escort is moved closer to the player. Zero disables catching up."
+    )]
+    pub follow_teleport_distance: f32,
+    #[reflect(description = "Whether the death of the escort ends the game.")]
+    pub escort_mission_critical: bool,
+    #[reflect(
+        description = "Whether the bot raises the station-wide alarm when it spots the player."
+    )]
+    pub raises_alarm: bool,
+    #[reflect(
+        min_value = 0.0,
+        description = "Duration (in seconds) of the alarm raised by the bot. Zero means that the \
+    alarm lasts until its console is destroyed."
+    )]
+    pub alarm_duration: f32,
+    #[reflect(hidden)]
+    search_timer: f32,
+    #[reflect(hidden)]
+    death_reported: bool,
+    #[reflect(hidden)]
+    last_known_position: Option<Vector3<f32>>,
 }
 
 impl Deref for Bot {
@@ -174,6 +525,8 @@ impl Default for Bot {
             target: Default::default(),
             state_machine: Default::default(),
             restoration_time: 0.0,
+            under_fire_timer: 0.0,
+            is_peeking_from_cover: false,
             agent: Default::default(),
             impact_handler: Default::default(),
             behavior: Default::default(),
@@ -183,9 +536,27 @@ impl Default for Bot {
             threaten_timeout: 0.0,
             absm: Default::default(),
             walk_speed: 1.2,
+            use_root_motion: true,
             v_aim_angle_hack: 0.0,
             h_aim_angle_hack: 0.0,
             close_combat_distance: 1.2,
+            threaten_cooldown_min: Self::DEFAULT_THREATEN_COOLDOWN_MIN,
+            threaten_cooldown_max: Self::DEFAULT_THREATEN_COOLDOWN_MAX,
+            reinforcement_spawn_point: Default::default(),
+            reinforcement_budget: 0,
+            reinforcement_cooldown: 20.0,
+            reinforcement_call_probability: 0.3,
+            reinforcement_timer: 0.0,
+            fov_degrees: 90.0,
+            view_distance: 20.0,
+            heard_actor: Default::default(),
+            proximity_threat: 10.0,
+            threat_decay: 2.0,
+            threat_switch_margin: 10.0,
+            threat: Default::default(),
+            strafe_speed: 1.0,
+            strafe_direction_change_interval: 1.5,
+            is_strafing: false,
             pain_sounds: Default::default(),
             scream_sounds: Default::default(),
             idle_sounds: Default::default(),
@@ -203,13 +574,95 @@ impl Default for Bot {
             ragdoll: Default::default(),
             despawn_asset: None,
             despawn_timeout: 30.0,
+            corpse_fade_duration: 1.0,
+            corpse_fade_started: false,
+            corpse_render_paths: Default::default(),
             prev_is_dead: false,
             last_position: Default::default(),
+            loot_table: Default::default(),
+            patrol_waypoints: Default::default(),
+            patrol_mode: Default::default(),
+            patrol_speed_factor: 0.5,
+            search_duration: 10.0,
+            search_timer: 0.0,
+            reaction_time: 0.4,
+            reaction_timer: 0.0,
+            target_check_timer: 0.0,
+            escort: false,
+            follow_distance: 3.0,
+            follow_teleport_distance: 30.0,
+            escort_mission_critical: false,
+            raises_alarm: false,
+            alarm_duration: 60.0,
+            base_accuracy: 0.8,
+            distance_accuracy_penalty: 0.15,
+            speed_accuracy_penalty: 1.5,
+            dodge_chance: 0.0,
+            dodge_cooldown: 3.0,
+            dodge_speed: 5.0,
+            dodge_duration: 0.3,
+            dodge_cooldown_timer: 0.0,
+            block_chance: 0.0,
+            block_damage_reduction: 0.75,
+            block_arc: 120.0,
+            block_duration: 1.5,
+            block_cooldown: 4.0,
+            block_timer: 0.0,
+            block_cooldown_timer: 0.0,
+            stagger_damage_threshold: 0.0,
+            stagger_duration: 0.6,
+            stagger_cooldown: 3.0,
+            stagger_timer: 0.0,
+            stagger_cooldown_timer: 0.0,
+            death_impulse_scale: 0.5,
+            max_death_impulse: 30.0,
+            death_impulse: None,
+            combo_damage_scales: Default::default(),
+            combo_hit_count: 0,
+            death_reported: false,
+            last_known_position: None,
         }
     }
 }
 
 impl Bot {
+    /// Time (in seconds) during which the bot considers itself under fire after it was damaged.
+    const UNDER_FIRE_TIMEOUT: f32 = 3.0;
+    const TARGET_CHECK_INTERVAL: f32 = 0.15;
+    const DEFAULT_THREATEN_COOLDOWN_MIN: f32 = 20.0;
+    const DEFAULT_THREATEN_COOLDOWN_MAX: f32 = 60.0;
+
+    /// Returns min and max threaten cooldown, falls back to defaults if the range is
+    /// misconfigured.
+    fn threaten_cooldown_range(&self) -> (f32, f32) {
+        if self.threaten_cooldown_min >= 0.0
+            && self.threaten_cooldown_min <= self.threaten_cooldown_max
+        {
+            (self.threaten_cooldown_min, self.threaten_cooldown_max)
+        } else {
+            (
+                Self::DEFAULT_THREATEN_COOLDOWN_MIN,
+                Self::DEFAULT_THREATEN_COOLDOWN_MAX,
+            )
+        }
+    }
+
+    fn drop_loot(&self, scene: &mut Scene, difficulty: Difficulty) {
+        let position = self.position(&scene.graph) + Vector3::new(0.0, 0.5, 0.0);
+
+        for entry in self.loot_table.iter() {
+            let item = some_or_continue!(entry.item.as_ref());
+
+            if entry.amount > 0
+                && utils::is_probability_event_occurred(
+                    entry.probability * difficulty.loot_drop_multiplier(),
+                )
+            {
+                Item::add_to_scene(scene, item.clone(), position, true, entry.amount);
+            }
+        }
+    }
+
     #[allow(clippy::unnecessary_to_owned)] // false positive
     fn check_doors(&mut self, scene: &mut Scene, door_container: &DoorContainer) {
         if let Some(target) = self.target.as_ref() {
@@ -253,7 +706,7 @@ impl Bot {
         }
     }
 
-    pub fn debug_draw(&self, context: &mut SceneDrawingContext) {
+    pub fn debug_draw(&self, graph: &Graph, context: &mut SceneDrawingContext) {
         for pts in self.agent.path().windows(2) {
             let a = pts[0];
             let b = pts[1];
@@ -264,14 +717,199 @@ impl Bot {
             });
         }
 
-        // context.draw_frustum(&self.frustum, Color::from_rgba(0, 200, 0, 255)); TODO
+        VisionCone::new(
+            self.character.position(graph),
+            graph,
+            self.model,
+            self.fov_degrees,
+            self.view_distance,
+        )
+        .debug_draw(context, Color::from_rgba(0, 200, 0, 255));
+    }
+
+    /// Makes every mesh of the corpse semi-transparent, `visibility` is in `[0; 1]` range where
+    /// zero means fully transparent.
+    fn update_corpse_fade(
+        &mut self,
+        graph: &mut Graph,
+        self_handle: Handle<Node>,
+        visibility: f32,
+    ) {
+        let begin = !self.corpse_fade_started;
+        self.corpse_fade_started = true;
+
+        let alpha = (visibility.clamp(0.0, 1.0) * 255.0) as u8;
+
+        for node in graph.traverse_handle_iter(self_handle).collect::<Vec<_>>() {
+            let mesh = some_or_continue!(graph.try_get_mut_of_type::<Mesh>(node));
+
+            if begin {
+                // Materials are shared between all instances of the bot, every corpse needs its
+                // own copies to fade out independently.
+                self.corpse_render_paths.insert(node, mesh.render_path());
+                mesh.set_render_path(RenderPath::Forward);
+                for surface in mesh.surfaces_mut() {
+                    let material = surface.material().deep_copy();
+                    surface.set_material(material);
+                }
+            }
+
+            set_surfaces_alpha(mesh, alpha);
+        }
+    }
+
+    /// Makes the corpse fully opaque again and restores the original render paths of its meshes,
+    /// the materials stay unique to this bot.
+    fn reset_corpse_fade(
+        graph: &mut Graph,
+        self_handle: Handle<Node>,
+        render_paths: &FxHashMap<Handle<Node>, RenderPath>,
+    ) {
+        for node in graph.traverse_handle_iter(self_handle).collect::<Vec<_>>() {
+            let mesh = some_or_continue!(graph.try_get_mut_of_type::<Mesh>(node));
+            if let Some(render_path) = render_paths.get(&node) {
+                mesh.set_render_path(*render_path);
+            }
+            set_surfaces_alpha(mesh, 255);
+        }
+    }
+
+    /// Turns the bot into a ragdoll. The impulse of the killing blow is applied to the root limb
+    /// (usually hips) once the ragdoll is active, otherwise the bodies of the ragdoll are still
+    /// kinematic and ignore any impulses.
+    fn activate_ragdoll(&mut self, graph: &mut Graph) {
+        let Some(ragdoll) = graph.try_get_mut_of_type::<Ragdoll>(*self.ragdoll) else {
+            return;
+        };
+
+        if !*ragdoll.is_active {
+            ragdoll.is_active.set_value_and_mark_modified(true);
+            return;
+        }
+
+        let root_body = ragdoll.root_limb().physical_bone;
+
+        if let Some(impulse) = self.death_impulse.take() {
+            if let Some(rigid_body) = graph.try_get_mut_of_type::<RigidBody>(root_body) {
+                rigid_body.apply_impulse(impulse);
+                rigid_body.wake_up();
+            }
+        }
+    }
+
+    /// Brings a dead bot back to life. Health of the hit boxes must be restored separately.
+    pub fn revive(bot_handle: Handle<Node>, graph: &mut Graph) {
+        let Some(bot) = graph.try_get_script_of_mut::<Bot>(bot_handle) else {
+            return;
+        };
+        bot.prev_is_dead = false;
+        bot.death_reported = false;
+        bot.target = None;
+        bot.threat.clear();
+        bot.last_known_position = None;
+        bot.search_timer = 0.0;
+        bot.reaction_timer = 0.0;
+        bot.target_check_timer = 0.0;
+        bot.is_peeking_from_cover = false;
+        bot.dodge_cooldown_timer = 0.0;
+        bot.block_timer = 0.0;
+        bot.block_cooldown_timer = 0.0;
+        bot.stagger_timer = 0.0;
+        bot.stagger_cooldown_timer = 0.0;
+        bot.death_impulse = None;
+        let ragdoll = *bot.ragdoll;
+
+        let render_paths = std::mem::take(&mut bot.corpse_render_paths);
+        if std::mem::take(&mut bot.corpse_fade_started) {
+            Self::reset_corpse_fade(graph, bot_handle, &render_paths);
+        }
+
+        if let Some(ragdoll) = graph.try_get_mut_of_type::<Ragdoll>(ragdoll) {
+            ragdoll.is_active.set_value_and_mark_modified(false);
+        }
+
+        let node = &mut graph[bot_handle];
+        node.set_lifetime(None);
+        node.local_transform_mut().set_scale(Vector3::repeat(1.0));
+    }
+
+    pub fn target(&self) -> Option<Handle<Node>> {
+        self.target.as_ref().map(|target| target.handle)
     }
 
     pub fn set_target(&mut self, handle: Handle<Node>, position: Vector3<f32>) {
         self.target = Some(Target { position, handle });
     }
 
-    fn handle_animation_events(&mut self, scene: &mut Scene, sound_manager: &SoundManager) {
+    pub fn threat_of(&self, actor: Handle<Node>) -> f32 {
+        self.threat.get(&actor).cloned().unwrap_or_default()
+    }
+
+    fn update_threat(&mut self, graph: &Graph, dt: f32) {
+        let decay = self.threat_decay * dt;
+        self.threat.retain(|actor, threat| {
+            *threat -= decay;
+            *threat > 0.0 && graph.is_valid_handle(*actor)
+        });
+    }
+
+    /// Returns `true` if the bot fights alongside the player.
+    pub fn is_escort(&self) -> bool {
+        self.escort
+    }
+
+    pub fn is_blocking(&self) -> bool {
+        self.block_timer > 0.0
+    }
+
+    /// Returns a multiplier for the incoming damage. A raised guard absorbs a part of damage of
+    /// the hits that come from the front of the bot. Damage without a known source (for example
+    /// from hazard zones) is never blocked.
+    pub fn block_damage_scale(&self, damage: &HitBoxDamage, graph: &Graph) -> f32 {
+        if !self.is_blocking() {
+            return 1.0;
+        }
+
+        let Some(damage_position) = damage.position else {
+            return 1.0;
+        };
+
+        let source = damage
+            .dealer
+            .as_character(graph)
+            .map(|(_, character)| character.position(graph))
+            .unwrap_or(damage_position.point - damage_position.direction);
+        let to_source = source - self.position(graph);
+        let forward = graph[self.model].look_vector();
+
+        let (Some(to_source), Some(forward)) = (
+            Vector3::new(to_source.x, 0.0, to_source.z).try_normalize(f32::EPSILON),
+            Vector3::new(forward.x, 0.0, forward.z).try_normalize(f32::EPSILON),
+        ) else {
+            return 1.0;
+        };
+
+        if forward.dot(&to_source) >= (self.block_arc * 0.5).to_radians().cos() {
+            1.0 - self.block_damage_reduction
+        } else {
+            1.0
+        }
+    }
+
+    fn combo_damage_scale(&self) -> f32 {
+        self.combo_damage_scales
+            .get(self.combo_hit_count)
+            .or(self.combo_damage_scales.last())
+            .cloned()
+            .unwrap_or(1.0)
+    }
+
+    fn handle_animation_events(
+        &mut self,
+        self_handle: Handle<Node>,
+        scene: &mut Scene,
+        sound_manager: &SoundManager,
+    ) {
         if let Some(absm) = scene
             .graph
             .try_get_of_type::<AnimationBlendingStateMachine>(self.state_machine.absm)
@@ -312,14 +950,30 @@ impl Bot {
                             scene.graph[self.model].global_position() + Vector3::new(0.0, 0.5, 0.0);
 
                         self.character
-                            .footstep_ray_check(begin, scene, sound_manager);
+                            .footstep_ray_check(self_handle, begin, scene, sound_manager);
                     }
                 }
 
+                let is_attacking = self
+                    .state_machine
+                    .upper_body_layer(&scene.graph)
+                    .is_some_and(|l| l.active_state() == self.state_machine.attack_state);
+                if !is_attacking {
+                    self.combo_hit_count = 0;
+                }
+
                 for (_, event) in upper_layer_events.events {
                     if event.name == StateMachine::HIT_BEGIN_SIGNAL {
-                        self.melee_attack_context = Some(Default::default());
-                        utils::try_play_random_sound(&self.attack_sounds, &mut scene.graph);
+                        // Every hit of a combo must be closed before the next one, otherwise the
+                        // same hit would be counted twice.
+                        if self.melee_attack_context.is_none() && !self.is_staggered() {
+                            self.melee_attack_context = Some(MeleeAttackContext {
+                                damage_scale: self.combo_damage_scale(),
+                                ..Default::default()
+                            });
+                            self.combo_hit_count += 1;
+                            utils::try_play_random_sound(&self.attack_sounds, &mut scene.graph);
+                        }
                     } else if event.name == StateMachine::HIT_END_SIGNAL {
                         self.melee_attack_context = None;
                     }
@@ -336,9 +990,38 @@ impl Bot {
         }
     }
 
+    pub fn is_staggered(&self) -> bool {
+        self.stagger_timer > 0.0
+    }
+
+    /// Interrupts the current attack of the bot and prevents it from acting for a while.
+    fn stagger(&mut self, graph: &mut Graph) {
+        self.stagger_timer = self.stagger_duration;
+        // The cooldown starts when the stagger ends.
+        self.stagger_cooldown_timer = self.stagger_duration + self.stagger_cooldown;
+        self.melee_attack_context = None;
+        self.state_machine.interrupt_attack(graph);
+        self.character.stand_still(graph);
+    }
+
     fn on_damage(&mut self, damage: &HitBoxDamage, ctx: &mut ScriptMessageContext) {
+        self.under_fire_timer = Self::UNDER_FIRE_TIMEOUT;
+
         if let Some((character_handle, character)) = damage.dealer.as_character(&ctx.scene.graph) {
-            self.set_target(character_handle, character.position(&ctx.scene.graph));
+            // Allies are never considered as threats, even if friendly fire is enabled.
+            if character_handle != ctx.handle && !character.is_ally_of(&self.character) {
+                *self.threat.entry(character_handle).or_default() += damage.damage;
+
+                // Turn to the attacker only if it is more dangerous than the current target.
+                let switch_to_attacker = self.target().is_none_or(|target| {
+                    target == character_handle
+                        || self.threat_of(character_handle)
+                            > self.threat_of(target) + self.threat_switch_margin
+                });
+                if switch_to_attacker {
+                    self.set_target(character_handle, character.position(&ctx.scene.graph));
+                }
+            }
         }
 
         let hit_box = ctx
@@ -353,18 +1036,91 @@ impl Bot {
                 *hit_box.bone,
                 position.point,
                 position.direction,
+                damage.damage,
             );
         }
 
+        if !self.death_reported && self.is_dead(&ctx.scene.graph) {
+            self.death_reported = true;
+
+            self.death_impulse = damage.position.and_then(|position| {
+                let direction = position.direction.try_normalize(f32::EPSILON)?;
+                let magnitude =
+                    (damage.damage * self.death_impulse_scale).min(self.max_death_impulse);
+                Some(direction.scale(magnitude))
+            });
+
+            if ctx.plugins.get::<Game>().is_gore_enabled() {
+                let blood_pool = Decal::new_blood_pool(
+                    ctx.resource_manager,
+                    &mut ctx.scene.graph,
+                    self.position(&ctx.scene.graph),
+                    ctx.handle,
+                );
+                if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+                    level.add_blood_pool(blood_pool, &mut ctx.scene.graph);
+                }
+            }
+
+            if self.escort && self.escort_mission_critical {
+                ctx.plugins
+                    .get::<Game>()
+                    .message_sender
+                    .send(Message::EndMatch);
+            }
+
+            ctx.message_sender.send_global(KillEvent {
+                victim: ctx.handle,
+                killer: damage
+                    .dealer
+                    .as_character(&ctx.scene.graph)
+                    .map(|(handle, _)| handle)
+                    .unwrap_or(damage.dealer.entity),
+                hit_box: damage.hit_box,
+                is_head_shot: *hit_box.limb_type == LimbType::Head,
+                position: damage
+                    .position
+                    .map(|p| p.point)
+                    .unwrap_or_else(|| self.position(&ctx.scene.graph)),
+            });
+        }
+
+        if self.stagger_damage_threshold > 0.0
+            && damage.damage >= self.stagger_damage_threshold
+            && self.stagger_cooldown_timer <= 0.0
+            && !self.is_dead(&ctx.scene.graph)
+        {
+            self.stagger(&mut ctx.scene.graph);
+        }
+
         // Prevent spamming with grunt sounds.
         let graph = &ctx.scene.graph;
         if !self.is_dead(graph) && !utils::is_any_sound_playing(&self.pain_sounds, graph) {
-            self.restoration_time = 0.8;
+            self.restoration_time = 0.8
+                * ctx
+                    .plugins
+                    .get::<Game>()
+                    .config
+                    .difficulty
+                    .reaction_time_multiplier();
             utils::try_play_random_sound(&self.pain_sounds, &mut ctx.scene.graph);
         }
     }
 }
 
+/// Changes alpha of the diffuse color of every surface of the mesh, keeping the tint of the
+/// materials.
+fn set_surfaces_alpha(mesh: &Mesh, alpha: u8) {
+    for surface in mesh.surfaces() {
+        let mut material = surface.material().data_ref();
+        let color = match material.property_ref("diffuseColor") {
+            Some(MaterialProperty::Color(color)) => *color,
+            _ => Color::WHITE,
+        };
+        material.set_property("diffuseColor", color.with_new_alpha(alpha));
+    }
+}
+
 impl ScriptTrait for Bot {
     fn on_init(&mut self, ctx: &mut ScriptContext) {
         self.agent = NavmeshAgentBuilder::new()
@@ -391,6 +1147,10 @@ impl ScriptTrait for Bot {
             .subscribe_to::<WeaponMessage>(ctx.handle);
         ctx.message_dispatcher
             .subscribe_to::<HitBoxMessage>(ctx.handle);
+        ctx.message_dispatcher
+            .subscribe_to::<NoiseMessage>(ctx.handle);
+        ctx.message_dispatcher
+            .subscribe_to::<AlarmEvent>(ctx.handle);
 
         // Try to equip the first available weapon.
         if !ctx
@@ -474,6 +1234,23 @@ impl ScriptTrait for Bot {
             message.downcast_ref::<HitBoxMessage>()
         {
             self.on_damage(hit_box_damage, ctx)
+        } else if let Some(noise) = message.downcast_ref::<NoiseMessage>() {
+            if noise.source != ctx.handle
+                && self
+                    .position(&ctx.scene.graph)
+                    .metric_distance(&noise.position)
+                    <= noise.radius
+            {
+                self.heard_actor = noise.source;
+            }
+        } else if let Some(alarm) = message.downcast_ref::<AlarmEvent>() {
+            // Hostile bots without a target go to check the place where the player was seen.
+            if let Some(position) = alarm.position {
+                if alarm.active && !self.escort && self.target.is_none() {
+                    self.last_known_position = Some(position);
+                    self.search_timer = self.search_duration;
+                }
+            }
         }
     }
 
@@ -481,13 +1258,23 @@ impl ScriptTrait for Bot {
         let game = ctx.plugins.get::<Game>();
         let level = game.level.as_ref().unwrap();
 
+        let (threaten_cooldown_min, threaten_cooldown_max) = self.threaten_cooldown_range();
+
+        let had_player_target = self
+            .target
+            .as_ref()
+            .is_some_and(|target| target.handle == level.player);
+
         let movement_speed_factor;
         let need_to_melee_attack;
 
         let is_moving;
+        let is_strafing;
         let is_aiming;
         let attack_animation_index;
         let is_screaming;
+        let is_dodging;
+        let is_blocking;
         {
             let mut behavior_ctx = BehaviorContext {
                 scene: ctx.scene,
@@ -503,10 +1290,28 @@ impl ScriptTrait for Bot {
                 impact_handler: &self.impact_handler,
                 model: self.model,
                 restoration_time: self.restoration_time,
+                is_under_fire: self.under_fire_timer > 0.0,
+                is_peeking_from_cover: &mut self.is_peeking_from_cover,
                 v_recoil: &mut self.v_recoil,
                 h_recoil: &mut self.h_recoil,
                 move_speed: self.walk_speed,
+                use_root_motion: self.use_root_motion,
+                fov_degrees: self.fov_degrees,
+                view_distance: self.view_distance,
+                heard_actor: self.heard_actor,
+                threat: &mut self.threat,
+                proximity_threat: self.proximity_threat,
+                threat_switch_margin: self.threat_switch_margin,
+                strafe_speed: self.strafe_speed,
+                strafe_direction_change_interval: self.strafe_direction_change_interval,
                 threaten_timeout: &mut self.threaten_timeout,
+                threaten_cooldown_min,
+                threaten_cooldown_max,
+                reinforcement_spawn_point: self.reinforcement_spawn_point,
+                reinforcement_budget: &mut self.reinforcement_budget,
+                reinforcement_timer: &mut self.reinforcement_timer,
+                reinforcement_cooldown: self.reinforcement_cooldown,
+                reinforcement_call_probability: self.reinforcement_call_probability,
                 sound_manager: &level.sound_manager,
                 script_message_sender: ctx.message_sender,
                 navmesh: level.navmesh,
@@ -514,6 +1319,38 @@ impl ScriptTrait for Bot {
                 pitch: &mut self.pitch,
                 scream_sounds: &self.scream_sounds,
                 plugins: &ctx.plugins,
+                patrol_waypoints: &self.patrol_waypoints,
+                patrol_mode: self.patrol_mode,
+                patrol_speed_factor: self.patrol_speed_factor,
+                search_duration: self.search_duration,
+                search_timer: &mut self.search_timer,
+                last_known_position: &mut self.last_known_position,
+                reaction_time: self.reaction_time
+                    * game.config.difficulty.reaction_time_multiplier(),
+                reaction_timer: &mut self.reaction_timer,
+                target_check_interval: Self::TARGET_CHECK_INTERVAL
+                    * game.config.difficulty.reaction_time_multiplier(),
+                target_check_timer: &mut self.target_check_timer,
+                base_accuracy: self.base_accuracy,
+                distance_accuracy_penalty: self.distance_accuracy_penalty,
+                speed_accuracy_penalty: self.speed_accuracy_penalty,
+                dodge_chance: self.dodge_chance,
+                dodge_cooldown: self.dodge_cooldown,
+                dodge_speed: self.dodge_speed,
+                dodge_duration: self.dodge_duration,
+                dodge_cooldown_timer: &mut self.dodge_cooldown_timer,
+                block_chance: self.block_chance,
+                block_cooldown: self.block_cooldown,
+                block_duration: self.block_duration,
+                block_timer: &mut self.block_timer,
+                block_cooldown_timer: &mut self.block_cooldown_timer,
+                leader: if self.escort {
+                    level.player
+                } else {
+                    Handle::NONE
+                },
+                follow_distance: self.follow_distance,
+                follow_teleport_distance: self.follow_teleport_distance,
 
                 // Output
                 hostility: self.hostility,
@@ -522,35 +1359,71 @@ impl ScriptTrait for Bot {
                 attack_animation_index: 0,
                 movement_speed_factor: 1.0,
                 is_moving: false,
+                is_strafing: false,
                 need_to_melee_attack: false,
 
                 is_aiming_weapon: false,
                 is_screaming: false,
+                is_dodging: false,
+                is_blocking: false,
+                is_path_blocked: false,
             };
 
-            self.behavior.tree.tick(&mut behavior_ctx);
+            // Staggered bot cannot do anything, but it still can die - the first branch of the
+            // tree handles death, so nothing else will run for a dead bot.
+            if self.stagger_timer <= 0.0
+                || behavior_ctx.character.is_dead(&behavior_ctx.scene.graph)
+            {
+                self.behavior.tree.tick(&mut behavior_ctx);
+            }
+
+            self.heard_actor = Handle::NONE;
 
             movement_speed_factor = behavior_ctx.movement_speed_factor;
             need_to_melee_attack = behavior_ctx.need_to_melee_attack;
             is_moving = behavior_ctx.is_moving;
+            is_strafing = behavior_ctx.is_strafing;
             is_aiming = behavior_ctx.is_aiming_weapon;
             attack_animation_index = behavior_ctx.attack_animation_index;
             is_screaming = behavior_ctx.is_screaming;
+            is_dodging = behavior_ctx.is_dodging;
+            is_blocking = behavior_ctx.is_blocking;
+        }
+
+        // Position of the player, if the bot has just spotted the player.
+        let spotted_player = if had_player_target {
+            None
+        } else {
+            self.target
+                .as_ref()
+                .filter(|target| target.handle == level.player)
+                .map(|target| target.position)
+        };
+
+        // Strafing sets the velocity directly, so it must be reset when the bot stops strafing.
+        if self.is_strafing && !is_strafing && !is_moving {
+            self.character.stand_still(&mut ctx.scene.graph);
         }
+        self.is_strafing = is_strafing;
 
         let is_dead = self.is_dead(&ctx.scene.graph);
         if is_dead {
-            if let Some(ragdoll) = ctx
-                .scene
-                .graph
-                .try_get_mut_of_type::<Ragdoll>(*self.ragdoll)
-            {
-                ragdoll.is_active.set_value_and_mark_modified(true);
-            }
+            self.activate_ragdoll(&mut ctx.scene.graph);
         }
 
         self.update_melee_attack(ctx.scene, ctx.message_sender, ctx.handle);
         self.check_doors(ctx.scene, &level.doors_container);
+        if !is_dead {
+            self.character.update_hazards(
+                &level.hazard_zones,
+                &ctx.scene.graph,
+                ctx.message_sender,
+                ctx.dt,
+            );
+            self.character
+                .update_status_effects(&ctx.scene.graph, ctx.message_sender, ctx.dt);
+            self.character.update_weapon_switch(ctx.dt);
+        }
 
         let no_leg = self
             .character
@@ -567,6 +1440,10 @@ impl ScriptTrait for Bot {
                 attack_animation_index: attack_animation_index as u32,
                 aim: is_aiming,
                 badly_damaged: self.restoration_time > 0.0,
+                stagger: self.is_staggered(),
+                dodge: is_dodging,
+                block: is_blocking,
+                switch_weapon: self.character.is_switching_weapon(),
                 movement_type: if no_leg {
                     MovementType::Crawl
                 } else {
@@ -577,7 +1454,17 @@ impl ScriptTrait for Bot {
         self.impact_handler.update_and_apply(ctx.dt, ctx.scene);
 
         self.restoration_time -= ctx.dt;
+        self.under_fire_timer -= ctx.dt;
+        self.reinforcement_timer -= ctx.dt;
+        self.dodge_cooldown_timer -= ctx.dt;
+        self.update_threat(&ctx.scene.graph, ctx.dt);
+        self.block_timer -= ctx.dt;
+        self.block_cooldown_timer -= ctx.dt;
+        self.stagger_timer -= ctx.dt;
+        self.stagger_cooldown_timer -= ctx.dt;
         self.threaten_timeout -= ctx.dt;
+        self.reaction_timer -= ctx.dt;
+        self.target_check_timer -= ctx.dt;
 
         self.v_recoil.update(ctx.dt);
         self.h_recoil.update(ctx.dt);
@@ -590,7 +1477,7 @@ impl ScriptTrait for Bot {
                 * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), self.h_recoil.angle()),
         );
 
-        self.handle_animation_events(ctx.scene, &level.sound_manager);
+        self.handle_animation_events(ctx.handle, ctx.scene, &level.sound_manager);
 
         let node = &mut ctx.scene.graph[ctx.handle];
 
@@ -601,16 +1488,18 @@ impl ScriptTrait for Bot {
             node.set_lifetime(Some(self.despawn_timeout));
         }
 
+        self.last_position = node.global_position();
+
         if let Some(lifetime) = node.lifetime() {
-            if lifetime <= 1.0 {
-                node.local_transform_mut()
-                    .set_scale(Vector3::repeat(lifetime));
+            let fade_duration = self.corpse_fade_duration.min(self.despawn_timeout);
+            if fade_duration > 0.0 && lifetime <= fade_duration {
+                self.update_corpse_fade(&mut ctx.scene.graph, ctx.handle, lifetime / fade_duration);
             }
         }
 
-        self.last_position = node.global_position();
-
         if died {
+            self.drop_loot(ctx.scene, game.config.difficulty);
+
             for node in ctx
                 .scene
                 .graph
@@ -621,5 +1510,15 @@ impl ScriptTrait for Bot {
                 sound.set_gain(0.0);
             }
         }
+
+        if self.raises_alarm && !is_dead {
+            if let Some(position) = spotted_player {
+                if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+                    level
+                        .alarm
+                        .raise(self.alarm_duration, Some(position), ctx.message_sender);
+                }
+            }
+        }
     }
 }
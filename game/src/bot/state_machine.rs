@@ -1,7 +1,7 @@
 use crate::bot::MovementType;
 use fyrox::graph::SceneGraph;
 use fyrox::{
-    core::pool::Handle,
+    core::{log::Log, pool::Handle, some_or_return},
     scene::{animation::absm::prelude::*, animation::prelude::*, graph::Graph, node::Node, Scene},
 };
 
@@ -14,6 +14,10 @@ pub struct StateMachineInput {
     pub attack_animation_index: u32,
     pub aim: bool,
     pub badly_damaged: bool,
+    pub stagger: bool,
+    pub dodge: bool,
+    pub block: bool,
+    pub switch_weapon: bool,
     pub movement_type: MovementType,
 }
 
@@ -35,6 +39,13 @@ impl StateMachine {
     const LOWER_BODY_LAYER_INDEX: usize = 0;
     const UPPER_BODY_LAYER_INDEX: usize = 1;
 
+    /// States that are driven by the rule parameters of the same name. Older state machine assets
+    /// do not have them, and then the respective parameter has no effect. To support a state, add
+    /// it (with its animation) to every layer that should play it, along with a transition to it
+    /// that is activated when the rule is set, and a transition back that is activated when the
+    /// rule is reset.
+    const ACTION_STATES: [&'static str; 4] = ["Dodge", "Block", "Stagger", "SwitchWeapon"];
+
     pub fn new(machine_handle: Handle<Node>, graph: &Graph) -> Option<Self> {
         let absm = graph.try_get_of_type::<AnimationBlendingStateMachine>(machine_handle)?;
         let machine = absm.machine();
@@ -44,6 +55,19 @@ impl StateMachine {
 
         let attack_state = upper_body.find_state_by_name_ref("MeleeAttack")?.0;
 
+        for state in Self::ACTION_STATES {
+            if !machine
+                .layers()
+                .iter()
+                .any(|layer| layer.find_state_by_name_ref(state).is_some())
+            {
+                Log::warn(format!(
+                    "State machine {} has no {state} state, the {state} parameter has no effect!",
+                    graph[machine_handle].name()
+                ));
+            }
+        }
+
         Some(Self {
             attack_state,
             absm: machine_handle,
@@ -73,9 +97,28 @@ impl StateMachine {
             .set_parameter("Aim", Parameter::Rule(input.aim))
             .set_parameter("Dead", Parameter::Rule(input.dead))
             .set_parameter("WasHit", Parameter::Rule(input.badly_damaged))
+            .set_parameter("Stagger", Parameter::Rule(input.stagger))
+            .set_parameter("Dodge", Parameter::Rule(input.dodge))
+            .set_parameter("Block", Parameter::Rule(input.block))
+            .set_parameter("SwitchWeapon", Parameter::Rule(input.switch_weapon))
             .set_parameter("MovementType", Parameter::Index(input.movement_type as u32));
     }
 
+    /// Rewinds melee attack animations, so an interrupted attack starts over next time.
+    pub fn interrupt_attack(&self, graph: &mut Graph) {
+        let animation_player = some_or_return!(graph
+            .try_get_of_type::<AnimationBlendingStateMachine>(self.absm)
+            .map(|absm| absm.animation_player()));
+        let animation_player =
+            some_or_return!(graph.try_get_mut_of_type::<AnimationPlayer>(animation_player));
+        let animations = animation_player.animations_mut().get_value_mut_silent();
+        for attack_animation in self.attack_animations.iter() {
+            if let Some(animation) = animations.try_get_mut(*attack_animation) {
+                animation.rewind();
+            }
+        }
+    }
+
     pub fn fetch_layer<'a>(&self, graph: &'a Graph, idx: usize) -> Option<&'a MachineLayer> {
         graph
             .try_get_of_type::<AnimationBlendingStateMachine>(self.absm)
@@ -1,12 +1,14 @@
 use crate::{
     inventory::Inventory,
     level::{
+        hazard_zone::HazardZone,
         hit_box::{HitBox, HitBoxDamage, HitBoxHeal, HitBoxMessage, LimbType},
         item::ItemAction,
     },
     sound::{SoundKind, SoundManager},
+    status_effect::{StatusEffect, StatusEffects},
     utils,
-    weapon::{weapon_mut, WeaponMessage, WeaponMessageData},
+    weapon::{dropped::DroppedWeapon, weapon_mut, weapon_ref, WeaponMessage, WeaponMessageData},
     Item, Weapon,
 };
 use fyrox::{
@@ -16,7 +18,7 @@ use fyrox::{
         math::ray::Ray,
         pool::Handle,
         reflect::prelude::*,
-        some_or_continue,
+        some_or_continue, some_or_return,
         variable::InheritableVariable,
         visitor::prelude::*,
     },
@@ -27,6 +29,7 @@ use fyrox::{
         collider::Collider,
         graph::{physics::RayCastOptions, Graph},
         node::Node,
+        rigidbody::RigidBody,
         Scene,
     },
     script::{RoutingStrategy, ScriptContext, ScriptMessageSender},
@@ -56,6 +59,20 @@ impl DamageDealer {
         }
         None
     }
+
+    /// Returns `true` if the dealer is an ally of a character, that owns the given node (a hit box
+    /// or any other collider, for example). Damage to the dealer itself is not a friendly fire.
+    pub fn is_ally_of_owner(&self, node: Handle<Node>, graph: &Graph) -> bool {
+        let Some((dealer, dealer_character)) = self.as_character(graph) else {
+            return false;
+        };
+
+        graph
+            .find_up_map(node, &mut |n| n.try_get_script_component::<Character>())
+            .is_some_and(|(owner, owner_character)| {
+                owner != dealer && dealer_character.is_ally_of(owner_character)
+            })
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -73,6 +90,8 @@ pub enum CharacterMessageData {
     PickupItem(Handle<Node>),
     DropItems { item: ModelResource, count: u32 },
     UseItem { item: ModelResource },
+    ApplyStatusEffect(StatusEffect),
+    ClearStatusEffects,
 }
 
 #[derive(Debug)]
@@ -95,18 +114,67 @@ pub struct Character {
     pub punch_sounds: InheritableVariable<Vec<Handle<Node>>>,
     #[reflect(min_value = 0.0, max_value = 20.0)]
     melee_attack_damage: InheritableVariable<f32>,
+    #[reflect(
+        description = "Maximum amount of weapons the character can carry. Picking up a new weapon \
+        when all slots are taken will drop the current one. Zero means no limit."
+    )]
+    pub max_weapon_slots: InheritableVariable<u32>,
+    #[reflect(
+        description = "Team of the character. Characters of the same team are allies, they do \
+        not attack each other and cannot damage each other unless friendly fire is enabled. Zero \
+        means that the character does not belong to any team."
+    )]
+    pub team: InheritableVariable<u32>,
     #[visit(skip)]
     #[reflect(hidden)]
     pub hit_boxes: FxHashSet<Handle<Node>>,
     #[reflect(hidden)]
     #[visit(skip)]
     pub melee_attack_context: Option<MeleeAttackContext>,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    pub item_use_context: Option<ItemUseContext>,
+    // Damage from hazard zones that was accumulated, but not yet dealt.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    hazard_damage: f32,
+    #[reflect(hidden)]
+    pub status_effects: StatusEffects,
+    // A weapon that was current before the current one.
+    #[reflect(hidden)]
+    last_weapon: Handle<Node>,
+    // Time (in seconds) left until the current weapon is ready to shoot after switching weapons.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    switch_timer: f32,
 }
 
-#[derive(Default, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct MeleeAttackContext {
     pub damaged_hitboxes: FxHashSet<Handle<Node>>,
     pub damaged_characters: FxHashSet<Handle<Node>>,
+    /// Multiplier of the melee damage of the character, used to make hits of a combo attack
+    /// stronger or weaker.
+    pub damage_scale: f32,
+}
+
+impl Default for MeleeAttackContext {
+    fn default() -> Self {
+        Self {
+            damaged_hitboxes: Default::default(),
+            damaged_characters: Default::default(),
+            damage_scale: 1.0,
+        }
+    }
+}
+
+/// An item that is being used right now. The item is already taken from the inventory, its
+/// action will be applied when the timer runs out.
+#[derive(Clone, Debug)]
+pub struct ItemUseContext {
+    pub item: ModelResource,
+    pub timer: f32,
+    pub refund_on_interrupt: bool,
 }
 
 impl Default for Character {
@@ -123,11 +191,30 @@ impl Default for Character {
             attack_sounds: Default::default(),
             punch_sounds: Default::default(),
             melee_attack_damage: 20.0.into(),
+            max_weapon_slots: 0.into(),
+            team: 0.into(),
             melee_attack_context: None,
+            item_use_context: None,
+            hazard_damage: 0.0,
+            status_effects: Default::default(),
+            last_weapon: Default::default(),
+            switch_timer: 0.0,
         }
     }
 }
 
+fn weapon_holster_time(weapon: Handle<Node>, graph: &Graph) -> f32 {
+    graph
+        .try_get_script_component_of::<Weapon>(weapon)
+        .map_or(0.0, |weapon| *weapon.holster_time)
+}
+
+fn weapon_draw_time(weapon: Handle<Node>, graph: &Graph) -> f32 {
+    graph
+        .try_get_script_component_of::<Weapon>(weapon)
+        .map_or(0.0, |weapon| *weapon.draw_time)
+}
+
 fn parent_character(mut node_handle: Handle<Node>, graph: &Graph) -> Option<Handle<Node>> {
     while let Some(node) = graph.try_get(node_handle) {
         if node.try_get_script_component::<Character>().is_some() {
@@ -201,6 +288,15 @@ impl Character {
         }
     }
 
+    /// Returns a hit box that represents the body of the character or any other hit box if there's
+    /// no body.
+    pub fn body_hit_box(&self, graph: &Graph) -> Option<Handle<Node>> {
+        self.hit_box_iter(graph)
+            .find(|(_, h)| *h.limb_type == LimbType::Body)
+            .or_else(|| self.hit_box_iter(graph).next())
+            .map(|(handle, _)| handle)
+    }
+
     pub fn is_limb_sliced_off(&self, graph: &Graph, limb_type: LimbType) -> bool {
         self.hit_box_iter(graph)
             .any(|(_, hitbox)| *hitbox.limb_type == limb_type && hitbox.is_sliced_off())
@@ -253,14 +349,9 @@ impl Character {
     }
 
     pub fn add_weapon(&mut self, weapon: Handle<Node>, graph: &mut Graph) {
-        for &other_weapon in self.weapons.iter() {
-            graph[other_weapon].set_enabled(false);
-        }
-
-        self.current_weapon = self.weapons.len();
         self.weapons.push(weapon);
 
-        self.set_current_weapon_enabled(true, graph);
+        self.switch_to_weapon(self.weapons.len() - 1, graph);
     }
 
     pub fn use_item(
@@ -287,6 +378,134 @@ impl Character {
         }
     }
 
+    pub fn is_using_item(&self) -> bool {
+        self.item_use_context.is_some()
+    }
+
+    /// Starts using an item that was already taken from the inventory. Items without use time
+    /// are applied instantly.
+    pub fn begin_use_item(
+        &mut self,
+        item_resource: &ModelResource,
+        item: &Item,
+        graph: &Graph,
+        script_message_sender: &ScriptMessageSender,
+    ) {
+        if *item.use_time > 0.0 {
+            self.item_use_context = Some(ItemUseContext {
+                item: item_resource.clone(),
+                timer: *item.use_time,
+                refund_on_interrupt: *item.refund_on_interrupt,
+            });
+        } else {
+            self.use_item(item, graph, script_message_sender);
+        }
+    }
+
+    pub fn update_item_use(
+        &mut self,
+        graph: &Graph,
+        script_message_sender: &ScriptMessageSender,
+        dt: f32,
+    ) {
+        let context = some_or_return!(self.item_use_context.as_mut());
+
+        context.timer -= dt;
+        if context.timer <= 0.0 {
+            let item_resource = context.item.clone();
+            self.item_use_context = None;
+            Item::from_resource(&item_resource, |item| {
+                if let Some(item) = item {
+                    self.use_item(item, graph, script_message_sender);
+                }
+            });
+        }
+    }
+
+    /// Interrupts current item usage (if any). The item is either wasted or returned back to
+    /// the inventory, depending on its settings.
+    pub fn interrupt_item_use(&mut self) {
+        if let Some(context) = self.item_use_context.take() {
+            if context.refund_on_interrupt {
+                self.inventory.add_item(&context.item, 1);
+            }
+        }
+    }
+
+    /// Deals damage that does not come from any actor (hazards, status effects, etc.).
+    fn deal_environment_damage(
+        &self,
+        amount: f32,
+        graph: &Graph,
+        script_message_sender: &ScriptMessageSender,
+    ) {
+        if let Some(hit_box) = self.body_hit_box(graph) {
+            script_message_sender.send_hierarchical(
+                hit_box,
+                RoutingStrategy::Up,
+                HitBoxMessage::Damage(HitBoxDamage {
+                    hit_box,
+                    damage: amount,
+                    dealer: DamageDealer::default(),
+                    position: None,
+                    is_melee: false,
+                    is_critical: false,
+                }),
+            );
+        }
+    }
+
+    /// Applies damage-over-time and status effects of all hazard zones the character is in. The
+    /// damage is accumulated and dealt in portions to prevent flooding the character with tiny
+    /// hits every frame.
+    pub fn update_hazards(
+        &mut self,
+        hazard_zones: &FxHashSet<Handle<Node>>,
+        graph: &Graph,
+        script_message_sender: &ScriptMessageSender,
+        dt: f32,
+    ) {
+        const DAMAGE_PORTION: f32 = 5.0;
+
+        let position = self.position(graph);
+        let mut damage_per_second = 0.0;
+        for &zone in hazard_zones {
+            let hazard = some_or_continue!(graph.try_get_script_of::<HazardZone>(zone));
+            if hazard.is_instant_death() || !HazardZone::contains(graph, zone, position) {
+                continue;
+            }
+
+            damage_per_second += hazard.damage_per_second;
+            if let Some(effect) = hazard.status_effect.as_ref() {
+                self.status_effects.apply_effect(effect.clone());
+            }
+        }
+
+        if damage_per_second <= 0.0 {
+            self.hazard_damage = 0.0;
+            return;
+        }
+
+        self.hazard_damage += damage_per_second * dt;
+        if self.hazard_damage < DAMAGE_PORTION {
+            return;
+        }
+
+        self.deal_environment_damage(self.hazard_damage, graph, script_message_sender);
+        self.hazard_damage = 0.0;
+    }
+
+    pub fn update_status_effects(
+        &mut self,
+        graph: &Graph,
+        script_message_sender: &ScriptMessageSender,
+        dt: f32,
+    ) {
+        if let Some(damage) = self.status_effects.update(dt) {
+            self.deal_environment_damage(damage, graph, script_message_sender);
+        }
+    }
+
     pub fn on_weapon_message(&mut self, weapon_message: &WeaponMessage, graph: &mut Graph) {
         if let WeaponMessageData::Removed = weapon_message.data {
             let removed_weapon = weapon_message.weapon;
@@ -338,7 +557,7 @@ impl Character {
                     .try_get_script_of::<HitBox>(intersected_hit_box)
                     .is_none()
                 {
-                    return None;
+                    continue;
                 }
 
                 if self.hit_boxes.contains(&intersected_hit_box) {
@@ -356,6 +575,13 @@ impl Character {
                 // Do not over-damage characters.
                 if let Some(parent_character) = parent_character(intersected_hit_box, &scene.graph)
                 {
+                    // The rest of hits of a combo attack should not hit the corpse.
+                    if try_get_character_ref(parent_character, &scene.graph)
+                        .is_some_and(|character| character.is_dead(&scene.graph))
+                    {
+                        continue;
+                    }
+
                     if attack_context
                         .damaged_characters
                         .contains(&parent_character)
@@ -372,7 +598,7 @@ impl Character {
                     RoutingStrategy::Up,
                     HitBoxMessage::Damage(HitBoxDamage {
                         hit_box: intersected_hit_box,
-                        damage: *self.melee_attack_damage,
+                        damage: *self.melee_attack_damage * attack_context.damage_scale,
                         dealer: DamageDealer {
                             entity: self_handle,
                         },
@@ -381,6 +607,7 @@ impl Character {
                             direction: Vector3::new(0.0, 0.0, 1.0),
                         }),
                         is_melee: true,
+                        is_critical: false,
                     }),
                 );
             }
@@ -406,6 +633,10 @@ impl Character {
         sound_manager: &SoundManager,
     ) {
         match message_data {
+            CharacterMessageData::ApplyStatusEffect(effect) => {
+                self.status_effects.apply_effect(effect.clone())
+            }
+            CharacterMessageData::ClearStatusEffects => self.status_effects.clear_effects(),
             CharacterMessageData::SelectWeapon(weapon_resource) => {
                 self.select_weapon(weapon_resource.clone(), &mut scene.graph)
             }
@@ -414,19 +645,7 @@ impl Character {
 
                 if Weapon::is_weapon_resource(weapon_resource) {
                     let weapon = weapon_resource.instantiate(scene);
-
-                    let weapon_script = weapon_mut(weapon, &mut scene.graph);
-
-                    weapon_script.set_owner(self_handle);
-
-                    let inventory = self.inventory_mut();
-                    if !inventory.has_item(weapon_resource) {
-                        inventory.add_item(weapon_resource, 1)
-                    };
-
-                    self.add_weapon(weapon, &mut scene.graph);
-
-                    scene.graph.link_nodes(weapon, self.weapon_pivot());
+                    self.equip_weapon(weapon, weapon_resource, self_handle, &mut scene.graph);
                 } else {
                     Log::warn(format!(
                         "{} is not a weapon resource!",
@@ -440,11 +659,14 @@ impl Character {
                 let item = item_node.try_get_script_component::<Item>().unwrap();
                 let stack_size = *item.stack_size;
                 let position = item_node.global_position();
+                let is_dropped_weapon =
+                    DroppedWeapon::find_body(item_handle, &scene.graph).is_some();
+                // Dropped weapons are taken as is (with the rounds in their magazines), instead of
+                // creating new ones.
+                let mut taken = false;
 
                 if item_node.is_globally_enabled() {
                     if let Some(item_resource) = item_resource {
-                        self.inventory.add_item(&item_resource, stack_size);
-
                         // It might be a weapon-like item.
                         if Weapon::is_weapon_resource(&item_resource) {
                             let mut found_weapon = false;
@@ -456,16 +678,65 @@ impl Character {
                                     break;
                                 }
                             }
-                            if !found_weapon {
-                                // Finally if actor does not have such weapon, give new one to him.
-                                script_message_sender.send_to_target(
-                                    self_handle,
-                                    CharacterMessage {
-                                        character: self_handle,
-                                        data: CharacterMessageData::AddWeapon(item_resource),
-                                    },
-                                );
+                            if found_weapon {
+                                // Do not hoard the same weapons, take its ammo instead. Dropped
+                                // weapons give only the rounds that are left in their magazines.
+                                let ammo = if is_dropped_weapon {
+                                    let weapon = weapon_ref(item_handle, &scene.graph);
+                                    (*weapon.ammo_item).clone().map(|ammo_item| {
+                                        (
+                                            ammo_item,
+                                            weapon.rounds_in_magazine()
+                                                * *weapon.ammo_consumption_per_shot,
+                                        )
+                                    })
+                                } else {
+                                    Weapon::from_resource(&item_resource, |weapon| {
+                                        weapon.and_then(|weapon| {
+                                            (*weapon.ammo_item).clone().map(|ammo_item| {
+                                                (
+                                                    ammo_item,
+                                                    weapon.magazine_capacity()
+                                                        * *weapon.ammo_consumption_per_shot,
+                                                )
+                                            })
+                                        })
+                                    })
+                                };
+                                if let Some((ammo_item, count)) = ammo {
+                                    if count > 0 {
+                                        self.inventory.add_item(&ammo_item, count);
+                                    }
+                                }
+                            } else {
+                                if self.is_weapon_slots_full() {
+                                    self.drop_current_weapon(scene, position);
+                                }
+
+                                self.inventory.add_item(&item_resource, stack_size);
+
+                                if DroppedWeapon::try_take_weapon(item_handle, &mut scene.graph) {
+                                    taken = true;
+                                    self.equip_weapon(
+                                        item_handle,
+                                        &item_resource,
+                                        self_handle,
+                                        &mut scene.graph,
+                                    );
+                                } else {
+                                    // Finally if actor does not have such weapon, give new one to
+                                    // him.
+                                    script_message_sender.send_to_target(
+                                        self_handle,
+                                        CharacterMessage {
+                                            character: self_handle,
+                                            data: CharacterMessageData::AddWeapon(item_resource),
+                                        },
+                                    );
+                                }
                             }
+                        } else {
+                            self.inventory.add_item(&item_resource, stack_size);
                         }
                     }
 
@@ -473,12 +744,24 @@ impl Character {
                         &mut scene.graph,
                         "data/sounds/item_pickup.ogg",
                         position,
+                        self_handle,
                         1.0,
                         3.0,
                         2.0,
                     );
 
-                    scene.graph[item_handle].set_enabled(false);
+                    if !taken {
+                        if let Some(body) = DroppedWeapon::find_body(item_handle, &scene.graph) {
+                            // Dropped weapons never respawn.
+                            scene.graph.remove_node(body);
+                        } else {
+                            let item_node = &mut scene.graph[item_handle];
+                            item_node.set_enabled(false);
+                            if let Some(item) = item_node.try_get_script_component_mut::<Item>() {
+                                item.start_respawn();
+                            }
+                        }
+                    }
                 }
             }
             CharacterMessageData::DropItems { item, count } => {
@@ -486,14 +769,23 @@ impl Character {
                 let weapons = self.weapons().to_vec();
 
                 if self.inventory.try_extract_exact_items(item, *count) == *count {
-                    // Make sure to remove weapons associated with items.
+                    // Weapons associated with the items are dropped as is, so they keep the rounds
+                    // in their magazines.
+                    let mut dropped = false;
                     for &weapon in weapons.iter() {
                         if scene.graph[weapon].root_resource() == Some(item.clone()) {
-                            scene.graph.remove_node(weapon);
+                            if dropped {
+                                scene.graph.remove_node(weapon);
+                            } else {
+                                self.drop_weapon(weapon, drop_position, *count, &mut scene.graph);
+                                dropped = true;
+                            }
                         }
                     }
 
-                    Item::add_to_scene(scene, item.clone(), drop_position, true, *count);
+                    if !dropped {
+                        Item::add_to_scene(scene, item.clone(), drop_position, true, *count);
+                    }
                 }
             }
             CharacterMessageData::UseItem {
@@ -501,13 +793,20 @@ impl Character {
             } => {
                 Item::from_resource(item_resource, |item| {
                     if let Some(item) = item {
-                        if *item.consumable
-                            && self
-                                .inventory_mut()
-                                .try_extract_exact_items(item_resource, 1)
-                                == 1
-                        {
-                            self.use_item(item, &scene.graph, script_message_sender);
+                        if *item.consumable {
+                            if !self.is_using_item()
+                                && self
+                                    .inventory_mut()
+                                    .try_extract_exact_items(item_resource, 1)
+                                    == 1
+                            {
+                                self.begin_use_item(
+                                    item_resource,
+                                    item,
+                                    &scene.graph,
+                                    script_message_sender,
+                                );
+                            }
                         } else {
                             script_message_sender.send_to_target(
                                 self_handle,
@@ -524,19 +823,85 @@ impl Character {
         }
     }
 
+    pub fn is_weapon_slots_full(&self) -> bool {
+        *self.max_weapon_slots > 0 && self.weapons.len() >= *self.max_weapon_slots as usize
+    }
+
+    /// Gives the weapon to the character and makes it current.
+    fn equip_weapon(
+        &mut self,
+        weapon: Handle<Node>,
+        weapon_resource: &ModelResource,
+        self_handle: Handle<Node>,
+        graph: &mut Graph,
+    ) {
+        weapon_mut(weapon, graph).set_owner(self_handle);
+
+        let inventory = self.inventory_mut();
+        if !inventory.has_item(weapon_resource) {
+            inventory.add_item(weapon_resource, 1)
+        };
+
+        self.add_weapon(weapon, graph);
+
+        graph.link_nodes(weapon, self.weapon_pivot());
+    }
+
+    /// Removes the weapon from the character and drops it on the ground at the given position.
+    /// The weapon is thrown with the velocity of the character.
+    fn drop_weapon(
+        &mut self,
+        weapon: Handle<Node>,
+        position: Vector3<f32>,
+        stack_size: u32,
+        graph: &mut Graph,
+    ) {
+        let current_weapon = self.current_weapon();
+        if let Some(i) = self.weapons.iter().position(|&w| w == weapon) {
+            self.weapons.remove(i);
+            if current_weapon == weapon {
+                self.current_weapon = 0;
+                self.set_current_weapon_enabled(true, graph);
+            } else if i < self.current_weapon {
+                self.current_weapon -= 1;
+            }
+        }
+
+        if let Some(item) = graph[weapon].try_get_script_component_mut::<Item>() {
+            item.stack_size.set_value_and_mark_modified(stack_size);
+        }
+
+        let velocity = graph
+            .try_get_of_type::<RigidBody>(self.body)
+            .map(|body| body.lin_vel())
+            .unwrap_or_default();
+
+        DroppedWeapon::spawn(weapon, position, velocity, graph);
+    }
+
+    /// Removes current weapon from the character and drops it at the given position.
+    pub fn drop_current_weapon(&mut self, scene: &mut Scene, position: Vector3<f32>) {
+        let weapon = self.current_weapon();
+        let resource = some_or_return!(scene
+            .graph
+            .try_get(weapon)
+            .and_then(|weapon| weapon.root_resource()));
+
+        let count = self.inventory.item_count(&resource);
+        self.inventory.try_extract_exact_items(&resource, count);
+
+        self.drop_weapon(weapon, position, count.max(1), &mut scene.graph);
+        // The dropped weapon does not need to be holstered.
+        self.switch_timer = weapon_draw_time(self.current_weapon(), &scene.graph);
+    }
+
     pub fn select_weapon(&mut self, weapon: ModelResource, graph: &mut Graph) {
         if let Some(index) = self
             .weapons
             .iter()
             .position(|&w| graph[w].root_resource() == Some(weapon.clone()))
         {
-            for &other_weapon in self.weapons.iter() {
-                graph[other_weapon].set_enabled(false);
-            }
-
-            self.current_weapon = index;
-
-            self.set_current_weapon_enabled(true, graph);
+            self.switch_to_weapon(index, graph);
         }
     }
 
@@ -553,43 +918,81 @@ impl Character {
         }
     }
 
-    pub fn next_weapon(&mut self, graph: &mut Graph) {
-        if !self.weapons.is_empty() && (self.current_weapon) < self.weapons.len() - 1 {
-            self.set_current_weapon_enabled(false, graph);
+    /// Makes the weapon with the given index current. The new weapon cannot shoot until the
+    /// previous one is holstered and the new one is drawn.
+    fn switch_to_weapon(&mut self, index: usize, graph: &mut Graph) {
+        let previous_weapon = self.current_weapon();
 
-            self.current_weapon += 1;
+        for &weapon in self.weapons.iter() {
+            graph[weapon].set_enabled(false);
+        }
+
+        self.current_weapon = index;
 
-            self.set_current_weapon_enabled(true, graph);
+        self.set_current_weapon_enabled(true, graph);
+
+        let new_weapon = self.current_weapon();
+        if new_weapon != previous_weapon {
+            self.last_weapon = previous_weapon;
+            self.switch_timer =
+                weapon_holster_time(previous_weapon, graph) + weapon_draw_time(new_weapon, graph);
         }
     }
 
-    pub fn prev_weapon(&mut self, graph: &mut Graph) {
-        if self.current_weapon > 0 {
-            self.set_current_weapon_enabled(false, graph);
+    /// Returns an index of the weapon that was used before the current one. The weapon could be
+    /// dropped, so there could be no such weapon anymore.
+    fn last_weapon_index(&self) -> Option<usize> {
+        self.weapons
+            .iter()
+            .position(|&weapon| weapon == self.last_weapon)
+            .filter(|&index| index != self.current_weapon)
+    }
+
+    pub fn has_last_weapon(&self) -> bool {
+        self.last_weapon_index().is_some()
+    }
+
+    /// Switches back to the weapon that was used before the current one.
+    pub fn switch_to_last_weapon(&mut self, graph: &mut Graph) {
+        if let Some(index) = self.last_weapon_index() {
+            self.switch_to_weapon(index, graph);
+        }
+    }
 
-            self.current_weapon -= 1;
+    /// Returns `true` if both characters belong to the same team.
+    pub fn is_ally_of(&self, other: &Character) -> bool {
+        *self.team != 0 && *self.team == *other.team
+    }
 
-            self.set_current_weapon_enabled(true, graph);
+    pub fn is_switching_weapon(&self) -> bool {
+        self.switch_timer > 0.0
+    }
+
+    pub fn update_weapon_switch(&mut self, dt: f32) {
+        self.switch_timer = (self.switch_timer - dt).max(0.0);
+    }
+
+    pub fn next_weapon(&mut self, graph: &mut Graph) {
+        if !self.weapons.is_empty() && (self.current_weapon) < self.weapons.len() - 1 {
+            self.switch_to_weapon(self.current_weapon + 1, graph);
+        }
+    }
+
+    pub fn prev_weapon(&mut self, graph: &mut Graph) {
+        if self.current_weapon > 0 {
+            self.switch_to_weapon(self.current_weapon - 1, graph);
         }
     }
 
     pub fn use_first_weapon_or_none(&mut self, graph: &mut Graph) {
         if !self.weapons.is_empty() {
-            self.set_current_weapon_enabled(false, graph);
-
-            self.current_weapon = 0;
-
-            self.set_current_weapon_enabled(true, graph);
+            self.switch_to_weapon(0, graph);
         }
     }
 
     pub fn set_current_weapon(&mut self, i: usize, graph: &mut Graph) {
         if i < self.weapons.len() {
-            self.set_current_weapon_enabled(false, graph);
-
-            self.current_weapon = i;
-
-            self.set_current_weapon_enabled(true, graph);
+            self.switch_to_weapon(i, graph);
         }
     }
 
@@ -603,6 +1006,7 @@ impl Character {
 
     pub fn footstep_ray_check(
         &self,
+        self_handle: Handle<Node>,
         begin: Vector3<f32>,
         scene: &mut Scene,
         manager: &SoundManager,
@@ -631,6 +1035,7 @@ impl Character {
                 intersection.collider,
                 intersection.feature,
                 intersection.position.coords,
+                self_handle,
                 SoundKind::FootStep,
                 0.45,
                 1.0,
@@ -1,4 +1,4 @@
-use crate::control_scheme::ControlScheme;
+use crate::{control_scheme::ControlScheme, difficulty::Difficulty};
 use fyrox::{core::log::Log, core::visitor::prelude::*, renderer::QualitySettings};
 use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
@@ -61,12 +61,39 @@ impl Default for SoundConfig {
     }
 }
 
-#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+fn default_gore_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ConfigData {
     pub graphics: QualitySettings,
     pub controls: ControlScheme,
     pub sound: SoundConfig,
     pub show_debug_info: bool,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// If `false`, blood, dismemberment and other gore effects are replaced with neutral ones.
+    /// Does not affect damage.
+    #[serde(default = "default_gore_enabled")]
+    pub gore_enabled: bool,
+    /// If `false`, characters of the same team cannot damage each other.
+    #[serde(default)]
+    pub friendly_fire: bool,
+}
+
+impl Default for ConfigData {
+    fn default() -> Self {
+        Self {
+            graphics: Default::default(),
+            controls: Default::default(),
+            sound: Default::default(),
+            show_debug_info: false,
+            difficulty: Default::default(),
+            gore_enabled: default_gore_enabled(),
+            friendly_fire: false,
+        }
+    }
 }
 
 impl ConfigData {
@@ -43,9 +43,15 @@ pub struct ControlScheme {
     pub shoot: ControlButtonDefinition,
     pub next_weapon: ControlButtonDefinition,
     pub prev_weapon: ControlButtonDefinition,
+    #[serde(default = "default_last_weapon")]
+    pub last_weapon: ControlButtonDefinition,
     pub run: ControlButtonDefinition,
     pub aim: ControlButtonDefinition,
+    #[serde(default = "default_aim_down_sights")]
+    pub aim_down_sights: ControlButtonDefinition,
     pub toss_grenade: ControlButtonDefinition,
+    #[serde(default = "default_place_mine")]
+    pub place_mine: ControlButtonDefinition,
     pub journal: ControlButtonDefinition,
     pub flash_light: ControlButtonDefinition,
     pub grab_ak47: ControlButtonDefinition,
@@ -60,10 +66,52 @@ pub struct ControlScheme {
     pub cursor_left: ControlButtonDefinition,
     pub cursor_right: ControlButtonDefinition,
     pub quick_heal: ControlButtonDefinition,
+    #[serde(default = "default_reload")]
+    pub reload: ControlButtonDefinition,
+    #[serde(default = "default_crouch")]
+    pub crouch: ControlButtonDefinition,
     pub mouse_sens: f32,
     pub mouse_y_inverse: bool,
 }
 
+// Bindings that were added after the first release need defaults, otherwise existing settings
+// files will fail to load.
+
+fn default_reload() -> ControlButtonDefinition {
+    ControlButtonDefinition {
+        description: "Reload".to_string(),
+        button: ControlButton::Key(KeyCode::KeyT),
+    }
+}
+
+fn default_crouch() -> ControlButtonDefinition {
+    ControlButtonDefinition {
+        description: "Crouch".to_string(),
+        button: ControlButton::Key(KeyCode::KeyC),
+    }
+}
+
+fn default_aim_down_sights() -> ControlButtonDefinition {
+    ControlButtonDefinition {
+        description: "Aim Down Sights".to_string(),
+        button: ControlButton::Mouse(2),
+    }
+}
+
+fn default_last_weapon() -> ControlButtonDefinition {
+    ControlButtonDefinition {
+        description: "Last Weapon".to_string(),
+        button: ControlButton::Key(KeyCode::KeyX),
+    }
+}
+
+fn default_place_mine() -> ControlButtonDefinition {
+    ControlButtonDefinition {
+        description: "Place Mine".to_string(),
+        button: ControlButton::Key(KeyCode::KeyB),
+    }
+}
+
 impl Default for ControlScheme {
     fn default() -> Self {
         Self {
@@ -99,18 +147,22 @@ impl Default for ControlScheme {
                 description: "Previous Weapon".to_string(),
                 button: ControlButton::WheelDown,
             },
+            last_weapon: default_last_weapon(),
             run: ControlButtonDefinition {
                 description: "Run".to_string(),
                 button: ControlButton::Key(KeyCode::ShiftLeft),
             },
+            crouch: default_crouch(),
             aim: ControlButtonDefinition {
                 description: "Aim".to_string(),
                 button: ControlButton::Mouse(1),
             },
+            aim_down_sights: default_aim_down_sights(),
             toss_grenade: ControlButtonDefinition {
                 description: "Toss Grenade".to_string(),
                 button: ControlButton::Key(KeyCode::KeyG),
             },
+            place_mine: default_place_mine(),
             journal: ControlButtonDefinition {
                 description: "Journal".to_string(),
                 button: ControlButton::Key(KeyCode::KeyJ),
@@ -167,6 +219,7 @@ impl Default for ControlScheme {
                 description: "Quick Heal".to_string(),
                 button: ControlButton::Key(KeyCode::KeyQ),
             },
+            reload: default_reload(),
             mouse_sens: 0.3,
             mouse_y_inverse: false,
         }
@@ -174,7 +227,7 @@ impl Default for ControlScheme {
 }
 
 impl ControlScheme {
-    pub fn buttons_mut(&mut self) -> [&mut ControlButtonDefinition; 24] {
+    pub fn buttons_mut(&mut self) -> [&mut ControlButtonDefinition; 29] {
         [
             &mut self.move_forward,
             &mut self.move_backward,
@@ -184,12 +237,17 @@ impl ControlScheme {
             &mut self.drop_item,
             &mut self.jump,
             &mut self.shoot,
+            &mut self.reload,
             &mut self.next_weapon,
             &mut self.prev_weapon,
+            &mut self.last_weapon,
             &mut self.run,
+            &mut self.crouch,
             &mut self.aim,
+            &mut self.aim_down_sights,
             &mut self.inventory,
             &mut self.toss_grenade,
+            &mut self.place_mine,
             &mut self.journal,
             &mut self.flash_light,
             &mut self.grab_pistol,
@@ -203,7 +261,7 @@ impl ControlScheme {
         ]
     }
 
-    pub fn buttons(&self) -> [&ControlButtonDefinition; 24] {
+    pub fn buttons(&self) -> [&ControlButtonDefinition; 29] {
         [
             &self.move_forward,
             &self.move_backward,
@@ -213,12 +271,17 @@ impl ControlScheme {
             &self.drop_item,
             &self.jump,
             &self.shoot,
+            &self.reload,
             &self.next_weapon,
             &self.prev_weapon,
+            &self.last_weapon,
             &self.run,
+            &self.crouch,
             &self.aim,
+            &self.aim_down_sights,
             &self.inventory,
             &self.toss_grenade,
+            &self.place_mine,
             &self.journal,
             &self.flash_light,
             &self.grab_pistol,
@@ -0,0 +1,103 @@
+//! Global difficulty setting. Every difficulty level defines a set of multipliers that are used by
+//! various game entities.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl Difficulty {
+    /// Every difficulty level, from the easiest to the hardest one.
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+    ];
+
+    /// Human-readable name of the difficulty level.
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Nightmare => "Nightmare",
+        }
+    }
+
+    /// Position of the difficulty level in [`Self::ALL`].
+    pub fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|d| *d == self)
+            .unwrap_or_default()
+    }
+
+    /// Multiplier for damage that is dealt to the player.
+    pub fn player_damage_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+            Difficulty::Nightmare => 2.0,
+        }
+    }
+
+    /// Multiplier for health of bots. Damage that is dealt to bots is divided by this value.
+    pub fn bot_health_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+            Difficulty::Nightmare => 1.5,
+        }
+    }
+
+    /// Multiplier for reaction times of bots and turrets (time to select a target, time to
+    /// recover from a hit, etc.). Lower values mean faster reaction.
+    pub fn reaction_time_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.75,
+            Difficulty::Nightmare => 0.5,
+        }
+    }
+
+    /// Multiplier for the rate of passive health regeneration of the player. Regeneration is
+    /// disabled completely on the highest difficulty.
+    pub fn health_regen_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.5,
+            Difficulty::Nightmare => 0.0,
+        }
+    }
+
+    /// Multiplier for the amount of characters spawned by every spawn point.
+    pub fn bot_spawn_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.0,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+            Difficulty::Nightmare => 2.0,
+        }
+    }
+
+    /// Multiplier for probabilities of loot (ammo, med kits, etc.) drops.
+    pub fn loot_drop_multiplier(self) -> f32 {
+        match self {
+            Difficulty::Easy => 1.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 0.75,
+            Difficulty::Nightmare => 0.5,
+        }
+    }
+}
@@ -1,14 +1,22 @@
 use crate::{
-    character::try_get_character_ref, door::ui::DoorUi, inventory::Inventory, utils, Game,
+    character::{try_get_character_ref, DamageDealer},
+    door::ui::DoorUi,
+    inventory::Inventory,
+    level::{
+        hit_box::{HitBox, HitBoxDamage, HitBoxMessage},
+        item::{Item, KeyTier},
+        navmesh_blocker::BlockedRegion,
+    },
+    utils, Game,
 };
 use fyrox::{
     asset::{manager::ResourceManager, Resource},
     core::{
-        algebra::Vector3, pool::Handle, reflect::prelude::*, type_traits::prelude::*,
-        variable::InheritableVariable, visitor::prelude::*,
+        algebra::Vector3, pool::Handle, reflect::prelude::*, some_or_return,
+        type_traits::prelude::*, variable::InheritableVariable, visitor::prelude::*,
     },
     engine::GraphicsContext,
-    graph::SceneGraph,
+    graph::{BaseSceneGraph, SceneGraph},
     gui::UserInterface,
     material::{Material, MaterialResource, MaterialResourceExtension},
     resource::{
@@ -16,7 +24,10 @@ use fyrox::{
         texture::{Texture, TextureResource},
     },
     scene::{animation::absm::prelude::*, graph::Graph, mesh::Mesh, node::Node},
-    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+    script::{
+        RoutingStrategy, ScriptContext, ScriptDeinitContext, ScriptMessageContext,
+        ScriptMessagePayload, ScriptTrait,
+    },
 };
 
 pub mod ui;
@@ -37,6 +48,11 @@ pub struct Door {
     access_granted_sound: InheritableVariable<Handle<Node>>,
     access_denied_sound: InheritableVariable<Handle<Node>>,
     key_item: InheritableVariable<Option<ModelResource>>,
+    #[reflect(
+        description = "Minimal key card tier that unlocks the door. Any key card of the same or \
+        higher tier will open the door. `None` means that only the key item could open the door."
+    )]
+    required_key_tier: InheritableVariable<KeyTier>,
     pub locked: InheritableVariable<bool>,
     opened_state: InheritableVariable<String>,
     opening_state: InheritableVariable<String>,
@@ -45,6 +61,52 @@ pub struct Door {
     locked_state: InheritableVariable<String>,
     ui_resource: InheritableVariable<Option<Resource<UserInterface>>>,
 
+    #[reflect(
+        description = "Time (in seconds) after which an opened door will be closed even if there \
+        are actors nearby. The door won't be closed while someone is standing in the doorway and \
+        it won't open again for the actors nearby until they leave. Zero disables auto-closing."
+    )]
+    auto_close_delay: InheritableVariable<f32>,
+
+    #[reflect(hidden)]
+    auto_close_timer: f32,
+
+    // The door was closed by the timer, it ignores the actors that are still nearby.
+    #[reflect(hidden)]
+    auto_closed: bool,
+
+    #[reflect(hidden)]
+    is_opened: bool,
+
+    #[reflect(
+        description = "Amount of damage per second that will be dealt to every actor standing in \
+        the doorway while the door is closing."
+    )]
+    crush_damage: InheritableVariable<f32>,
+
+    #[reflect(
+        description = "Whether the door should open again if someone blocks it while closing."
+    )]
+    reverse_when_blocked: InheritableVariable<bool>,
+
+    #[reflect(hidden)]
+    is_closing: bool,
+
+    #[reflect(
+        description = "Whether the door could be broken by damage. Broken doors stay opened forever."
+    )]
+    breakable: InheritableVariable<bool>,
+
+    health: InheritableVariable<f32>,
+
+    break_sound: InheritableVariable<Handle<Node>>,
+
+    #[reflect(description = "A list of lights that will be disabled when the door is broken.")]
+    lights: InheritableVariable<Vec<Handle<Node>>>,
+
+    #[reflect(hidden)]
+    broken: bool,
+
     #[visit(skip)]
     #[reflect(hidden)]
     ui: Option<DoorUi>,
@@ -73,6 +135,7 @@ impl Default for Door {
             access_granted_sound: Default::default(),
             access_denied_sound: Default::default(),
             key_item: Default::default(),
+            required_key_tier: Default::default(),
             locked: Default::default(),
             opened_state: "Opened".to_string().into(),
             opening_state: "Open".to_string().into(),
@@ -80,6 +143,18 @@ impl Default for Door {
             closing_state: "Close".to_string().into(),
             locked_state: "Locked".to_string().into(),
             ui_resource: Default::default(),
+            auto_close_delay: 0.0.into(),
+            auto_close_timer: 0.0,
+            auto_closed: false,
+            is_opened: false,
+            crush_damage: 50.0.into(),
+            reverse_when_blocked: true.into(),
+            is_closing: false,
+            breakable: false.into(),
+            health: 300.0.into(),
+            break_sound: Default::default(),
+            lights: Default::default(),
+            broken: false,
             ui: Default::default(),
             initial_position: Default::default(),
             state_machine: Default::default(),
@@ -108,6 +183,9 @@ impl ScriptTrait for Door {
 
         self.initial_position = ctx.scene.graph[ctx.handle].global_position();
 
+        ctx.message_dispatcher
+            .subscribe_to::<HitBoxMessage>(ctx.handle);
+
         if let Some(ui_resource) = self.ui_resource.as_ref() {
             let ui = DoorUi::new(ui_resource.data_ref().clone());
             self.apply_screen_texture(
@@ -131,26 +209,100 @@ impl ScriptTrait for Door {
             {
                 level.doors_container.doors.remove(position);
             }
+
+            level.navmesh_blockers.remove(ctx.node_handle);
+        }
+    }
+
+    fn on_message(
+        &mut self,
+        message: &mut dyn ScriptMessagePayload,
+        ctx: &mut ScriptMessageContext,
+    ) {
+        if let HitBoxMessage::Damage(hit_box_damage) =
+            some_or_return!(message.downcast_ref::<HitBoxMessage>())
+        {
+            if *self.breakable && !self.broken {
+                // Weak spots (hinges, locks, etc.) could be made with hit boxes of higher damage
+                // factor.
+                let damage_factor = ctx
+                    .scene
+                    .graph
+                    .try_get_script_of::<HitBox>(hit_box_damage.hit_box)
+                    .map_or(1.0, |hit_box| *hit_box.damage_factor);
+                *self.health -= hit_box_damage.damage * damage_factor;
+            }
         }
     }
 
     fn on_update(&mut self, ctx: &mut ScriptContext) {
+        if *self.breakable && !self.broken && *self.health <= 0.0 {
+            self.break_door(&mut ctx.scene.graph);
+        }
+
         let game = ctx.plugins.get_mut::<Game>();
-        let level = game.level.as_ref().unwrap();
-
-        let mut closest_actor = None;
-        let someone_nearby = level.actors.iter().any(|a| {
-            if let Some(actor) = try_get_character_ref(*a, &ctx.scene.graph) {
-                let actor_position = actor.position(&ctx.scene.graph);
-                let close_enough = actor_position.metric_distance(&self.initial_position) < 1.25;
-                if close_enough {
-                    closest_actor = Some(a);
-                }
-                close_enough
-            } else {
-                false
+        let level = game.level.as_mut().unwrap();
+
+        // Locked doors block the doorway for navmesh agents. Bots do not route around blocked
+        // doorways, instead they stop in front of them and pick another behavior.
+        level.navmesh_blockers.set_blocked(
+            ctx.handle,
+            BlockedRegion {
+                center: self.initial_position,
+                radius: Self::NAVMESH_BLOCK_RADIUS,
+            },
+            self.is_blocking_navmesh(),
+        );
+
+        let someone_nearby = level
+            .actor_grid
+            .query_radius(self.initial_position, 1.25)
+            .next()
+            .is_some();
+
+        let actors_in_doorway = level
+            .actor_grid
+            .query_radius(self.initial_position, Self::DOORWAY_RADIUS)
+            .filter_map(|a| {
+                try_get_character_ref(a, &ctx.scene.graph)?.body_hit_box(&ctx.scene.graph)
+            })
+            .collect::<Vec<_>>();
+        let someone_in_doorway = !actors_in_doorway.is_empty();
+
+        if self.is_closing && !self.broken && *self.crush_damage > 0.0 {
+            for &hit_box in actors_in_doorway.iter() {
+                ctx.message_sender.send_hierarchical(
+                    hit_box,
+                    RoutingStrategy::Up,
+                    HitBoxMessage::Damage(HitBoxDamage {
+                        hit_box,
+                        damage: *self.crush_damage * ctx.dt,
+                        dealer: DamageDealer { entity: ctx.handle },
+                        position: None,
+                        is_melee: false,
+                        is_critical: false,
+                    }),
+                );
+            }
+
+            if someone_in_doorway && *self.reverse_when_blocked {
+                self.open_request = Some(OpenRequest { open: true });
             }
-        });
+        }
+
+        if self.is_opened && self.auto_close_timer > 0.0 {
+            self.auto_close_timer -= ctx.dt;
+        }
+
+        // Pretend that there's nobody nearby, so the state machine will close the door. The door
+        // stays closed until the actors nearby leave, otherwise it would be opened again at once.
+        if self.is_opened && *self.auto_close_delay > 0.0 && self.auto_close_timer <= 0.0 {
+            self.auto_closed = true;
+        } else if !someone_nearby {
+            self.auto_closed = false;
+        }
+        let someone_nearby =
+            self.broken || someone_in_doorway || (someone_nearby && !self.auto_closed);
 
         if let Some(state_machine) = ctx
             .scene
@@ -161,11 +313,11 @@ impl ScriptTrait for Door {
 
             let machine = state_machine.machine_mut().get_value_mut_silent();
             machine
-                .set_parameter("Locked", Parameter::Rule(*self.locked))
+                .set_parameter("Locked", Parameter::Rule(*self.locked && !self.broken))
                 .set_parameter("SomeoneNearby", Parameter::Rule(someone_nearby))
                 .set_parameter(
                     "Open",
-                    Parameter::Rule(open_request.as_ref().is_some_and(|r| r.open)),
+                    Parameter::Rule(self.broken || open_request.as_ref().is_some_and(|r| r.open)),
                 );
 
             let mut sound = Handle::NONE;
@@ -175,6 +327,13 @@ impl ScriptTrait for Door {
                     if let Event::ActiveStateChanged { new, .. } = event {
                         let new_state_name = layer.state(new).name.as_str();
 
+                        self.is_opened = new_state_name == self.opened_state.as_str();
+                        self.is_closing = new_state_name == self.closing_state.as_str();
+                        if self.is_opened {
+                            self.auto_close_timer = *self.auto_close_delay;
+                            self.auto_closed = false;
+                        }
+
                         if new_state_name == self.opening_state.as_str() {
                             sound = *self.open_sound;
                         } else if new_state_name == self.closing_state.as_str() {
@@ -187,7 +346,9 @@ impl ScriptTrait for Door {
                     let mut can_interact = false;
                     let mut locked = false;
                     let text;
-                    if current_state.name == self.opening_state.as_str() {
+                    if self.broken {
+                        text = "Broken";
+                    } else if current_state.name == self.opening_state.as_str() {
                         text = "Opening...";
                     } else if current_state.name == self.opened_state.as_str() {
                         text = "Opened";
@@ -239,6 +400,37 @@ impl ScriptTrait for Door {
 }
 
 impl Door {
+    const DOORWAY_RADIUS: f32 = 0.5;
+    const NAVMESH_BLOCK_RADIUS: f32 = 1.0;
+
+    fn break_door(&mut self, graph: &mut Graph) {
+        self.broken = true;
+        self.locked.set_value_and_mark_modified(false);
+
+        for &light in self.lights.iter() {
+            if let Some(light) = graph.try_get_mut(light) {
+                light.set_enabled(false);
+            }
+        }
+
+        utils::try_play_sound(*self.break_sound, graph);
+    }
+
+    /// Returns `true` if the door is locked and closed. Broken doors stay opened forever, so they
+    /// never block anything.
+    pub fn is_blocking_navmesh(&self) -> bool {
+        *self.locked && !self.is_opened && !self.broken
+    }
+
+    /// Checks whether the door could be opened by someone with the given inventory.
+    pub fn can_be_opened_with(&self, inventory: &Inventory) -> bool {
+        !*self.locked || self.has_key(inventory)
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.broken
+    }
+
     pub fn initial_position(&self) -> Vector3<f32> {
         self.initial_position
     }
@@ -270,17 +462,39 @@ impl Door {
         }
     }
 
+    fn has_key(&self, inventory: &Inventory) -> bool {
+        if let Some(key_item) = self.key_item.as_ref() {
+            if inventory.item_count(key_item) > 0 {
+                return true;
+            }
+        }
+
+        let required_tier = *self.required_key_tier;
+        if required_tier == KeyTier::None {
+            return false;
+        }
+
+        inventory.items().iter().any(|entry| {
+            entry.amount > 0
+                && entry.resource.as_ref().is_some_and(|resource| {
+                    Item::from_resource(resource, |item| {
+                        item.is_some_and(|item| *item.key_tier >= required_tier)
+                    })
+                })
+        })
+    }
+
+    pub fn unlock(&mut self) {
+        self.locked.set_value_and_mark_modified(false);
+    }
+
     pub fn try_open(&mut self, inventory: Option<&Inventory>) {
         let mut open = false;
 
         if *self.locked {
-            if let Some(inventory) = inventory {
-                if let Some(key_item) = self.key_item.as_ref() {
-                    if inventory.item_count(key_item) > 0 {
-                        open = true;
-                        self.locked.set_value_and_mark_modified(false);
-                    }
-                }
+            if inventory.is_some_and(|inventory| self.has_key(inventory)) {
+                open = true;
+                self.locked.set_value_and_mark_modified(false);
             }
         } else {
             open = true;
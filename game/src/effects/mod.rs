@@ -1,4 +1,6 @@
 //! Procedural visual effects.
 
 pub mod beam;
+pub mod muzzle_flash;
 pub mod rail;
+pub mod trail;
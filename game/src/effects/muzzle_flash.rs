@@ -0,0 +1,84 @@
+//! Short-lived light that illuminates surroundings on shot.
+
+use fyrox::{
+    core::{
+        algebra::Vector3, color::Color, pool::Handle, reflect::prelude::*, type_traits::prelude::*,
+        visitor::prelude::*,
+    },
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        light::{point::PointLightBuilder, BaseLight, BaseLightBuilder},
+        node::Node,
+        transform::TransformBuilder,
+    },
+    script::{ScriptContext, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "141417a5-7f07-45aa-9ef6-8aac96f891af")]
+#[visit(optional)]
+pub struct MuzzleFlash {
+    intensity: f32,
+    duration: f32,
+    timer: f32,
+}
+
+impl Default for MuzzleFlash {
+    fn default() -> Self {
+        Self {
+            intensity: 2.0,
+            duration: 0.06,
+            timer: 0.0,
+        }
+    }
+}
+
+impl ScriptTrait for MuzzleFlash {
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        self.timer += ctx.dt;
+
+        if self.timer >= self.duration {
+            ctx.scene.graph.remove_node(ctx.handle);
+            return;
+        }
+
+        // Fast quadratic decay.
+        let k = 1.0 - self.timer / self.duration;
+        if let Some(light) = ctx.scene.graph[ctx.handle].component_mut::<BaseLight>() {
+            light.set_intensity(self.intensity * k * k);
+        }
+    }
+}
+
+impl MuzzleFlash {
+    pub fn spawn(
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        color: Color,
+        intensity: f32,
+        radius: f32,
+        duration: f32,
+    ) -> Handle<Node> {
+        PointLightBuilder::new(
+            BaseLightBuilder::new(
+                BaseBuilder::new()
+                    .with_local_transform(
+                        TransformBuilder::new()
+                            .with_local_position(position)
+                            .build(),
+                    )
+                    .with_script(MuzzleFlash {
+                        intensity,
+                        duration,
+                        timer: 0.0,
+                    }),
+            )
+            .with_color(color)
+            .with_intensity(intensity)
+            .cast_shadows(false),
+        )
+        .with_radius(radius)
+        .build(graph)
+    }
+}
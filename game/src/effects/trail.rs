@@ -0,0 +1,192 @@
+//! A trail that is left by fast-moving projectiles. The trail is a single mesh with a cross-shaped
+//! ribbon that goes through the recent positions of the projectile. The ribbon narrows down over
+//! its lifetime and the trail removes itself once all of its points have faded out.
+
+use fyrox::{
+    core::{
+        algebra::{Vector2, Vector3},
+        color::Color,
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        visitor::prelude::*,
+    },
+    graph::SceneGraph,
+    material::{Material, MaterialResource, MaterialResourceExtension},
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        mesh::{
+            buffer::{TriangleBuffer, TriangleDefinition, VertexBuffer},
+            surface::{SurfaceBuilder, SurfaceData, SurfaceResource},
+            vertex::StaticVertex,
+            Mesh, MeshBuilder, RenderPath,
+        },
+        node::Node,
+    },
+    script::{ScriptContext, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Default, Debug, Clone)]
+struct TrailPoint {
+    position: Vector3<f32>,
+    age: f32,
+}
+
+#[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "ea3bca1e-77c8-4324-b241-856740824da0")]
+#[visit(optional)]
+pub struct Trail {
+    width: f32,
+    lifetime: f32,
+    #[reflect(hidden)]
+    points: Vec<TrailPoint>,
+}
+
+impl Default for Trail {
+    fn default() -> Self {
+        Self {
+            width: 0.015,
+            lifetime: 0.25,
+            points: Default::default(),
+        }
+    }
+}
+
+impl ScriptTrait for Trail {
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        for point in self.points.iter_mut() {
+            point.age += ctx.dt;
+        }
+        let lifetime = self.lifetime;
+        self.points.retain(|point| point.age < lifetime);
+
+        // The owner is gone (or stopped) and the trail has faded out completely.
+        if self.points.is_empty() {
+            ctx.scene.graph.remove_node(ctx.handle);
+            return;
+        }
+
+        if let Some(mesh) = ctx.scene.graph.try_get_mut_of_type::<Mesh>(ctx.handle) {
+            if let Some(surface) = mesh.surfaces().first() {
+                self.write_geometry(&mut surface.data_ref().data_ref());
+            }
+        }
+    }
+}
+
+impl Trail {
+    /// Spawns a new trail that starts with a segment between the two given points.
+    pub fn spawn(
+        graph: &mut Graph,
+        begin: Vector3<f32>,
+        end: Vector3<f32>,
+        color: Color,
+        width: f32,
+        lifetime: f32,
+    ) -> Handle<Node> {
+        if lifetime <= 0.0 {
+            return Handle::NONE;
+        }
+
+        let mut material = Material::standard();
+        material.set_property("diffuseColor", color);
+
+        let trail = Trail {
+            width,
+            lifetime,
+            points: vec![
+                TrailPoint {
+                    position: begin,
+                    age: 0.0,
+                },
+                TrailPoint {
+                    position: end,
+                    age: 0.0,
+                },
+            ],
+        };
+
+        let mut data = SurfaceData::new(
+            VertexBuffer::new::<StaticVertex>(0, Vec::new()).unwrap(),
+            TriangleBuffer::default(),
+        );
+        trail.write_geometry(&mut data);
+
+        // Vertices are in world space and the geometry changes every frame, so the bounds of the
+        // mesh cannot be used for culling.
+        MeshBuilder::new(
+            BaseBuilder::new()
+                .with_frustum_culling(false)
+                .with_script(trail),
+        )
+        .with_surfaces(vec![SurfaceBuilder::new(SurfaceResource::new_embedded(
+            data,
+        ))
+        .with_material(MaterialResource::new(material))
+        .build()])
+        .with_render_path(RenderPath::Forward)
+        .build(graph)
+    }
+
+    /// Adds a new point to the given trail. Returns `false` if there is no such trail (for
+    /// example, it has already faded out), so a new one should be spawned.
+    pub fn add_point(graph: &mut Graph, trail: Handle<Node>, position: Vector3<f32>) -> bool {
+        match graph.try_get_script_of_mut::<Trail>(trail) {
+            Some(trail) => {
+                trail.points.push(TrailPoint { position, age: 0.0 });
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Rebuilds the ribbon in place, the buffers keep their memory between frames.
+    fn write_geometry(&self, data: &mut SurfaceData) {
+        let mut vertices = data.vertex_buffer.modify();
+        vertices.clear();
+        let mut triangles = data.geometry_buffer.modify();
+        triangles.clear();
+
+        for (i, point) in self.points.iter().enumerate() {
+            let next = self.points.get(i + 1).unwrap_or(point);
+            let prev = i
+                .checked_sub(1)
+                .and_then(|i| self.points.get(i))
+                .unwrap_or(point);
+            let direction = (next.position - prev.position)
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(Vector3::z);
+
+            // Two perpendicular strips, so the trail is visible from any side.
+            let side = direction
+                .cross(&Vector3::y())
+                .try_normalize(f32::EPSILON)
+                .unwrap_or_else(Vector3::x);
+            let up = side.cross(&direction);
+
+            let half_width = 0.5 * self.width * (1.0 - point.age / self.lifetime).max(0.0);
+            let v = i as f32;
+            for (offset, normal) in [(side, up), (up, side)] {
+                for (k, u) in [(-1.0, 0.0), (1.0, 1.0)] {
+                    let _ = vertices.push_vertex(&StaticVertex::from_pos_uv_normal(
+                        point.position + offset.scale(k * half_width),
+                        Vector2::new(u, v),
+                        normal,
+                    ));
+                }
+            }
+
+            if i > 0 {
+                let current = 4 * i as u32;
+                let previous = current - 4;
+                for strip in [0, 2] {
+                    let (a, b) = (previous + strip, previous + strip + 1);
+                    let (c, d) = (current + strip, current + strip + 1);
+                    triangles.push(TriangleDefinition([a, b, d]));
+                    triangles.push(TriangleDefinition([a, d, c]));
+                }
+            }
+        }
+    }
+}
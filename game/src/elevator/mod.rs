@@ -1,30 +1,108 @@
-use crate::Game;
+use crate::{character::try_get_character_ref, Game};
 use fyrox::{
-    core::{pool::Handle, reflect::prelude::*, type_traits::prelude::*, visitor::prelude::*},
-    scene::{node::Node, rigidbody::RigidBody},
+    core::{
+        algebra::Vector3, pool::Handle, reflect::prelude::*, some_or_return,
+        type_traits::prelude::*, visitor::prelude::*,
+    },
+    graph::{BaseSceneGraph, SceneGraph},
+    scene::{collider::Collider, graph::Graph, node::Node, rigidbody::RigidBody},
     script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
 };
 
 pub mod call_button;
 pub mod ui;
 
-#[derive(Visit, Reflect, Default, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
 #[type_uuid(id = "67904c1b-0d12-427c-a92e-e66cb0ec6dae")]
 #[visit(optional)]
 pub struct Elevator {
     pub current_floor: u32,
     pub dest_floor: u32,
     k: f32,
+    #[reflect(description = "Travel points of the elevator. Every point is a separate floor.")]
     pub point_handles: Vec<Handle<Node>>,
     pub call_buttons: Vec<Handle<Node>>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Movement speed of the elevator (in meters per second). Zero means that \
+    every trip takes two seconds regardless of the distance between the floors."
+    )]
+    pub speed: f32,
+    #[reflect(
+        description = "Whether the elevator is controlled by call buttons. If not set, the \
+    elevator moves to the next travel point as soon as someone steps on it."
+    )]
+    pub call_button_activated: bool,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    has_riders: bool,
+}
+
+impl Default for Elevator {
+    fn default() -> Self {
+        Self {
+            current_floor: 0,
+            dest_floor: 0,
+            k: 0.0,
+            point_handles: Default::default(),
+            call_buttons: Default::default(),
+            speed: 0.0,
+            call_button_activated: true,
+            has_riders: false,
+        }
+    }
 }
 
 impl Elevator {
+    // Fraction of a trip (per second) that is passed when the speed is not set.
+    const FIXED_TRIP_RATE: f32 = 0.5;
+
     pub fn call_to(&mut self, floor: u32) {
         if floor < self.point_handles.len() as u32 {
             self.dest_floor = floor;
         }
     }
+
+    pub fn is_moving(&self) -> bool {
+        self.current_floor != self.dest_floor
+    }
+
+    fn next_floor(&self) -> u32 {
+        let count = self.point_handles.len() as u32;
+        if count == 0 {
+            0
+        } else {
+            (self.current_floor + 1) % count
+        }
+    }
+
+    fn point_position(&self, floor: u32, graph: &Graph) -> Option<Vector3<f32>> {
+        self.point_handles
+            .get(floor as usize)
+            .and_then(|h| graph.try_get(*h))
+            .map(|n| n.global_position())
+    }
+}
+
+/// Checks whether the given collider stands on any collider of the elevator.
+fn is_standing_on(collider: Handle<Node>, elevator: Handle<Node>, graph: &Graph) -> bool {
+    let Some(collider_ref) = graph.try_get(collider).and_then(|n| n.cast::<Collider>()) else {
+        return false;
+    };
+
+    collider_ref.contacts(&graph.physics).any(|contact| {
+        let other = if contact.collider1 == collider {
+            contact.collider2
+        } else {
+            contact.collider1
+        };
+
+        graph.try_get(other).is_some_and(|n| n.parent() == elevator)
+            && contact
+                .manifolds
+                .iter()
+                .any(|m| m.local_n1.y.abs() > 0.7 || m.local_n2.y.abs() > 0.7)
+    })
 }
 
 impl ScriptTrait for Elevator {
@@ -46,9 +124,43 @@ impl ScriptTrait for Elevator {
         }
     }
 
-    fn on_update(&mut self, context: &mut ScriptContext) {
-        if self.current_floor != self.dest_floor {
-            self.k += 0.5 * context.dt;
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        let level = some_or_return!(ctx.plugins.get::<Game>().level.as_ref());
+
+        // Collect bodies of every actor that stands on the elevator, they will be moved together
+        // with the elevator.
+        let riders = level
+            .actors
+            .iter()
+            .filter_map(|a| try_get_character_ref(*a, &ctx.scene.graph))
+            .filter(|c| is_standing_on(c.capsule_collider, ctx.handle, &ctx.scene.graph))
+            .map(|c| c.body)
+            .collect::<Vec<_>>();
+
+        let has_riders = !riders.is_empty();
+        if !self.call_button_activated && has_riders && !self.has_riders && !self.is_moving() {
+            self.call_to(self.next_floor());
+        }
+        self.has_riders = has_riders;
+
+        let graph = &mut ctx.scene.graph;
+
+        let (Some(current_pos), Some(dest_pos)) = (
+            self.point_position(self.current_floor, graph),
+            self.point_position(self.dest_floor, graph),
+        ) else {
+            return;
+        };
+
+        if self.is_moving() {
+            let distance = current_pos.metric_distance(&dest_pos);
+            if self.speed <= 0.0 {
+                self.k += Self::FIXED_TRIP_RATE * ctx.dt;
+            } else if distance > f32::EPSILON {
+                self.k += self.speed * ctx.dt / distance;
+            } else {
+                self.k = 1.0;
+            }
 
             if self.k >= 1.0 {
                 self.current_floor = self.dest_floor;
@@ -56,17 +168,29 @@ impl ScriptTrait for Elevator {
             }
         }
 
-        if let (Some(current), Some(dest)) = (
-            self.point_handles.get(self.current_floor as usize),
-            self.point_handles.get(self.dest_floor as usize),
-        ) {
-            let current_pos = context.scene.graph[*current].global_position();
-            let dest_pos = context.scene.graph[*dest].global_position();
-            if let Some(rigid_body_ref) =
-                context.scene.graph[context.handle].cast_mut::<RigidBody>()
-            {
-                let position = current_pos.lerp(&dest_pos, self.k);
-                rigid_body_ref.local_transform_mut().set_position(position);
+        let position = if self.is_moving() {
+            current_pos.lerp(&dest_pos, self.k)
+        } else {
+            dest_pos
+        };
+
+        let Some(rigid_body_ref) = graph[ctx.handle].cast_mut::<RigidBody>() else {
+            return;
+        };
+
+        let prev_position = **rigid_body_ref.local_transform().position();
+        rigid_body_ref.local_transform_mut().set_position(position);
+
+        // Move the riders by the same offset as the elevator to prevent them from sliding off or
+        // bouncing on the elevator.
+        let offset = position - prev_position;
+        if offset.norm_squared() > 0.0 {
+            for rider in riders {
+                if let Some(body) = graph.try_get_mut(rider) {
+                    let rider_position = **body.local_transform().position();
+                    body.local_transform_mut()
+                        .set_position(rider_position + offset);
+                }
             }
         }
     }
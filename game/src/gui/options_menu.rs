@@ -1,6 +1,7 @@
 use crate::{
     config::Config,
     control_scheme::ControlButton,
+    difficulty::Difficulty,
     gui::{create_check_box, create_scroll_bar, ScrollBarData},
     message::Message,
     MessageSender,
@@ -65,6 +66,9 @@ pub struct OptionsMenu {
     point_shadows_quality: Handle<UiNode>,
     spot_shadows_quality: Handle<UiNode>,
     show_debug_info: Handle<UiNode>,
+    gore_enabled: Handle<UiNode>,
+    friendly_fire: Handle<UiNode>,
+    difficulty: Handle<UiNode>,
     font: FontResource,
 }
 
@@ -146,10 +150,11 @@ fn make_video_mode_item_raw(
     .build(ctx)
 }
 
-fn make_shadows_quality_drop_down(
+fn make_drop_down(
     ctx: &mut BuildContext,
     font: FontResource,
     row: usize,
+    items: &[&str],
     current: usize,
 ) -> Handle<UiNode> {
     DropdownListBuilder::new(
@@ -159,7 +164,7 @@ fn make_shadows_quality_drop_down(
             .with_margin(Thickness::uniform(1.0)),
     )
     .with_items({
-        ["Low", "Medium", "High", "Ultra"]
+        items
             .iter()
             .map(|o| {
                 DecoratorBuilder::new(BorderBuilder::new(
@@ -181,6 +186,15 @@ fn make_shadows_quality_drop_down(
     .build(ctx)
 }
 
+fn make_shadows_quality_drop_down(
+    ctx: &mut BuildContext,
+    font: FontResource,
+    row: usize,
+    current: usize,
+) -> Handle<UiNode> {
+    make_drop_down(ctx, font, row, &["Low", "Medium", "High", "Ultra"], current)
+}
+
 fn shadows_quality(size: usize) -> usize {
     if size < 256 {
         0
@@ -231,6 +245,9 @@ impl OptionsMenu {
         let point_shadows_quality;
         let spot_shadows_quality;
         let show_debug_info;
+        let gore_enabled;
+        let friendly_fire;
+        let difficulty;
 
         let font = engine
             .resource_manager
@@ -380,6 +397,27 @@ impl OptionsMenu {
                 .with_child({
                     show_debug_info = create_check_box(ctx, 12, 1, config.show_debug_info);
                     show_debug_info
+                })
+                .with_child(make_text_mark("Show Gore", font.clone(), 13, ctx))
+                .with_child({
+                    gore_enabled = create_check_box(ctx, 13, 1, config.gore_enabled);
+                    gore_enabled
+                })
+                .with_child(make_text_mark("Friendly Fire", font.clone(), 14, ctx))
+                .with_child({
+                    friendly_fire = create_check_box(ctx, 14, 1, config.friendly_fire);
+                    friendly_fire
+                })
+                .with_child(make_text_mark("Difficulty", font.clone(), 15, ctx))
+                .with_child({
+                    difficulty = make_drop_down(
+                        ctx,
+                        font.clone(),
+                        15,
+                        &Difficulty::ALL.map(Difficulty::name),
+                        config.difficulty.index(),
+                    );
+                    difficulty
                 }),
         )
         .add_row(common_row)
@@ -395,6 +433,9 @@ impl OptionsMenu {
         .add_row(common_row)
         .add_row(common_row)
         .add_row(common_row)
+        .add_row(common_row)
+        .add_row(common_row)
+        .add_row(common_row)
         .add_column(Column::strict(270.0))
         .add_column(Column::stretch())
         .build(ctx);
@@ -622,6 +663,9 @@ impl OptionsMenu {
             ssao,
             spot_shadows_quality,
             show_debug_info,
+            gore_enabled,
+            friendly_fire,
+            difficulty,
             font,
         }
     }
@@ -663,11 +707,19 @@ impl OptionsMenu {
         sync_check_box(self.mouse_y_inverse, config.controls.mouse_y_inverse);
         sync_check_box(self.use_hrtf, config.sound.use_hrtf);
         sync_check_box(self.show_debug_info, config.show_debug_info);
+        sync_check_box(self.gore_enabled, config.gore_enabled);
+        sync_check_box(self.friendly_fire, config.friendly_fire);
 
         sync_scroll_bar(self.mouse_sens, config.controls.mouse_sens);
         sync_scroll_bar(self.sound_volume, config.sound.master_volume);
         sync_scroll_bar(self.music_volume, config.sound.music_volume);
 
+        ui.send_message(DropdownListMessage::selection(
+            self.difficulty,
+            MessageDirection::ToWidget,
+            Some(config.difficulty.index()),
+        ));
+
         for (btn, def) in self
             .control_scheme_buttons
             .iter()
@@ -842,6 +894,10 @@ impl OptionsMenu {
                 } else {
                     graphics_settings.point_shadow_map_precision = ShadowMapPrecision::Half;
                 }
+            } else if message.destination() == self.difficulty {
+                if let Some(difficulty) = Difficulty::ALL.get(*index) {
+                    sender.send(Message::SetDifficulty(*difficulty));
+                }
             }
         } else if let Some(CheckBoxMessage::Check(value)) = message.data() {
             let value = value.unwrap_or(false);
@@ -865,6 +921,10 @@ impl OptionsMenu {
                 sender.send(Message::SetUseHrtf(value));
             } else if message.destination() == self.show_debug_info {
                 config.show_debug_info = value;
+            } else if message.destination() == self.gore_enabled {
+                config.gore_enabled = value;
+            } else if message.destination() == self.friendly_fire {
+                config.friendly_fire = value;
             }
         } else if let Some(ButtonMessage::Click) = message.data() {
             if message.destination() == self.reset_control_scheme {
@@ -9,7 +9,7 @@ use fyrox::{
         image::ImageBuilder,
         message::MessageDirection,
         text::{TextBuilder, TextMessage},
-        widget::WidgetBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
         UiNode, UserInterface, VerticalAlignment,
     },
     resource::texture::{Texture, TextureResource},
@@ -23,11 +23,20 @@ pub struct WeaponDisplay {
     pub render_target: TextureResource,
     ammo: Handle<UiNode>,
     grenades: Handle<UiNode>,
+    head_shot_marker: Handle<UiNode>,
+    head_shot_marker_timer: f32,
+    low_ammo_warning_timer: f32,
 }
 
 impl WeaponDisplay {
     pub const WIDTH: f32 = 120.0;
     pub const HEIGHT: f32 = 120.0;
+    /// Time (in seconds) during which the head shot marker is visible.
+    const HEAD_SHOT_MARKER_DURATION: f32 = 1.0;
+    /// Time (in seconds) during which the ammo counter flashes after the low ammo warning.
+    const LOW_AMMO_WARNING_DURATION: f32 = 1.5;
+    const AMMO_COLOR: Color = Color::opaque(0, 162, 232);
+    const LOW_AMMO_COLOR: Color = Color::opaque(232, 40, 40);
 
     pub fn new(font: FontResource, resource_manager: ResourceManager) -> Self {
         let mut ui = UserInterface::new(Vector2::new(Self::WIDTH, Self::HEIGHT));
@@ -36,6 +45,7 @@ impl WeaponDisplay {
 
         let ammo;
         let grenades;
+        let head_shot_marker;
         GridBuilder::new(
             WidgetBuilder::new()
                 .with_width(Self::WIDTH)
@@ -57,7 +67,7 @@ impl WeaponDisplay {
                     ammo = TextBuilder::new(
                         WidgetBuilder::new()
                             .with_vertical_alignment(VerticalAlignment::Center)
-                            .with_foreground(Brush::Solid(Color::opaque(0, 162, 232)).into())
+                            .with_foreground(Brush::Solid(Self::AMMO_COLOR).into())
                             .on_row(0)
                             .on_column(1),
                     )
@@ -87,10 +97,25 @@ impl WeaponDisplay {
                             .on_row(1)
                             .on_column(1),
                     )
-                    .with_font(font)
+                    .with_font(font.clone())
                     .with_font_size(31.0.into())
                     .build(&mut ui.build_ctx());
                     grenades
+                })
+                .with_child({
+                    head_shot_marker = TextBuilder::new(
+                        WidgetBuilder::new()
+                            .with_visibility(false)
+                            .with_vertical_alignment(VerticalAlignment::Center)
+                            .with_foreground(Brush::Solid(Color::opaque(232, 40, 40)).into())
+                            .on_row(2)
+                            .on_column(1),
+                    )
+                    .with_text("HEADSHOT")
+                    .with_font(font)
+                    .with_font_size(20.0.into())
+                    .build(&mut ui.build_ctx());
+                    head_shot_marker
                 }),
         )
         .add_column(Column::auto())
@@ -105,31 +130,55 @@ impl WeaponDisplay {
             render_target,
             ammo,
             grenades,
+            head_shot_marker,
+            head_shot_marker_timer: 0.0,
+            low_ammo_warning_timer: 0.0,
         }
     }
 
+    pub fn flash_low_ammo_warning(&mut self) {
+        self.low_ammo_warning_timer = Self::LOW_AMMO_WARNING_DURATION;
+    }
+
+    fn set_ammo_color(&self, color: Color) {
+        self.ui.send_message(WidgetMessage::foreground(
+            self.ammo,
+            MessageDirection::ToWidget,
+            Brush::Solid(color).into(),
+        ));
+    }
+
+    pub fn show_head_shot_marker(&mut self) {
+        self.head_shot_marker_timer = Self::HEAD_SHOT_MARKER_DURATION;
+        self.ui.send_message(WidgetMessage::visibility(
+            self.head_shot_marker,
+            MessageDirection::ToWidget,
+            true,
+        ));
+    }
+
     pub fn sync_to_model(&self, player: &Player, graph: &Graph) {
         let ammo = if let Some(weapon) =
             graph.try_get_script_component_of::<Weapon>(player.current_weapon())
         {
-            if let Some(ammo_item) = weapon.ammo_item.as_ref() {
-                let total_ammo = player.inventory().item_count(ammo_item);
-                total_ammo / *weapon.ammo_consumption_per_shot
+            let reserve = weapon.reserve_rounds(player.inventory());
+            if weapon.is_melee() {
+                "-".to_string()
+            } else if weapon.is_reloading() {
+                "...".to_string()
+            } else if reserve == u32::MAX {
+                format!("{}/INF", weapon.rounds_in_magazine())
             } else {
-                u32::MAX
+                format!("{}/{reserve}", weapon.rounds_in_magazine())
             }
         } else {
-            0
+            "0".to_string()
         };
 
         self.ui.send_message(TextMessage::text(
             self.ammo,
             MessageDirection::ToWidget,
-            if ammo == u32::MAX {
-                "INF".to_string()
-            } else {
-                format!("{ammo}")
-            },
+            ammo,
         ));
 
         if let Some(grenade_item) = player.grenade_item.as_ref() {
@@ -143,6 +192,29 @@ impl WeaponDisplay {
     }
 
     pub fn update(&mut self, delta: f32) {
+        if self.low_ammo_warning_timer > 0.0 {
+            self.low_ammo_warning_timer -= delta;
+            let color = if self.low_ammo_warning_timer > 0.0
+                && (self.low_ammo_warning_timer * 4.0).fract() < 0.5
+            {
+                Self::LOW_AMMO_COLOR
+            } else {
+                Self::AMMO_COLOR
+            };
+            self.set_ammo_color(color);
+        }
+
+        if self.head_shot_marker_timer > 0.0 {
+            self.head_shot_marker_timer -= delta;
+            if self.head_shot_marker_timer <= 0.0 {
+                self.ui.send_message(WidgetMessage::visibility(
+                    self.head_shot_marker,
+                    MessageDirection::ToWidget,
+                    false,
+                ));
+            }
+        }
+
         self.ui.update(
             Vector2::new(WeaponDisplay::WIDTH, WeaponDisplay::HEIGHT),
             delta,
@@ -58,6 +58,34 @@ impl Inventory {
         0
     }
 
+    /// Extracts the exact amount of items, that satisfy the given predicate. Items could be taken
+    /// from multiple entries, nothing is extracted if there's not enough items.
+    pub fn try_extract_exact_items_by<P>(&mut self, mut predicate: P, amount: u32) -> u32
+    where
+        P: FnMut(&ModelResource) -> bool,
+    {
+        if self.item_count_by(&mut predicate) < amount {
+            return 0;
+        }
+
+        let mut left = amount;
+        for item in self.items.iter_mut() {
+            if left == 0 {
+                break;
+            }
+
+            if item.resource.as_ref().is_some_and(&mut predicate) {
+                let taken = item.amount.min(left);
+                item.amount -= taken;
+                left -= taken;
+            }
+        }
+
+        self.items.retain(|i| i.amount > 0);
+
+        amount
+    }
+
     pub fn items(&self) -> &[ItemEntry] {
         &self.items
     }
@@ -70,6 +98,18 @@ impl Inventory {
         }
     }
 
+    /// Total amount of items, that satisfy the given predicate.
+    pub fn item_count_by<P>(&self, mut predicate: P) -> u32
+    where
+        P: FnMut(&ModelResource) -> bool,
+    {
+        self.items
+            .iter()
+            .filter(|i| i.resource.as_ref().is_some_and(&mut predicate))
+            .map(|i| i.amount)
+            .sum()
+    }
+
     pub fn has_item(&self, item: &ModelResource) -> bool {
         self.item_count(item) != 0
     }
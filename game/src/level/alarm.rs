@@ -0,0 +1,167 @@
+//! Station-wide alarm. When the alarm is raised, turrets warm up faster, bots investigate the last
+//! known position of the player and emergency lighting engages. Entities that react to the alarm
+//! subscribe to [`AlarmEvent`], or check [`Alarm::is_active`] of the level.
+
+use crate::{level::hit_box::HitBoxMessage, Game};
+use fyrox::{
+    core::{
+        algebra::Vector3, log::Log, reflect::prelude::*, some_or_return, type_traits::prelude::*,
+        variable::InheritableVariable, visitor::prelude::*,
+    },
+    resource::model::{ModelResource, ModelResourceExtension},
+    script::{
+        ScriptContext, ScriptMessageContext, ScriptMessagePayload, ScriptMessageSender, ScriptTrait,
+    },
+};
+
+/// A global event, that is sent when the alarm is raised or cleared.
+#[derive(Debug, Clone)]
+pub struct AlarmEvent {
+    pub active: bool,
+    /// Last known position of the player at the moment the alarm was raised (if known).
+    pub position: Option<Vector3<f32>>,
+}
+
+/// State of the station-wide alarm of a level.
+#[derive(Default, Debug)]
+pub struct Alarm {
+    // Time (in seconds) left until the alarm goes off, `None` means that the alarm is not active.
+    timer: Option<f32>,
+    // The alarm cannot be raised anymore, when its console is destroyed.
+    disabled: bool,
+    sender: Option<ScriptMessageSender>,
+}
+
+impl Alarm {
+    pub fn is_active(&self) -> bool {
+        self.timer.is_some()
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    /// Raises the alarm for the given duration (in seconds). Zero duration means that the alarm
+    /// lasts until it is disabled. Raising an active alarm prolongs it.
+    pub fn raise(
+        &mut self,
+        duration: f32,
+        position: Option<Vector3<f32>>,
+        sender: &ScriptMessageSender,
+    ) {
+        if self.disabled {
+            return;
+        }
+
+        let duration = if duration > 0.0 {
+            duration
+        } else {
+            f32::INFINITY
+        };
+        let was_active = self.is_active();
+        self.timer = Some(self.timer.map_or(duration, |timer| timer.max(duration)));
+        self.sender = Some(sender.clone());
+
+        if !was_active {
+            Log::info("Alarm was raised!");
+            sender.send_global(AlarmEvent {
+                active: true,
+                position,
+            });
+        }
+    }
+
+    /// Turns the alarm off.
+    pub fn clear(&mut self) {
+        if self.timer.take().is_some() {
+            Log::info("Alarm was cleared.");
+            if let Some(sender) = self.sender.as_ref() {
+                sender.send_global(AlarmEvent {
+                    active: false,
+                    position: None,
+                });
+            }
+        }
+    }
+
+    /// Turns the alarm off and prevents it from being raised again.
+    pub fn disable(&mut self) {
+        self.clear();
+        self.disabled = true;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        if let Some(timer) = self.timer.as_mut() {
+            *timer -= dt;
+            if *timer <= 0.0 {
+                self.clear();
+            }
+        }
+    }
+}
+
+/// A destructible console, that controls the alarm. Destroying the console turns the alarm off for
+/// good. Damage is received via hit boxes that are attached to the console.
+#[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "0f6a4c2e-93b7-4d1a-8e5f-7c2b9d4a1e63")]
+#[visit(optional)]
+pub struct AlarmConsole {
+    health: InheritableVariable<f32>,
+    #[reflect(description = "A prefab that is instantiated when the console is destroyed.")]
+    destruction_prefab: InheritableVariable<Option<ModelResource>>,
+    #[reflect(hidden)]
+    destroyed: bool,
+}
+
+impl Default for AlarmConsole {
+    fn default() -> Self {
+        Self {
+            health: 50.0.into(),
+            destruction_prefab: Default::default(),
+            destroyed: false,
+        }
+    }
+}
+
+impl ScriptTrait for AlarmConsole {
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        ctx.message_dispatcher
+            .subscribe_to::<HitBoxMessage>(ctx.handle);
+
+        // The alarm is not saved, disable it again when a level with destroyed console is loaded.
+        if self.destroyed {
+            if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+                level.alarm.disable();
+            }
+        }
+    }
+
+    fn on_message(
+        &mut self,
+        message: &mut dyn ScriptMessagePayload,
+        _ctx: &mut ScriptMessageContext,
+    ) {
+        if let HitBoxMessage::Damage(hit_box_damage) =
+            some_or_return!(message.downcast_ref::<HitBoxMessage>())
+        {
+            *self.health -= hit_box_damage.damage;
+        }
+    }
+
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        if self.destroyed || *self.health > 0.0 {
+            return;
+        }
+
+        self.destroyed = true;
+
+        if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+            level.alarm.disable();
+        }
+
+        if let Some(destruction_prefab) = self.destruction_prefab.as_ref() {
+            let position = ctx.scene.graph[ctx.handle].global_position();
+            destruction_prefab.instantiate_at(ctx.scene, position, Default::default());
+        }
+    }
+}
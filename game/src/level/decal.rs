@@ -1,4 +1,4 @@
-use fyrox::graph::BaseSceneGraph;
+use fyrox::graph::{BaseSceneGraph, SceneGraph};
 use fyrox::{
     asset::manager::ResourceManager,
     core::{
@@ -12,7 +12,11 @@ use fyrox::{
     },
     resource::texture::{Texture, TextureResource},
     scene::{
-        base::BaseBuilder, decal::DecalBuilder, graph::Graph, node::Node,
+        base::BaseBuilder,
+        collider::ColliderShape,
+        decal::DecalBuilder,
+        graph::{physics::RayCastOptions, Graph},
+        node::Node,
         transform::TransformBuilder,
     },
     script::{ScriptContext, ScriptTrait},
@@ -24,6 +28,17 @@ use fyrox::{
 pub struct Decal {
     lifetime: f32,
     fade_interval: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) during which the decal grows up to its full size. Zero \
+        means that the decal appears at full size."
+    )]
+    growth_time: f32,
+    // Full size of a growing decal.
+    #[reflect(hidden)]
+    full_scale: Vector3<f32>,
+    #[reflect(hidden)]
+    growth_timer: f32,
 }
 
 impl Default for Decal {
@@ -31,6 +46,9 @@ impl Default for Decal {
         Self {
             lifetime: 10.0,
             fade_interval: 1.0,
+            growth_time: 0.0,
+            full_scale: Vector3::repeat(1.0),
+            growth_timer: 0.0,
         }
     }
 }
@@ -49,6 +67,15 @@ impl ScriptTrait for Decal {
 
         let decal_node = ctx.scene.graph[ctx.handle].as_decal_mut();
 
+        if self.growth_timer < self.growth_time {
+            self.growth_timer = (self.growth_timer + ctx.dt).min(self.growth_time);
+            // Fast at the beginning and slow at the end, like a spreading liquid.
+            let k = 1.0 - (1.0 - self.growth_timer / self.growth_time).powi(2);
+            decal_node
+                .local_transform_mut()
+                .set_scale(self.full_scale.scale(k.max(0.01)));
+        }
+
         decal_node.set_color(decal_node.color().with_new_alpha((255.0 * alpha) as u8));
 
         if self.lifetime < 0.0 && abs_lifetime > self.fade_interval {
@@ -58,6 +85,11 @@ impl ScriptTrait for Decal {
 }
 
 impl Decal {
+    /// Makes the decal fade out right now, regardless of its remaining lifetime.
+    pub fn fade_out(&mut self) {
+        self.lifetime = self.lifetime.min(0.0);
+    }
+
     pub fn spawn(
         graph: &mut Graph,
         position: Vector3<f32>,
@@ -136,4 +168,93 @@ impl Decal {
             resource_manager.request::<Texture>("data/textures/decals/BulletImpact_BaseColor.png"),
         )
     }
+
+    /// Creates a pool of blood under a corpse. The pool is placed on the floor below the given
+    /// position (colliders of the corpse are ignored) and grows up to its full size over a few
+    /// seconds. Returns [`Handle::NONE`] if there's no floor below the position.
+    pub fn new_blood_pool(
+        resource_manager: &ResourceManager,
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        corpse: Handle<Node>,
+    ) -> Handle<Node> {
+        const SIZE: f32 = 1.2;
+        const GROWTH_TIME: f32 = 4.0;
+        const LIFETIME: f32 = 60.0;
+
+        let mut intersections = Vec::new();
+        graph.physics.cast_ray(
+            RayCastOptions {
+                // Lift the ray a bit, the position could be slightly below the floor.
+                ray_origin: Point3::from(position + Vector3::new(0.0, 0.5, 0.0)),
+                ray_direction: -Vector3::y(),
+                max_len: 3.0,
+                groups: Default::default(),
+                sort_results: true,
+            },
+            &mut intersections,
+        );
+
+        let is_part_of_corpse = |mut handle: Handle<Node>| {
+            while let Some(node) = graph.try_get(handle) {
+                if handle == corpse {
+                    return true;
+                }
+                handle = node.parent();
+            }
+            false
+        };
+
+        let Some(floor) = intersections.iter().find(|i| {
+            !matches!(
+                graph[i.collider].as_collider().shape(),
+                ColliderShape::Capsule(_)
+            ) && !is_part_of_corpse(i.collider)
+        }) else {
+            return Handle::NONE;
+        };
+
+        let decal = Self::spawn(
+            graph,
+            floor.position.coords,
+            floor.normal,
+            floor.collider,
+            Color::opaque(70, 0, 0),
+            Vector3::repeat(SIZE * 0.01),
+            resource_manager.request::<Texture>("data/textures/decals/BulletImpact_BaseColor.png"),
+        );
+
+        // Discard floor's scale, the same as `spawn` does for the initial scale.
+        let full_scale = Vector3::repeat(SIZE).component_div(&graph.global_scale(floor.collider));
+        if let Some(script) = graph.try_get_script_of_mut::<Decal>(decal) {
+            script.lifetime = LIFETIME;
+            script.growth_time = GROWTH_TIME;
+            script.full_scale = full_scale;
+        }
+
+        decal
+    }
+
+    /// Creates a scorch mark left by an explosion. Its size depends on the radius of the
+    /// explosion.
+    pub fn new_scorch_mark(
+        resource_manager: &ResourceManager,
+        graph: &mut Graph,
+        position: Vector3<f32>,
+        face_towards: Vector3<f32>,
+        parent: Handle<Node>,
+        radius: f32,
+    ) -> Handle<Node> {
+        let size = (radius * 0.5).clamp(0.2, 2.0);
+
+        Self::spawn(
+            graph,
+            position,
+            face_towards,
+            parent,
+            Color::opaque(10, 10, 10),
+            Vector3::new(size, size, size),
+            resource_manager.request::<Texture>("data/textures/decals/BulletImpact_BaseColor.png"),
+        )
+    }
 }
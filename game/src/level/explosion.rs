@@ -13,7 +13,7 @@ use fyrox::{
         variable::InheritableVariable,
         visitor::prelude::*,
     },
-    graph::{SceneGraph, SceneGraphNode},
+    graph::{BaseSceneGraph, SceneGraph, SceneGraphNode},
     scene::rigidbody::RigidBody,
     script::{RoutingStrategy, ScriptContext, ScriptTrait},
 };
@@ -61,8 +61,12 @@ impl ScriptTrait for Explosion {
             let game = ctx.plugins.get::<Game>();
             let level = game.level.as_ref().unwrap();
 
-            for &hit_box in level.hit_boxes.iter() {
-                let hit_box_ref = &ctx.scene.graph[hit_box];
+            let radius = (aabb.max - aabb.min).norm() * 0.5;
+            for hit_box in level.hit_box_grid.query_radius(center, radius) {
+                // The grid is rebuilt once per frame, so it could contain removed hit boxes.
+                let Some(hit_box_ref) = ctx.scene.graph.try_get(hit_box) else {
+                    continue;
+                };
                 let position = hit_box_ref.global_position();
                 let direction = hit_box_ref.global_position() - center;
                 if aabb.is_contains_point(position) {
@@ -78,6 +82,7 @@ impl ScriptTrait for Explosion {
                                 direction,
                             }),
                             is_melee: false,
+                            is_critical: false,
                         }),
                     );
                 }
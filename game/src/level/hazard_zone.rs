@@ -0,0 +1,92 @@
+//! Hazard zones are volumes that damage actors inside them. The size of a zone is defined by the
+//! bounding box of its node (usually a mesh or a collider).
+
+use crate::{status_effect::StatusEffect, Game};
+use fyrox::script::ScriptDeinitContext;
+use fyrox::{
+    core::{
+        algebra::Vector3, pool::Handle, reflect::prelude::*, stub_uuid_provider,
+        type_traits::prelude::*, visitor::prelude::*,
+    },
+    graph::BaseSceneGraph,
+    scene::{graph::Graph, node::Node},
+    script::{ScriptContext, ScriptTrait},
+};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+#[derive(
+    Default, Copy, Clone, PartialEq, Eq, Debug, Visit, Reflect, AsRefStr, EnumString, VariantNames,
+)]
+pub enum HazardKind {
+    /// Kills everything that gets inside, regardless of the damage rate.
+    #[default]
+    InstantDeath,
+    ToxicGas,
+    Fire,
+    Electricity,
+}
+
+stub_uuid_provider!(HazardKind);
+
+// The zone was called `DeathZone` before, the id is kept to stay compatible with existing levels.
+#[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "9c258713-e44e-4366-a236-f91e09c6f0aa")]
+#[visit(optional)]
+pub struct HazardZone {
+    pub kind: HazardKind,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of damage per second that will be dealt to every actor inside the \
+        zone. Ignored by instant death zones."
+    )]
+    pub damage_per_second: f32,
+    #[reflect(
+        description = "A status effect that will be applied to every actor inside the zone. The \
+        effect lasts for its duration after the actor leaves the zone."
+    )]
+    pub status_effect: Option<StatusEffect>,
+}
+
+impl Default for HazardZone {
+    fn default() -> Self {
+        Self {
+            kind: Default::default(),
+            damage_per_second: 10.0,
+            status_effect: None,
+        }
+    }
+}
+
+impl HazardZone {
+    pub fn is_instant_death(&self) -> bool {
+        self.kind == HazardKind::InstantDeath
+    }
+
+    pub fn contains(graph: &Graph, zone: Handle<Node>, point: Vector3<f32>) -> bool {
+        graph
+            .try_get(zone)
+            .is_some_and(|node| node.world_bounding_box().is_contains_point(point))
+    }
+}
+
+impl ScriptTrait for HazardZone {
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        ctx.plugins
+            .get_mut::<Game>()
+            .level
+            .as_mut()
+            .unwrap()
+            .hazard_zones
+            .insert(ctx.handle);
+    }
+
+    fn on_deinit(&mut self, ctx: &mut ScriptDeinitContext) {
+        ctx.plugins
+            .get_mut::<Game>()
+            .level
+            .as_mut()
+            .unwrap()
+            .hazard_zones
+            .remove(&ctx.node_handle);
+    }
+}
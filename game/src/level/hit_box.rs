@@ -1,10 +1,14 @@
 use crate::{
-    character::{DamageDealer, DamagePosition},
+    bot::Bot,
+    character::{try_get_character_ref, DamageDealer, DamagePosition},
+    level::hazard_zone::HazardZone,
+    player::Player,
     Game,
 };
 use fyrox::{
     core::{
         algebra::{Point3, Vector3},
+        log::Log,
         math::vector_to_quat,
         pool::Handle,
         reflect::prelude::*,
@@ -12,7 +16,8 @@ use fyrox::{
         variable::InheritableVariable,
         visitor::prelude::*,
     },
-    graph::SceneGraph,
+    graph::{BaseSceneGraph, SceneGraph},
+    lazy_static::lazy_static,
     resource::model::{ModelResource, ModelResourceExtension},
     scene::{
         collider::{Collider, ColliderShape},
@@ -25,6 +30,8 @@ use fyrox::{
         ScriptMessagePayload, ScriptTrait,
     },
 };
+use serde::Deserialize;
+use std::fs::File;
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 #[derive(Debug, Clone)]
@@ -34,6 +41,39 @@ pub struct HitBoxDamage {
     pub dealer: DamageDealer,
     pub position: Option<DamagePosition>,
     pub is_melee: bool,
+    pub is_critical: bool,
+}
+
+/// A global script message that is sent every time when a hit box receives damage. Subscribe to
+/// it (`message_dispatcher.subscribe_to::<DamageEvent>(..)`) to show floating damage numbers,
+/// kill feeds, etc.
+#[derive(Debug, Clone)]
+pub struct DamageEvent {
+    /// An actor that owns the damaged hit box. Could be [`Handle::NONE`] if the hit box does not
+    /// belong to an actor (doors, turrets, etc.).
+    pub victim: Handle<Node>,
+    /// An actor that dealt the damage. Could be [`Handle::NONE`] for environmental damage.
+    pub shooter: Handle<Node>,
+    pub hit_box: Handle<Node>,
+    /// Actual amount of damage that was applied to the hit box.
+    pub amount: f32,
+    pub is_critical: bool,
+    pub position: Vector3<f32>,
+}
+
+/// A global script message that is sent when an actor is killed. Subscribe to it
+/// (`message_dispatcher.subscribe_to::<KillEvent>(..)`) to show kill confirmations, kill feeds,
+/// etc.
+#[derive(Debug, Clone)]
+pub struct KillEvent {
+    pub victim: Handle<Node>,
+    /// An actor that dealt the lethal damage. Could be [`Handle::NONE`] for environmental damage.
+    pub killer: Handle<Node>,
+    /// A hit box that received the lethal damage.
+    pub hit_box: Handle<Node>,
+    /// `true` if the lethal damage was dealt to the head of the victim.
+    pub is_head_shot: bool,
+    pub position: Vector3<f32>,
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +85,9 @@ pub struct HitBoxHeal {
 #[derive(Debug)]
 pub enum HitBoxMessage {
     Damage(HitBoxDamage),
+    /// Damage that is passed from a hit box to its [`HitBox::children_hit_boxes`]. It does not
+    /// produce a [`DamageEvent`], because the damage is already reported by the parent hit box.
+    PropagatedDamage(HitBoxDamage),
     Heal(HitBoxHeal),
 }
 
@@ -80,6 +123,84 @@ impl LimbType {
     }
 }
 
+/// Damage multipliers for every limb type. Multipliers are loaded from an optional config file, so
+/// they could be tuned without recompilation. Every multiplier is neutral (one) by default.
+#[derive(Deserialize, Debug, Clone)]
+pub struct LimbDamageMultipliers {
+    pub head: f32,
+    pub body: f32,
+    pub arm: f32,
+    pub leg: f32,
+}
+
+impl Default for LimbDamageMultipliers {
+    fn default() -> Self {
+        Self {
+            head: 1.0,
+            body: 1.0,
+            arm: 1.0,
+            leg: 1.0,
+        }
+    }
+}
+
+impl LimbDamageMultipliers {
+    const PATH: &'static str = "data/configs/limb_damage.ron";
+
+    fn load() -> Self {
+        let Ok(file) = File::open(Self::PATH) else {
+            Log::info(format!(
+                "Unable to open {}, default limb damage multipliers will be used.",
+                Self::PATH
+            ));
+            return Self::default();
+        };
+
+        match ron::de::from_reader::<_, Self>(file) {
+            Ok(mut multipliers) => {
+                multipliers.validate();
+                multipliers
+            }
+            Err(err) => {
+                Log::err(format!(
+                    "Unable to parse {}: {err}. Default limb damage multipliers will be used.",
+                    Self::PATH
+                ));
+                Self::default()
+            }
+        }
+    }
+
+    fn validate(&mut self) {
+        for (name, value) in [
+            ("head", &mut self.head),
+            ("body", &mut self.body),
+            ("arm", &mut self.arm),
+            ("leg", &mut self.leg),
+        ] {
+            if *value < 0.0 {
+                Log::warn(format!(
+                    "Damage multiplier for {name} is negative ({value}), it will be set to zero."
+                ));
+                *value = 0.0;
+            }
+        }
+    }
+
+    pub fn get(&self, limb_type: LimbType) -> f32 {
+        match limb_type {
+            LimbType::Head => self.head,
+            LimbType::Body => self.body,
+            LimbType::Arm => self.arm,
+            LimbType::Leg => self.leg,
+        }
+    }
+}
+
+lazy_static! {
+    pub static ref LIMB_DAMAGE_MULTIPLIERS: LimbDamageMultipliers = LimbDamageMultipliers::load();
+}
+
 #[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
 #[type_uuid(id = "28a107ad-ee88-4a0f-8b32-be348e779115")]
 #[visit(optional)]
@@ -106,6 +227,11 @@ pub struct HitBox {
         to be destroyed. Could be used to spawn various visual effects."
     )]
     pub destruction_prefab: InheritableVariable<Option<ModelResource>>,
+    #[reflect(
+        description = "A neutral effect prefab (sparks, dust, etc.) that will be spawned by any hit \
+        instead of the other effects when gore is disabled in the settings."
+    )]
+    pub neutral_hit_prefab: InheritableVariable<Option<ModelResource>>,
     pub health: InheritableVariable<f32>,
     pub limb_type: InheritableVariable<LimbType>,
     pub environment_damage_timeout: f32,
@@ -124,6 +250,7 @@ impl Default for HitBox {
             pierce_prefab: Default::default(),
             damage_prefab: Default::default(),
             destruction_prefab: Default::default(),
+            neutral_hit_prefab: Default::default(),
             health: 100.0.into(),
             limb_type: Default::default(),
             environment_damage_timeout: 0.0,
@@ -182,6 +309,7 @@ impl HitBox {
                                     direction: manifold.normal,
                                 }),
                                 is_melee: true,
+                                is_critical: false,
                             }),
                         );
 
@@ -194,14 +322,21 @@ impl HitBox {
         }
     }
 
+    // Damage-over-time hazards are handled per character, see `Character::update_hazards`.
     fn handle_death_zones(&mut self, ctx: &mut ScriptContext) {
         let graph = &ctx.scene.graph;
 
         let level = ctx.plugins.get::<Game>().level.as_ref().unwrap();
-        for zone in level.death_zones.iter() {
-            let zone_bounds = graph[*zone].world_bounding_box();
+        for &zone in level.hazard_zones.iter() {
+            if !graph
+                .try_get_script_of::<HazardZone>(zone)
+                .is_some_and(|zone| zone.is_instant_death())
+            {
+                continue;
+            }
+
             let self_position = graph[ctx.handle].global_position();
-            if zone_bounds.is_contains_point(self_position) {
+            if HazardZone::contains(graph, zone, self_position) {
                 ctx.message_sender.send_hierarchical(
                     ctx.handle,
                     RoutingStrategy::Up,
@@ -211,17 +346,90 @@ impl HitBox {
                         dealer: DamageDealer::default(),
                         position: None,
                         is_melee: false,
+                        is_critical: false,
                     }),
                 );
             }
         }
     }
 
-    fn on_damage(&mut self, damage: &HitBoxDamage, ctx: &mut ScriptMessageContext) {
+    fn on_damage(
+        &mut self,
+        damage: &HitBoxDamage,
+        is_propagated: bool,
+        ctx: &mut ScriptMessageContext,
+    ) {
+        // The damage is routed up the hierarchy, so it could pass through other hit boxes.
+        if damage.hit_box != ctx.handle {
+            return;
+        }
+
         let prev_is_sliced_off = self.is_sliced_off();
-        *self.health -= damage.damage;
+        let difficulty = ctx.plugins.get::<Game>().config.difficulty;
+        let mut damage_scale = 1.0;
+        let mut victim = Handle::NONE;
+        let mut owner = ctx.handle;
+        while let Some(node) = ctx.scene.graph.try_get(owner) {
+            if node.has_script::<Player>() {
+                damage_scale = difficulty.player_damage_multiplier();
+                victim = owner;
+                break;
+            } else if let Some(bot) = node.try_get_script::<Bot>() {
+                damage_scale = bot.block_damage_scale(damage, &ctx.scene.graph)
+                    / difficulty.bot_health_multiplier();
+                victim = owner;
+                break;
+            }
+            owner = node.parent();
+        }
+
+        if !ctx.plugins.get::<Game>().is_friendly_fire_enabled() {
+            if let (Some((dealer, dealer_character)), Some(victim_character)) = (
+                damage.dealer.as_character(&ctx.scene.graph),
+                try_get_character_ref(victim, &ctx.scene.graph),
+            ) {
+                // Self-damage (own grenade, for example) is not a friendly fire.
+                if dealer != victim && dealer_character.is_ally_of(victim_character) {
+                    return;
+                }
+            }
+        }
+
+        let amount = damage.damage
+            * damage_scale
+            * *self.damage_factor
+            * LIMB_DAMAGE_MULTIPLIERS.get(*self.limb_type);
+        *self.health -= amount;
+
+        if !is_propagated {
+            ctx.message_sender.send_global(DamageEvent {
+                victim,
+                shooter: damage
+                    .dealer
+                    .as_character(&ctx.scene.graph)
+                    .map(|(handle, _)| handle)
+                    .unwrap_or(damage.dealer.entity),
+                hit_box: ctx.handle,
+                amount,
+                is_critical: damage.is_critical || *self.limb_type == LimbType::Head,
+                position: damage
+                    .position
+                    .map(|p| p.point)
+                    .unwrap_or_else(|| ctx.scene.graph[ctx.handle].global_position()),
+            });
+        }
 
-        if let Some(position) = damage.position {
+        let gore_enabled = ctx.plugins.get::<Game>().is_gore_enabled();
+
+        if let Some(position) = damage.position.filter(|_| !gore_enabled) {
+            if let Some(prefab) = self.neutral_hit_prefab.as_ref() {
+                prefab.instantiate_at(
+                    ctx.scene,
+                    position.point,
+                    vector_to_quat(position.direction),
+                );
+            }
+        } else if let Some(position) = damage.position {
             if !prev_is_sliced_off && self.is_sliced_off() {
                 if let Some(prefab) = self.destruction_prefab.as_ref() {
                     prefab.instantiate_at(
@@ -297,12 +505,13 @@ impl HitBox {
         for child in self.children_hit_boxes.iter() {
             ctx.message_sender.send_to_target(
                 *child,
-                HitBoxMessage::Damage(HitBoxDamage {
+                HitBoxMessage::PropagatedDamage(HitBoxDamage {
                     hit_box: *child,
                     damage: damage.damage,
                     dealer: damage.dealer,
                     position: damage.position,
                     is_melee: damage.is_melee,
+                    is_critical: damage.is_critical,
                 }),
             );
         }
@@ -340,7 +549,9 @@ impl ScriptTrait for HitBox {
     fn on_update(&mut self, ctx: &mut ScriptContext) {
         self.handle_death_zones(ctx);
         self.handle_environment_interaction(ctx);
-        if self.is_sliced_off() {
+        // Damage logic of sliced off limbs is the same regardless of the setting, only visuals are
+        // different.
+        if self.is_sliced_off() && ctx.plugins.get::<Game>().is_gore_enabled() {
             if let Some(bone) = ctx.scene.graph.try_get_mut(*self.bone) {
                 bone.local_transform_mut().set_scale(Vector3::repeat(0.0));
             }
@@ -357,7 +568,8 @@ impl ScriptTrait for HitBox {
         };
 
         match hit_box_message {
-            HitBoxMessage::Damage(damage) => self.on_damage(damage, ctx),
+            HitBoxMessage::Damage(damage) => self.on_damage(damage, false, ctx),
+            HitBoxMessage::PropagatedDamage(damage) => self.on_damage(damage, true, ctx),
             HitBoxMessage::Heal(heal) => self.on_heal(heal),
         }
     }
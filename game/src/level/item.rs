@@ -1,4 +1,4 @@
-use crate::{block_on, Game};
+use crate::{block_on, level::ContainerStats, Game};
 use fyrox::graph::BaseSceneGraph;
 use fyrox::material::MaterialResourceExtension;
 use fyrox::{
@@ -9,7 +9,7 @@ use fyrox::{
         math::ray::Ray,
         pool::Handle,
         reflect::prelude::*,
-        stub_uuid_provider,
+        some_or_continue, stub_uuid_provider,
         type_traits::prelude::*,
         variable::InheritableVariable,
         visitor::prelude::*,
@@ -20,8 +20,12 @@ use fyrox::{
         texture::{Texture, TextureResource},
     },
     scene::{
-        base::BaseBuilder, collider::ColliderShape, graph::physics::RayCastOptions, node::Node,
-        sprite::SpriteBuilder, Scene,
+        base::BaseBuilder,
+        collider::ColliderShape,
+        graph::{physics::RayCastOptions, Graph},
+        node::Node,
+        sprite::SpriteBuilder,
+        Scene,
     },
     script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
 };
@@ -38,6 +42,32 @@ pub enum ItemAction {
 
 stub_uuid_provider!(ItemAction);
 
+/// Access level of a key card. Key cards of higher tier open doors that require lower tiers.
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialOrd,
+    PartialEq,
+    Eq,
+    Ord,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum KeyTier {
+    #[default]
+    None,
+    Blue,
+    Red,
+    Master,
+}
+
+stub_uuid_provider!(KeyTier);
+
 #[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
 #[type_uuid(id = "b915fa9e-6fd0-420d-8879-33cf76adfb5e")]
 #[visit(optional)]
@@ -48,6 +78,31 @@ pub struct Item {
     pub consumable: InheritableVariable<bool>,
     pub preview: InheritableVariable<Option<TextureResource>>,
     pub action: InheritableVariable<ItemAction>,
+    #[reflect(description = "Access level of the item if it is a key card.")]
+    pub key_tier: InheritableVariable<KeyTier>,
+    #[reflect(
+        description = "Time (in seconds) after which the item will appear again after it was \
+        picked up. Zero means that the item will never respawn."
+    )]
+    pub respawn_time: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) that is needed to use a consumable item. Zero means \
+        that the item will be used instantly."
+    )]
+    pub use_time: InheritableVariable<f32>,
+    #[reflect(
+        description = "Whether the item should be returned to the inventory if its usage was \
+        interrupted (for example, by taking damage)."
+    )]
+    pub refund_on_interrupt: InheritableVariable<bool>,
+    #[reflect(
+        description = "Caliber of the item if it is ammo. Weapons of the same caliber could be \
+        loaded with the item."
+    )]
+    pub caliber: InheritableVariable<String>,
+    #[reflect(hidden)]
+    respawn_timer: Option<f32>,
     #[reflect(hidden)]
     pub enabled: bool,
     #[reflect(hidden)]
@@ -67,6 +122,12 @@ impl Default for Item {
             stack_size: 1.into(),
             preview: Default::default(),
             action: Default::default(),
+            key_tier: Default::default(),
+            respawn_time: 0.0.into(),
+            use_time: 0.0.into(),
+            refund_on_interrupt: false.into(),
+            caliber: Default::default(),
+            respawn_timer: None,
             enabled: true,
         }
     }
@@ -131,6 +192,13 @@ impl ScriptTrait for Item {
 }
 
 impl Item {
+    /// Starts respawn countdown if the item can respawn. Must be called when the item is picked up.
+    pub fn start_respawn(&mut self) {
+        if *self.respawn_time > 0.0 {
+            self.respawn_timer = Some(*self.respawn_time);
+        }
+    }
+
     pub fn from_resource<F, R>(model_resource: &ModelResource, func: F) -> R
     where
         F: FnOnce(Option<&Item>) -> R,
@@ -219,4 +287,26 @@ impl ItemContainer {
     pub fn iter(&self) -> impl Iterator<Item = &Handle<Node>> {
         self.container.iter()
     }
+
+    pub fn stats(&self) -> ContainerStats {
+        ContainerStats::of_vec(&self.container)
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.container.shrink_to_fit();
+    }
+
+    pub fn update(&self, graph: &mut Graph, dt: f32) {
+        for &handle in self.container.iter() {
+            let node = some_or_continue!(graph.try_get_mut(handle));
+            let item = some_or_continue!(node.try_get_script_component_mut::<Item>());
+            let timer = some_or_continue!(item.respawn_timer.as_mut());
+
+            *timer -= dt;
+            if *timer <= 0.0 {
+                item.respawn_timer = None;
+                node.set_enabled(true);
+            }
+        }
+    }
 }
@@ -1,44 +1,181 @@
 use crate::{
-    bot::Bot, config::SoundConfig, door::DoorContainer, level::item::ItemContainer,
-    sound::SoundManager, utils::use_hrtf, MessageSender,
+    bot::Bot,
+    character::try_get_character_ref,
+    config::SoundConfig,
+    door::DoorContainer,
+    level::{
+        alarm::Alarm, decal::Decal, item::ItemContainer, music::LevelMusic,
+        navmesh_blocker::NavmeshBlockers, reverb_zone::ReverbZone, spatial::SpatialGrid,
+        spawn::WaveTracker,
+    },
+    sound::SoundManager,
+    utils::use_hrtf,
+    weapon::projectile::{ProjectilePool, ProjectilePoolStats},
+    MessageSender,
 };
 use fyrox::fxhash::FxHashSet;
-use fyrox::graph::SceneGraph;
+use fyrox::graph::{BaseSceneGraph, SceneGraph};
 use fyrox::{
     asset::manager::ResourceManager,
-    core::{futures::executor::block_on, pool::Handle, visitor::prelude::*},
+    core::{
+        algebra::Vector3, futures::executor::block_on, log::Log, pool::Handle, visitor::prelude::*,
+    },
     plugin::PluginContext,
+    resource::model::{Model, ModelResource, ModelResourceExtension},
     scene::{
+        graph::Graph,
         navmesh::NavigationalMesh,
         node::{Node, NodeTrait},
         Scene,
     },
 };
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+};
 
+pub mod alarm;
 pub mod arrival;
-pub mod death_zone;
 pub mod decal;
 pub mod explosion;
 pub mod explosive_barrel;
+pub mod hazard_zone;
 pub mod hit_box;
 pub mod item;
+pub mod music;
+pub mod navmesh_blocker;
 pub mod point_of_interest;
+pub mod proximity_mine;
+pub mod quick_save;
+pub mod reverb_zone;
+pub mod spatial;
 pub mod spawn;
 pub mod trigger;
 pub mod turret;
 
+/// Utilization of a container of handles.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ContainerStats {
+    /// Amount of alive entities in the container.
+    pub alive: usize,
+    /// Total amount of entities that the container can hold without reallocation.
+    pub capacity: usize,
+}
+
+impl ContainerStats {
+    pub fn of_vec<T>(vec: &Vec<T>) -> Self {
+        Self {
+            alive: vec.len(),
+            capacity: vec.capacity(),
+        }
+    }
+}
+
+impl Display for ContainerStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.alive, self.capacity)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LevelStats {
+    pub actors: ContainerStats,
+    pub items: ContainerStats,
+    pub hit_boxes: ContainerStats,
+    pub corpses: ContainerStats,
+    pub decals: ContainerStats,
+    pub projectiles: ProjectilePoolStats,
+}
+
+impl Display for LevelStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Actors: {}", self.actors)?;
+        writeln!(f, "Items: {}", self.items)?;
+        writeln!(f, "Hit Boxes: {}", self.hit_boxes)?;
+        writeln!(f, "Corpses: {}", self.corpses)?;
+        writeln!(f, "Decals: {}", self.decals)?;
+        writeln!(f, "Projectiles: {}", self.projectiles)
+    }
+}
+
+/// A sub-level that is not added to the level yet. Pending sub-levels are saved, so a saved game
+/// that was made in the middle of loading will request them again (see [`Level::resolve`]).
+#[derive(Default, Visit, Debug)]
+struct PendingSubLevel {
+    path: PathBuf,
+    position: Vector3<f32>,
+    #[visit(skip)]
+    resource: Option<ModelResource>,
+}
+
 #[derive(Default, Visit, Debug)]
 pub struct Level {
     pub scene: Handle<Scene>,
     pub player: Handle<Node>,
     pub actors: Vec<Handle<Node>>,
-    pub death_zones: FxHashSet<Handle<Node>>,
+    #[visit(rename = "DeathZones")]
+    pub hazard_zones: FxHashSet<Handle<Node>>,
     pub hit_boxes: FxHashSet<Handle<Node>>,
     pub items: ItemContainer,
     pub doors_container: DoorContainer,
     pub elevators: Vec<Handle<Node>>,
     pub navmesh: Handle<Node>,
     pub pois: FxHashSet<Handle<Node>>,
+    #[visit(optional)]
+    pub waves: WaveTracker,
+    /// Reverb zones are not saved, they're collected from the scene when a saved game is loaded.
+    #[visit(skip)]
+    pub reverb_zones: Vec<Handle<Node>>,
+    /// Max amount of dead actors that can stay on the level at the same time. The oldest ones
+    /// will be removed when the limit is exceeded. Zero means no limit, `None` means
+    /// [`Self::DEFAULT_MAX_CORPSES`] (saves made before the limit was added do not have it).
+    #[visit(optional)]
+    max_corpses: Option<usize>,
+    /// Dead actors in the order of their death.
+    #[visit(skip)]
+    corpses: VecDeque<Handle<Node>>,
+    /// Max amount of impact decals that can stay on the level at the same time. The oldest ones
+    /// will fade out when the limit is exceeded. Zero means no limit, `None` means
+    /// [`Self::DEFAULT_MAX_DECALS`].
+    #[visit(optional)]
+    max_decals: Option<usize>,
+    /// Impact decals in the order of their appearance.
+    #[visit(skip)]
+    decals: VecDeque<Handle<Node>>,
+    /// Max amount of blood pools that can stay on the level at the same time. The oldest ones
+    /// will fade out when the limit is exceeded. Zero means no limit, `None` means
+    /// [`Self::DEFAULT_MAX_BLOOD_POOLS`].
+    #[visit(optional)]
+    max_blood_pools: Option<usize>,
+    /// Blood pools in the order of their appearance.
+    #[visit(skip)]
+    blood_pools: VecDeque<Handle<Node>>,
+    /// Positions of the actors, rebuilt every frame.
+    #[visit(skip)]
+    pub actor_grid: SpatialGrid,
+    /// Positions of the hit boxes, rebuilt every frame.
+    #[visit(skip)]
+    pub hit_box_grid: SpatialGrid,
+    #[visit(skip)]
+    pub projectile_pool: ProjectilePool,
+    /// Every projectile on the level, including disabled ones in the projectile pool.
+    #[visit(skip)]
+    pub projectiles: FxHashSet<Handle<Node>>,
+    #[visit(skip)]
+    pub navmesh_blockers: NavmeshBlockers,
+    /// Paths of the sub-levels that were loaded (or are being loaded) into the level.
+    #[visit(optional)]
+    pub sub_levels: Vec<PathBuf>,
+    #[visit(optional)]
+    pending_sub_levels: Vec<PendingSubLevel>,
+    /// Station-wide alarm. It is not saved, loaded levels start calm (destroyed alarm consoles
+    /// disable it again on start).
+    #[visit(skip)]
+    pub alarm: Alarm,
+    /// Index of the level in [`Level::SEQUENCE`].
+    #[visit(optional)]
+    pub sequence_index: usize,
 
     #[visit(skip)]
     pub sound_manager: SoundManager,
@@ -49,6 +186,11 @@ pub struct Level {
 impl Level {
     //pub const ARRIVAL_PATH: &'static str = "data/levels/arrival.rgs";
     pub const ARRIVAL_PATH: &'static str = "data/levels/testbed.rgs";
+    /// Levels of the campaign in the order they are played. New game starts from the first one.
+    pub const SEQUENCE: &'static [&'static str] = &[Self::ARRIVAL_PATH];
+    pub const DEFAULT_MAX_CORPSES: usize = 16;
+    pub const DEFAULT_MAX_DECALS: usize = 64;
+    pub const DEFAULT_MAX_BLOOD_POOLS: usize = 8;
 
     pub fn from_existing_scene(
         scene: &mut Scene,
@@ -77,19 +219,39 @@ impl Level {
             .map(|t| t.0)
             .unwrap_or_default();
 
+        let mut sound_manager = SoundManager::new(scene, resource_manager);
+        sound_manager.set_music_volume(sound_config.music_volume);
+
         Self {
             navmesh,
             player: Default::default(),
             actors: Default::default(),
-            death_zones: Default::default(),
+            hazard_zones: Default::default(),
             hit_boxes: Default::default(),
             items: Default::default(),
             scene: scene_handle,
             sender: Some(sender),
-            sound_manager: SoundManager::new(scene, resource_manager),
+            sound_manager,
             doors_container: Default::default(),
             elevators: Default::default(),
             pois: Default::default(),
+            waves: Default::default(),
+            reverb_zones: Default::default(),
+            max_corpses: None,
+            corpses: Default::default(),
+            max_decals: None,
+            decals: Default::default(),
+            max_blood_pools: None,
+            blood_pools: Default::default(),
+            actor_grid: Default::default(),
+            hit_box_grid: Default::default(),
+            projectile_pool: Default::default(),
+            projectiles: Default::default(),
+            navmesh_blockers: Default::default(),
+            sub_levels: Default::default(),
+            pending_sub_levels: Default::default(),
+            alarm: Default::default(),
+            sequence_index: 0,
         }
     }
 
@@ -97,14 +259,275 @@ impl Level {
         context.scenes.remove(self.scene);
     }
 
+    pub fn update(&mut self, ctx: &mut PluginContext) {
+        let scene = &mut ctx.scenes[self.scene];
+        self.alarm.update(ctx.dt);
+        if self.waves.update(&scene.graph) {
+            // A good moment to reclaim the memory used during the fight.
+            self.shrink_to_fit();
+        }
+        self.items.update(&mut scene.graph, ctx.dt);
+        self.sound_manager
+            .update_reverb(scene, &self.reverb_zones, ctx.dt);
+        self.update_corpses(scene);
+        self.update_grids(&scene.graph);
+        self.update_sub_levels(scene);
+        self.update_music(scene, ctx.dt);
+    }
+
+    /// The player is in combat if there is an alive bot that targets the player within the given
+    /// radius.
+    pub fn is_player_in_combat(&self, graph: &Graph, radius: f32) -> bool {
+        let Some(player) = try_get_character_ref(self.player, graph) else {
+            return false;
+        };
+        let player_position = player.position(graph);
+
+        self.actors.iter().any(|&actor| {
+            graph.try_get_script_of::<Bot>(actor).is_some_and(|bot| {
+                bot.target() == Some(self.player)
+                    && !bot.is_dead(graph)
+                    && bot.position(graph).metric_distance(&player_position) <= radius
+            })
+        })
+    }
+
+    fn update_music(&mut self, scene: &mut Scene, dt: f32) {
+        let Some(radius) = self
+            .sound_manager
+            .music()
+            .map(|music| music.combat_radius())
+        else {
+            return;
+        };
+
+        let in_combat = self.is_player_in_combat(&scene.graph, radius);
+        self.sound_manager
+            .update_music(&mut scene.graph, in_combat, dt);
+    }
+
+    /// Registers music of the level in the sound manager. The music is registered by the music
+    /// script itself when the level starts, this method is needed when the sound manager is
+    /// re-created (for example, when a saved game is loaded).
+    pub fn restore_music(&mut self, scene: &Scene, music_volume: f32) {
+        self.sound_manager.set_music_volume(music_volume);
+        if let Some(music) = scene
+            .graph
+            .linear_iter()
+            .find_map(|node| node.try_get_script::<LevelMusic>())
+        {
+            self.sound_manager.set_music(music.music());
+        }
+    }
+
+    /// Starts loading of a scene chunk (sub-level) that will be added to the level at the given
+    /// position when it is loaded. Doors, items, spawn points, etc. of the sub-level register
+    /// themselves in the level just like the ones of the main scene. Every sub-level is loaded only
+    /// once, repeated requests are ignored.
+    pub fn load_additive(
+        &mut self,
+        path: impl AsRef<Path>,
+        position: Vector3<f32>,
+        resource_manager: &ResourceManager,
+    ) {
+        let path = path.as_ref();
+        if self.sub_levels.iter().any(|sub_level| sub_level == path) {
+            return;
+        }
+
+        self.sub_levels.push(path.to_path_buf());
+        self.pending_sub_levels.push(PendingSubLevel {
+            path: path.to_path_buf(),
+            position,
+            resource: Some(resource_manager.request::<Model>(path)),
+        });
+
+        Log::info(format!("Loading sub-level {path:?}..."));
+    }
+
+    fn update_sub_levels(&mut self, scene: &mut Scene) {
+        let mut i = 0;
+        while i < self.pending_sub_levels.len() {
+            let Some(resource) = self.pending_sub_levels[i].resource.clone() else {
+                i += 1;
+                continue;
+            };
+
+            if resource.is_loading() {
+                i += 1;
+                continue;
+            }
+
+            let pending = self.pending_sub_levels.remove(i);
+            if !resource.is_ok() {
+                Log::err(format!("Unable to load sub-level {:?}!", pending.path));
+                continue;
+            }
+
+            let root = resource.instantiate_at(scene, pending.position, Default::default());
+
+            // There could be only one navmesh per level, the navmesh of the sub-level is used only
+            // if the level does not have its own.
+            if let Some((navmesh, _)) = scene
+                .graph
+                .find(root, &mut |n| n.cast::<NavigationalMesh>().is_some())
+            {
+                if scene.graph.try_get(self.navmesh).is_none() {
+                    self.navmesh = navmesh;
+                } else if navmesh != self.navmesh {
+                    Log::warn(format!(
+                        "Sub-level {:?} has its own navmesh, it will be ignored!",
+                        pending.path
+                    ));
+                }
+            }
+
+            Log::info(format!("Sub-level {:?} was loaded!", pending.path));
+        }
+    }
+
+    fn update_grids(&mut self, graph: &Graph) {
+        self.actor_grid
+            .rebuild(self.actors.iter().filter_map(|actor| {
+                try_get_character_ref(*actor, graph)
+                    .map(|character| (*actor, character.position(graph)))
+            }));
+        self.hit_box_grid
+            .rebuild(self.hit_boxes.iter().filter_map(|hit_box| {
+                graph
+                    .try_get(*hit_box)
+                    .map(|node| (*hit_box, node.global_position()))
+            }));
+    }
+
+    /// Sets max amount of dead actors that can stay on the level at the same time. Zero means no
+    /// limit. Lower values reduce the amount of scene nodes in long fights, higher values keep
+    /// the corpses around longer.
+    pub fn set_max_corpses(&mut self, max_corpses: usize) {
+        self.max_corpses = Some(max_corpses);
+    }
+
+    pub fn max_corpses(&self) -> usize {
+        self.max_corpses.unwrap_or(Self::DEFAULT_MAX_CORPSES)
+    }
+
+    fn update_corpses(&mut self, scene: &mut Scene) {
+        // Forget the corpses that were already removed or revived.
+        self.corpses.retain(|corpse| {
+            scene
+                .graph
+                .try_get_script_of::<Bot>(*corpse)
+                .is_some_and(|bot| bot.is_dead(&scene.graph))
+        });
+
+        for &actor in self.actors.iter() {
+            if let Some(bot) = scene.graph.try_get_script_of::<Bot>(actor) {
+                if bot.is_dead(&scene.graph) && !self.corpses.contains(&actor) {
+                    self.corpses.push_back(actor);
+                }
+            }
+        }
+
+        let max_corpses = self.max_corpses();
+        if max_corpses == 0 {
+            return;
+        }
+
+        while self.corpses.len() > max_corpses {
+            let Some(oldest) = self.corpses.pop_front() else {
+                break;
+            };
+
+            // Bots shrink during the last second of their lifetime, so this will make the corpse
+            // fade out smoothly instead of just disappearing.
+            let node = &mut scene.graph[oldest];
+            let lifetime = node.lifetime().map_or(1.0, |lifetime| lifetime.min(1.0));
+            node.set_lifetime(Some(lifetime));
+        }
+    }
+
+    /// Sets max amount of impact decals that can stay on the level at the same time. Zero means
+    /// no limit.
+    pub fn set_max_decals(&mut self, max_decals: usize) {
+        self.max_decals = Some(max_decals);
+    }
+
+    pub fn max_decals(&self) -> usize {
+        self.max_decals.unwrap_or(Self::DEFAULT_MAX_DECALS)
+    }
+
+    /// Registers a new impact decal in the level. The oldest decals will start fading out if there
+    /// are too many of them.
+    pub fn add_decal(&mut self, decal: Handle<Node>, graph: &mut Graph) {
+        let max_decals = self.max_decals();
+        add_limited_decal(&mut self.decals, max_decals, decal, graph);
+    }
+
+    pub fn max_blood_pools(&self) -> usize {
+        self.max_blood_pools
+            .unwrap_or(Self::DEFAULT_MAX_BLOOD_POOLS)
+    }
+
+    /// Registers a new blood pool in the level. The oldest blood pools will start fading out if
+    /// there are too many of them.
+    pub fn add_blood_pool(&mut self, blood_pool: Handle<Node>, graph: &mut Graph) {
+        let max_blood_pools = self.max_blood_pools();
+        add_limited_decal(&mut self.blood_pools, max_blood_pools, blood_pool, graph);
+    }
+
+    /// Returns utilization of the containers of the level. Useful to profile memory usage in long
+    /// sessions.
+    pub fn stats(&self) -> LevelStats {
+        LevelStats {
+            actors: ContainerStats::of_vec(&self.actors),
+            items: self.items.stats(),
+            hit_boxes: ContainerStats {
+                alive: self.hit_boxes.len(),
+                capacity: self.hit_boxes.capacity(),
+            },
+            corpses: ContainerStats {
+                alive: self.corpses.len(),
+                capacity: self.corpses.capacity(),
+            },
+            decals: ContainerStats {
+                alive: self.decals.len(),
+                capacity: self.decals.capacity(),
+            },
+            projectiles: self.projectile_pool.stats(),
+        }
+    }
+
+    /// Releases excess memory of the containers of the level. Should be called at the moments when
+    /// the game could afford a small hitch (between levels, after big fights, etc.). The level
+    /// calls it by itself every time a spawn wave is cleared.
+    pub fn shrink_to_fit(&mut self) {
+        self.actors.shrink_to_fit();
+        self.items.shrink_to_fit();
+        self.hit_boxes.shrink_to_fit();
+        self.corpses.shrink_to_fit();
+        self.decals.shrink_to_fit();
+        self.blood_pools.shrink_to_fit();
+    }
+
     pub fn get_player(&self) -> Handle<Node> {
         self.player
     }
 
     pub fn resolve(&mut self, ctx: &mut PluginContext, sender: MessageSender) {
         self.set_message_sender(sender);
-        self.sound_manager =
-            SoundManager::new(&mut ctx.scenes[self.scene], ctx.resource_manager.clone());
+        let scene = &mut ctx.scenes[self.scene];
+        self.reverb_zones = scene
+            .graph
+            .pair_iter()
+            .filter(|(_, node)| node.has_script::<ReverbZone>())
+            .map(|(handle, _)| handle)
+            .collect();
+        self.sound_manager = SoundManager::new(scene, ctx.resource_manager.clone());
+
+        // Sub-levels that were not loaded at the moment of saving must be requested again.
+        for pending in self.pending_sub_levels.iter_mut() {
+            pending.resource = Some(ctx.resource_manager.request::<Model>(&pending.path));
+        }
     }
 
     pub fn set_message_sender(&mut self, sender: MessageSender) {
@@ -129,8 +552,134 @@ impl Level {
 
         for actor in self.actors.iter() {
             if let Some(bot) = scene.graph[*actor].try_get_script::<Bot>() {
-                bot.debug_draw(drawing_context);
+                bot.debug_draw(&scene.graph, drawing_context);
             }
         }
     }
 }
+
+/// Adds a decal to the queue of decals. The oldest decals will start fading out if there are more
+/// than `max_decals` of them. Zero means no limit.
+fn add_limited_decal(
+    decals: &mut VecDeque<Handle<Node>>,
+    max_decals: usize,
+    decal: Handle<Node>,
+    graph: &mut Graph,
+) {
+    if decal.is_none() {
+        return;
+    }
+
+    // Forget the decals that were already removed.
+    decals.retain(|decal| graph.try_get(*decal).is_some());
+    decals.push_back(decal);
+
+    if max_decals == 0 {
+        return;
+    }
+
+    while decals.len() > max_decals {
+        let Some(oldest) = decals.pop_front() else {
+            break;
+        };
+
+        if let Some(decal) = graph.try_get_script_of_mut::<Decal>(oldest) {
+            decal.fade_out();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        door::DoorContainer,
+        level::{item::ItemContainer, Level},
+    };
+    use fyrox::{
+        core::{pool::Handle, visitor::prelude::*},
+        fxhash::FxHashSet,
+        scene::{node::Node, Scene},
+    };
+    use std::path::PathBuf;
+
+    /// Layout of a level saved before the limits were added.
+    #[derive(Default, Visit)]
+    struct LegacyLevel {
+        scene: Handle<Scene>,
+        player: Handle<Node>,
+        actors: Vec<Handle<Node>>,
+        #[visit(rename = "DeathZones")]
+        hazard_zones: FxHashSet<Handle<Node>>,
+        hit_boxes: FxHashSet<Handle<Node>>,
+        items: ItemContainer,
+        doors_container: DoorContainer,
+        elevators: Vec<Handle<Node>>,
+        navmesh: Handle<Node>,
+        pois: FxHashSet<Handle<Node>>,
+    }
+
+    #[test]
+    fn test_level_save_load_round_trip() {
+        let mut level = Level {
+            player: Handle::new(1, 1),
+            actors: vec![Handle::new(1, 1), Handle::new(2, 1), Handle::new(3, 2)],
+            navmesh: Handle::new(4, 1),
+            sub_levels: vec![PathBuf::from("data/levels/sub_level.rgs")],
+            sequence_index: 1,
+            ..Default::default()
+        };
+        level.hit_boxes.insert(Handle::new(5, 1));
+        level.pois.insert(Handle::new(6, 3));
+        level.doors_container.doors.push(Handle::new(7, 1));
+        level.set_max_corpses(3);
+        level.set_max_decals(16);
+
+        let path = std::env::temp_dir().join("station_iapetus_level_round_trip.bin");
+        let mut visitor = Visitor::new();
+        level.visit("Level", &mut visitor).unwrap();
+        visitor.save_binary(&path).unwrap();
+
+        // Saved games are loaded the same way in `Game::on_scene_loaded`.
+        let data = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        let mut loaded = Level::default();
+        loaded.visit("Level", &mut visitor).unwrap();
+
+        assert_eq!(loaded.player, level.player);
+        assert_eq!(loaded.actors, level.actors);
+        assert_eq!(loaded.navmesh, level.navmesh);
+        assert_eq!(loaded.hit_boxes, level.hit_boxes);
+        assert_eq!(loaded.pois, level.pois);
+        assert_eq!(loaded.doors_container.doors, level.doors_container.doors);
+        assert_eq!(loaded.sub_levels, level.sub_levels);
+        assert_eq!(loaded.sequence_index, level.sequence_index);
+        assert_eq!(loaded.max_corpses(), 3);
+        assert_eq!(loaded.max_decals(), 16);
+    }
+
+    #[test]
+    fn test_missing_limits_fall_back_to_defaults() {
+        let mut legacy = LegacyLevel {
+            player: Handle::new(1, 1),
+            ..Default::default()
+        };
+
+        let path = std::env::temp_dir().join("station_iapetus_legacy_level.bin");
+        let mut visitor = Visitor::new();
+        legacy.visit("Level", &mut visitor).unwrap();
+        visitor.save_binary(&path).unwrap();
+
+        let data = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let mut visitor = Visitor::load_from_memory(&data).unwrap();
+        // Saved games are loaded into a default level, see `Game::on_scene_loaded`.
+        let mut level = Level::default();
+        level.visit("Level", &mut visitor).unwrap();
+
+        assert_eq!(level.player, legacy.player);
+        assert_eq!(level.max_corpses(), Level::DEFAULT_MAX_CORPSES);
+        assert_eq!(level.max_decals(), Level::DEFAULT_MAX_DECALS);
+        assert_eq!(level.max_blood_pools(), Level::DEFAULT_MAX_BLOOD_POOLS);
+    }
+}
@@ -0,0 +1,127 @@
+//! Music settings of a level. Add this script to any node of a level to make the music change
+//! smoothly between the ambient and the combat tracks depending on the combat state of the player.
+
+use crate::{sound::DynamicMusic, Game};
+use fyrox::{
+    core::{
+        pool::Handle, reflect::prelude::*, type_traits::prelude::*, variable::InheritableVariable,
+        visitor::prelude::*,
+    },
+    graph::BaseSceneGraph,
+    scene::{
+        base::BaseBuilder,
+        graph::Graph,
+        node::Node,
+        sound::{SoundBufferResource, SoundBuilder, Status},
+    },
+    script::{ScriptContext, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "00106e98-56bf-4385-9b6e-39d30d9a995a")]
+#[visit(optional)]
+pub struct LevelMusic {
+    #[reflect(description = "A track that plays when the player is not in combat.")]
+    ambient_track: InheritableVariable<Option<SoundBufferResource>>,
+    #[reflect(description = "A track that plays when the player is in combat.")]
+    combat_track: InheritableVariable<Option<SoundBufferResource>>,
+    #[reflect(
+        min_value = 0.0,
+        description = "The player is considered in combat if there is a bot that targets the \
+        player within this radius."
+    )]
+    combat_radius: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) of transition from the ambient track to the combat track."
+    )]
+    fade_in_time: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) of transition from the combat track to the ambient track."
+    )]
+    fade_out_time: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) after the end of combat during which the combat track \
+        keeps playing. Prevents the music from changing too often during brief skirmishes."
+    )]
+    combat_cooldown: InheritableVariable<f32>,
+
+    #[reflect(hidden)]
+    ambient_sound: Handle<Node>,
+    #[reflect(hidden)]
+    combat_sound: Handle<Node>,
+}
+
+impl Default for LevelMusic {
+    fn default() -> Self {
+        Self {
+            ambient_track: Default::default(),
+            combat_track: Default::default(),
+            combat_radius: 20.0.into(),
+            fade_in_time: 1.5.into(),
+            fade_out_time: 4.0.into(),
+            combat_cooldown: 8.0.into(),
+            ambient_sound: Default::default(),
+            combat_sound: Default::default(),
+        }
+    }
+}
+
+impl LevelMusic {
+    fn create_sound(
+        track: &Option<SoundBufferResource>,
+        parent: Handle<Node>,
+        graph: &mut Graph,
+    ) -> Handle<Node> {
+        if track.is_none() {
+            return Handle::NONE;
+        }
+
+        // Music is not positional, gain is controlled by the sound manager.
+        let sound = SoundBuilder::new(BaseBuilder::new())
+            .with_buffer(track.clone())
+            .with_looping(true)
+            .with_status(Status::Playing)
+            .with_gain(0.0)
+            .with_spatial_blend_factor(0.0)
+            .build(graph);
+
+        graph.link_nodes(sound, parent);
+
+        sound
+    }
+
+    pub fn music(&self) -> DynamicMusic {
+        DynamicMusic::new(
+            self.ambient_sound,
+            self.combat_sound,
+            *self.combat_radius,
+            *self.fade_in_time,
+            *self.fade_out_time,
+            *self.combat_cooldown,
+        )
+    }
+}
+
+impl ScriptTrait for LevelMusic {
+    fn on_init(&mut self, ctx: &mut ScriptContext) {
+        // Sounds are the part of the scene, keep the existing ones (if any) to prevent duplicate
+        // tracks.
+        if !ctx.scene.graph.is_valid_handle(self.ambient_sound) {
+            self.ambient_sound =
+                Self::create_sound(&self.ambient_track, ctx.handle, &mut ctx.scene.graph);
+        }
+        if !ctx.scene.graph.is_valid_handle(self.combat_sound) {
+            self.combat_sound =
+                Self::create_sound(&self.combat_track, ctx.handle, &mut ctx.scene.graph);
+        }
+    }
+
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+            level.sound_manager.set_music(self.music());
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Navmesh regions that are temporarily impassable (doorways of locked doors, for example).
+//! Navmesh agents are not aware of them, so every path built by an agent must be checked with
+//! [`NavmeshBlockers::is_path_blocked`] before following it. Paths are not rebuilt around blocked
+//! regions, a blocked path could only be abandoned (bots stop and pick another behavior).
+
+use fyrox::{
+    core::{algebra::Vector3, pool::Handle},
+    fxhash::FxHashMap,
+    scene::node::Node,
+};
+
+#[derive(Debug, Clone)]
+pub struct BlockedRegion {
+    pub center: Vector3<f32>,
+    pub radius: f32,
+}
+
+impl BlockedRegion {
+    fn intersects_segment(&self, begin: Vector3<f32>, end: Vector3<f32>) -> bool {
+        // Regions are checked in XZ plane only, paths are laid on the navmesh surface and the
+        // center of a region could be above it.
+        let flat = |v: Vector3<f32>| Vector3::new(v.x, 0.0, v.z);
+        let (begin, end, center) = (flat(begin), flat(end), flat(self.center));
+
+        let segment = end - begin;
+        let length_squared = segment.norm_squared();
+        let t = if length_squared > f32::EPSILON {
+            ((center - begin).dot(&segment) / length_squared).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        (begin + segment.scale(t)).metric_distance(&center) <= self.radius
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct NavmeshBlockers {
+    regions: FxHashMap<Handle<Node>, BlockedRegion>,
+}
+
+impl NavmeshBlockers {
+    /// Marks or unmarks a region owned by the given node as impassable.
+    pub fn set_blocked(&mut self, owner: Handle<Node>, region: BlockedRegion, blocked: bool) {
+        if blocked {
+            self.regions.insert(owner, region);
+        } else {
+            self.regions.remove(&owner);
+        }
+    }
+
+    pub fn remove(&mut self, owner: Handle<Node>) {
+        self.regions.remove(&owner);
+    }
+
+    pub fn is_blocked(&self, owner: Handle<Node>) -> bool {
+        self.regions.contains_key(&owner)
+    }
+
+    /// Checks whether the given path goes through any blocked region. The filter could be used to
+    /// ignore some regions (for example, doorways of doors that could be opened by an actor).
+    pub fn is_path_blocked(
+        &self,
+        path: &[Vector3<f32>],
+        mut filter: impl FnMut(Handle<Node>) -> bool,
+    ) -> bool {
+        self.regions
+            .iter()
+            .filter(|(owner, _)| filter(**owner))
+            .any(|(_, region)| {
+                path.windows(2)
+                    .any(|segment| region.intersects_segment(segment[0], segment[1]))
+            })
+    }
+}
@@ -0,0 +1,150 @@
+//! Proximity mines are deployed by the player and explode when someone else comes close to them.
+//! A mine could also be destroyed by shooting at it, it needs a hit box for that.
+
+use crate::{
+    character::{try_get_character_ref, DamageDealer, DamagePosition},
+    level::hit_box::HitBoxMessage,
+    weapon::projectile::deal_splash_damage,
+    Game,
+};
+use fyrox::{
+    core::{
+        algebra::Vector3, pool::Handle, reflect::prelude::*, some_or_return,
+        type_traits::prelude::*, variable::InheritableVariable, visitor::prelude::*,
+    },
+    graph::BaseSceneGraph,
+    resource::model::{ModelResource, ModelResourceExtension},
+    scene::node::Node,
+    script::{ScriptContext, ScriptMessageContext, ScriptMessagePayload, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "0a2de673-29bd-45d7-9116-f8e6968cdf0d")]
+#[visit(optional)]
+pub struct ProximityMine {
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) after the deployment during which the mine does not \
+        react to actors nearby."
+    )]
+    arm_delay: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Distance (in meters) at which an actor makes the mine detonate."
+    )]
+    trigger_radius: InheritableVariable<f32>,
+    #[reflect(description = "Amount of damage the mine can take before it detonates.")]
+    health: InheritableVariable<f32>,
+    #[reflect(min_value = 0.0)]
+    damage: InheritableVariable<f32>,
+    #[reflect(min_value = 0.0)]
+    damage_radius: InheritableVariable<f32>,
+    explosion_effect: InheritableVariable<Option<ModelResource>>,
+    #[reflect(hidden)]
+    owner: Handle<Node>,
+    #[reflect(hidden)]
+    arm_timer: f32,
+    #[reflect(hidden)]
+    detonated: bool,
+}
+
+impl Default for ProximityMine {
+    fn default() -> Self {
+        Self {
+            arm_delay: 1.5.into(),
+            trigger_radius: 1.5.into(),
+            health: 20.0.into(),
+            damage: 80.0.into(),
+            damage_radius: 3.0.into(),
+            explosion_effect: Default::default(),
+            owner: Default::default(),
+            arm_timer: 0.0,
+            detonated: false,
+        }
+    }
+}
+
+impl ProximityMine {
+    /// Sets the actor that deployed the mine. The owner does not trigger the mine and is credited
+    /// for the damage dealt by it.
+    pub fn set_owner(&mut self, owner: Handle<Node>) {
+        self.owner = owner;
+    }
+
+    pub fn is_armed(&self) -> bool {
+        self.arm_timer >= *self.arm_delay
+    }
+
+    fn is_someone_nearby(&self, ctx: &ScriptContext, position: Vector3<f32>) -> bool {
+        let graph = &ctx.scene.graph;
+        let Some(level) = ctx.plugins.get::<Game>().level.as_ref() else {
+            return false;
+        };
+
+        level
+            .actor_grid
+            .query_radius(position, *self.trigger_radius)
+            .any(|actor| {
+                actor != self.owner
+                    && try_get_character_ref(actor, graph)
+                        .is_some_and(|character| !character.is_dead(graph))
+            })
+    }
+
+    fn detonate(&mut self, ctx: &mut ScriptContext, position: Vector3<f32>) {
+        self.detonated = true;
+
+        deal_splash_damage(
+            ctx.plugins.get::<Game>(),
+            &ctx.scene.graph,
+            ctx.message_sender,
+            position,
+            *self.damage_radius,
+            *self.damage,
+            DamageDealer { entity: self.owner },
+            DamagePosition {
+                point: position,
+                direction: Vector3::y(),
+            },
+        );
+
+        if let Some(explosion_effect) = self.explosion_effect.as_ref() {
+            explosion_effect.instantiate_at(ctx.scene, position, Default::default());
+        }
+
+        // Defer destruction.
+        ctx.scene.graph[ctx.handle].set_lifetime(Some(0.0));
+    }
+}
+
+impl ScriptTrait for ProximityMine {
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        ctx.message_dispatcher
+            .subscribe_to::<HitBoxMessage>(ctx.handle);
+    }
+
+    fn on_message(
+        &mut self,
+        message: &mut dyn ScriptMessagePayload,
+        _ctx: &mut ScriptMessageContext,
+    ) {
+        if let HitBoxMessage::Damage(hit_box_damage) =
+            some_or_return!(message.downcast_ref::<HitBoxMessage>())
+        {
+            *self.health -= hit_box_damage.damage;
+        }
+    }
+
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        if self.detonated {
+            return;
+        }
+
+        self.arm_timer += ctx.dt;
+
+        let position = ctx.scene.graph[ctx.handle].global_position();
+        if *self.health <= 0.0 || (self.is_armed() && self.is_someone_nearby(ctx, position)) {
+            self.detonate(ctx, position);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Quick save is a lightweight in-memory snapshot of the state of the actors of a level. Unlike
+//! full saves, it does not save the scene and could be restored instantly without reloading the
+//! level, which is very useful for playtesting.
+
+use crate::{
+    bot::Bot,
+    character::{try_get_character_mut, try_get_character_ref, Character},
+    inventory::Inventory,
+    level::{hit_box::HitBox, Level},
+};
+use fyrox::{
+    core::{algebra::Vector3, log::Log, pool::Handle},
+    graph::{BaseSceneGraph, SceneGraph},
+    resource::model::ModelResource,
+    scene::{graph::Graph, node::Node, rigidbody::RigidBody, Scene},
+};
+
+#[derive(Clone, Debug)]
+struct ActorSnapshot {
+    actor: Handle<Node>,
+    position: Vector3<f32>,
+    hit_boxes: Vec<(Handle<Node>, f32)>,
+    is_dead: bool,
+}
+
+impl ActorSnapshot {
+    fn take(actor: Handle<Node>, character: &Character, graph: &Graph) -> Self {
+        Self {
+            actor,
+            position: character.position(graph),
+            hit_boxes: character
+                .hit_box_iter(graph)
+                .map(|(handle, hit_box)| (handle, *hit_box.health))
+                .collect(),
+            is_dead: character.is_dead(graph),
+        }
+    }
+
+    /// Restores position and health of the actor. Returns `false` if the actor does not exist
+    /// anymore.
+    fn restore(&self, graph: &mut Graph) -> bool {
+        let Some(body) = try_get_character_ref(self.actor, graph).map(|c| c.body) else {
+            return false;
+        };
+
+        if let Some(body) = graph.try_get_mut(body) {
+            body.local_transform_mut().set_position(self.position);
+            if let Some(rigid_body) = body.cast_mut::<RigidBody>() {
+                rigid_body.set_lin_vel(Default::default());
+            }
+        }
+
+        for &(handle, health) in self.hit_boxes.iter() {
+            if let Some(hit_box) = graph.try_get_script_of_mut::<HitBox>(handle) {
+                *hit_box.health = health;
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct QuickSave {
+    // Quick saves could be restored only on the same level instance they were taken on.
+    scene: Handle<Scene>,
+    player: ActorSnapshot,
+    inventory: Inventory,
+    current_weapon: Option<ModelResource>,
+    bots: Vec<ActorSnapshot>,
+}
+
+impl QuickSave {
+    /// Takes a snapshot of the player and the bots of the level. Returns `None` if there's no
+    /// player on the level.
+    pub fn take(level: &Level, scene: &Scene) -> Option<Self> {
+        let graph = &scene.graph;
+        let player = try_get_character_ref(level.player, graph)?;
+
+        Some(Self {
+            scene: level.scene,
+            player: ActorSnapshot::take(level.player, player, graph),
+            inventory: player.inventory.clone(),
+            current_weapon: graph
+                .try_get(player.current_weapon())
+                .and_then(|weapon| weapon.root_resource()),
+            bots: level
+                .actors
+                .iter()
+                .filter_map(|&actor| {
+                    graph
+                        .try_get_script_of::<Bot>(actor)
+                        .map(|bot| ActorSnapshot::take(actor, bot, graph))
+                })
+                .collect(),
+        })
+    }
+
+    /// Restores the state of the actors. Bots that were killed after the snapshot was taken are
+    /// revived, unless they were already removed from the level.
+    pub fn restore(&self, level: &Level, scene: &mut Scene) {
+        if self.scene != level.scene {
+            Log::warn("The quick save was taken on another level and cannot be loaded!");
+            return;
+        }
+
+        let graph = &mut scene.graph;
+
+        if !self.player.restore(graph) {
+            Log::warn("The player of the quick save does not exist anymore!");
+            return;
+        }
+
+        if let Some(player) = try_get_character_mut(self.player.actor, graph) {
+            player.inventory = self.inventory.clone();
+        }
+
+        if let Some(current_weapon) = self.current_weapon.as_ref() {
+            let weapons = try_get_character_ref(self.player.actor, graph)
+                .map(|player| player.weapons.clone())
+                .unwrap_or_default();
+            if let Some(index) = weapons.iter().position(|&weapon| {
+                graph
+                    .try_get(weapon)
+                    .and_then(|weapon| weapon.root_resource())
+                    .as_ref()
+                    == Some(current_weapon)
+            }) {
+                for (i, &weapon) in weapons.iter().enumerate() {
+                    graph[weapon].set_enabled(i == index);
+                }
+                if let Some(player) = try_get_character_mut(self.player.actor, graph) {
+                    player.current_weapon = index;
+                }
+            }
+        }
+
+        let mut lost_bots = 0;
+        for snapshot in self.bots.iter() {
+            if !snapshot.restore(graph) {
+                lost_bots += 1;
+                continue;
+            }
+
+            if !snapshot.is_dead {
+                Bot::revive(snapshot.actor, graph);
+            }
+        }
+
+        if lost_bots > 0 {
+            Log::warn(format!(
+                "{lost_bots} bot(s) of the quick save were removed from the level and cannot be \
+                restored!"
+            ));
+        }
+
+        Log::info("Quick save was loaded.");
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{sound::ReverbParameters, Game};
+use fyrox::{
+    core::{
+        algebra::Vector3, math::aabb::AxisAlignedBoundingBox, pool::Handle, reflect::prelude::*,
+        type_traits::prelude::*, variable::InheritableVariable, visitor::prelude::*,
+    },
+    graph::BaseSceneGraph,
+    scene::{graph::Graph, node::Node},
+    script::{ScriptContext, ScriptDeinitContext, ScriptTrait},
+};
+
+/// A volume (unit cube transformed by the node's transform) that defines reverb parameters for
+/// the listener inside it.
+#[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "0149af15-ea80-4b8d-8704-877abf4c0d43")]
+#[visit(optional)]
+pub struct ReverbZone {
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    dry: InheritableVariable<f32>,
+    #[reflect(min_value = 0.0, max_value = 1.0)]
+    wet: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) that is needed for reverberation to decay by 60 dB."
+    )]
+    decay_time: InheritableVariable<f32>,
+}
+
+impl Default for ReverbZone {
+    fn default() -> Self {
+        Self {
+            dry: 0.5.into(),
+            wet: 0.5.into(),
+            decay_time: 3.0.into(),
+        }
+    }
+}
+
+impl ReverbZone {
+    pub fn parameters(&self) -> ReverbParameters {
+        ReverbParameters {
+            dry: *self.dry,
+            wet: *self.wet,
+            decay_time: *self.decay_time,
+        }
+    }
+
+    /// Calculates blended reverb parameters of every zone that contains the given point.
+    pub fn blend_at(
+        zones: &[Handle<Node>],
+        graph: &Graph,
+        point: Vector3<f32>,
+    ) -> Option<ReverbParameters> {
+        let mut result = ReverbParameters {
+            dry: 0.0,
+            wet: 0.0,
+            decay_time: 0.0,
+        };
+        let mut count = 0;
+
+        for &handle in zones {
+            let Some(node) = graph.try_get(handle) else {
+                continue;
+            };
+            let Some(zone) = node.try_get_script::<ReverbZone>() else {
+                continue;
+            };
+
+            let bounds = AxisAlignedBoundingBox::unit().transform(&node.global_transform());
+            if bounds.is_contains_point(point) {
+                let parameters = zone.parameters();
+                result.dry += parameters.dry;
+                result.wet += parameters.wet;
+                result.decay_time += parameters.decay_time;
+                count += 1;
+            }
+        }
+
+        if count > 0 {
+            let k = 1.0 / count as f32;
+            result.dry *= k;
+            result.wet *= k;
+            result.decay_time *= k;
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+impl ScriptTrait for ReverbZone {
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        // The zone could be registered already, if the level was loaded from a saved game.
+        if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+            if !level.reverb_zones.contains(&ctx.handle) {
+                level.reverb_zones.push(ctx.handle);
+            }
+        }
+    }
+
+    fn on_deinit(&mut self, ctx: &mut ScriptDeinitContext) {
+        if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+            if let Some(position) = level
+                .reverb_zones
+                .iter()
+                .position(|z| *z == ctx.node_handle)
+            {
+                level.reverb_zones.remove(position);
+            }
+        }
+    }
+}
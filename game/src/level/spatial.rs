@@ -0,0 +1,74 @@
+//! Uniform grid over positions of scene nodes. It allows to find nodes in a radius around some
+//! point without iterating over all of them.
+
+use fyrox::{
+    core::{algebra::Vector3, pool::Handle},
+    fxhash::FxHashMap,
+    scene::node::Node,
+};
+
+#[derive(Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: FxHashMap<(i32, i32), Vec<(Handle<Node>, Vector3<f32>)>>,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(4.0)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size: cell_size.max(f32::EPSILON),
+            cells: Default::default(),
+        }
+    }
+
+    fn cell_of(&self, position: Vector3<f32>) -> (i32, i32) {
+        (
+            (position.x / self.cell_size).floor() as i32,
+            (position.z / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Removes every entry from the grid. Memory of the cells is kept, so the grid could be
+    /// refilled without extra allocations.
+    pub fn clear(&mut self) {
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+    }
+
+    pub fn insert(&mut self, handle: Handle<Node>, position: Vector3<f32>) {
+        let cell = self.cell_of(position);
+        self.cells.entry(cell).or_default().push((handle, position));
+    }
+
+    pub fn rebuild(&mut self, entries: impl IntoIterator<Item = (Handle<Node>, Vector3<f32>)>) {
+        self.clear();
+        for (handle, position) in entries {
+            self.insert(handle, position);
+        }
+    }
+
+    /// Returns handles of every node which position is within the given radius around the
+    /// center.
+    pub fn query_radius(
+        &self,
+        center: Vector3<f32>,
+        radius: f32,
+    ) -> impl Iterator<Item = Handle<Node>> + '_ {
+        let (min_x, min_z) = self.cell_of(center - Vector3::repeat(radius));
+        let (max_x, max_z) = self.cell_of(center + Vector3::repeat(radius));
+
+        (min_x..=max_x)
+            .flat_map(move |x| (min_z..=max_z).map(move |z| (x, z)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .filter(move |(_, position)| position.metric_distance(&center) <= radius)
+            .map(|(handle, _)| *handle)
+    }
+}
@@ -1,10 +1,86 @@
-use crate::character::{CharacterMessage, CharacterMessageData};
+use crate::{
+    character::{try_get_character_ref, CharacterMessage, CharacterMessageData},
+    Game,
+};
 use fyrox::{
-    core::{log::Log, reflect::prelude::*, type_traits::prelude::*, visitor::prelude::*},
+    core::{
+        algebra::{Point3, UnitQuaternion, Vector3},
+        log::Log,
+        pool::Handle,
+        reflect::prelude::*,
+        some_or_continue, some_or_return,
+        type_traits::prelude::*,
+        visitor::prelude::*,
+    },
+    graph::SceneGraph,
     resource::model::{ModelResource, ModelResourceExtension},
-    script::{ScriptContext, ScriptTrait},
+    scene::{
+        collider::ColliderShape,
+        graph::{physics::RayCastOptions, Graph},
+        node::Node,
+        Scene,
+    },
+    script::{
+        ScriptContext, ScriptDeinitContext, ScriptMessageContext, ScriptMessagePayload,
+        ScriptMessageSender, ScriptTrait,
+    },
 };
 
+/// Keeps track of spawn waves of a level. Spawn points of the next wave become active only when
+/// every spawn point of the current wave has spawned all of its characters and all of them are
+/// dead.
+#[derive(Default, Visit, Debug)]
+pub struct WaveTracker {
+    pub current_wave: u32,
+    pub spawn_points: Vec<Handle<Node>>,
+    pub wave_actors: Vec<Handle<Node>>,
+}
+
+impl WaveTracker {
+    /// Returns `true` if the next wave has started, which means that the previous one was cleared.
+    pub fn update(&mut self, graph: &Graph) -> bool {
+        let mut next_wave = None;
+        for &handle in self.spawn_points.iter() {
+            let spawn_point =
+                some_or_continue!(graph.try_get_script_of::<CharacterSpawnPoint>(handle));
+
+            if spawn_point.wave == self.current_wave && !spawn_point.is_exhausted() {
+                return false;
+            }
+
+            if spawn_point.wave > self.current_wave
+                && next_wave.is_none_or(|next_wave| spawn_point.wave < next_wave)
+            {
+                next_wave = Some(spawn_point.wave);
+            }
+        }
+
+        let Some(next_wave) = next_wave else {
+            return false;
+        };
+
+        let wave_cleared = self.wave_actors.iter().all(|actor| {
+            try_get_character_ref(*actor, graph).is_none_or(|character| character.is_dead(graph))
+        });
+
+        if wave_cleared {
+            self.current_wave = next_wave;
+            self.wave_actors.clear();
+
+            Log::info(format!("Wave {next_wave} has started!"));
+        }
+
+        wave_cleared
+    }
+}
+
+/// A message that could be sent to a spawn point to spawn a character immediately, regardless of
+/// the current wave.
+#[derive(Debug)]
+pub enum SpawnPointMessage {
+    Spawn,
+}
+
 #[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
 #[type_uuid(id = "39c47baa-9fc3-4204-92ca-878d621f3656")]
 #[visit(optional)]
@@ -14,6 +90,26 @@ pub struct CharacterSpawnPoint {
     amount: usize,
     interval: f32,
     timer: f32,
+    #[reflect(
+        description = "Index of a wave in which the spawn point will be active. Waves are \
+        activated one by one, starting from zero."
+    )]
+    wave: u32,
+    #[reflect(
+        description = "Time (in seconds) between the start of the wave and the first spawn."
+    )]
+    delay: f32,
+    #[reflect(hidden)]
+    delay_timer: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Distance (in meters) from the spawn point at which extra characters \
+        (spawned on higher difficulties) are placed."
+    )]
+    extra_spread: f32,
+    // Amount of extra characters that are left to spawn on higher difficulties.
+    #[reflect(hidden)]
+    pending_extra: usize,
 }
 
 impl Default for CharacterSpawnPoint {
@@ -24,49 +120,216 @@ impl Default for CharacterSpawnPoint {
             amount: 1,
             interval: 30.0,
             timer: 0.0,
+            wave: 0,
+            delay: 0.0,
+            delay_timer: 0.0,
+            extra_spread: 1.5,
+            pending_extra: 0,
+        }
+    }
+}
+
+impl CharacterSpawnPoint {
+    // Minimal distance between an extra character and an obstacle.
+    const EXTRA_CLEARANCE: f32 = 0.5;
+
+    /// Calculates an offset (relative to the spawn point) for an extra character. Extra characters
+    /// are placed evenly on a circle around the spawn point, the offset is shortened if there's a
+    /// wall (or any other obstacle) in the way.
+    fn extra_offset(
+        &self,
+        graph: &Graph,
+        self_handle: Handle<Node>,
+        index: usize,
+        count: usize,
+    ) -> Vector3<f32> {
+        let angle = std::f32::consts::TAU * index as f32 / count as f32;
+        let direction = Vector3::new(angle.cos(), 0.0, angle.sin());
+        // Lift the ray a bit to not hit the floor.
+        let origin = graph[self_handle].global_position() + Vector3::new(0.0, 0.5, 0.0);
+
+        let mut intersections = Vec::new();
+        graph.physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(origin),
+                ray_direction: direction,
+                max_len: self.extra_spread + Self::EXTRA_CLEARANCE,
+                groups: Default::default(),
+                sort_results: true,
+            },
+            &mut intersections,
+        );
+
+        let distance = intersections
+            .iter()
+            .find(|i| {
+                // Other characters are fine, they will be pushed away by physics.
+                !matches!(
+                    graph[i.collider].as_collider().shape(),
+                    ColliderShape::Capsule(_)
+                )
+            })
+            .map_or(self.extra_spread, |i| {
+                (i.toi - Self::EXTRA_CLEARANCE).clamp(0.0, self.extra_spread)
+            });
+
+        direction.scale(distance)
+    }
+
+    /// Instantiates the prefab of the spawn point and gives default weapons to the new character.
+    /// The character is placed at the spawn point, shifted by the given offset.
+    fn spawn(
+        &self,
+        self_handle: Handle<Node>,
+        offset: Vector3<f32>,
+        scene: &mut Scene,
+        message_sender: &ScriptMessageSender,
+    ) -> Option<Handle<Node>> {
+        let Some(model) = self.prefab.as_ref() else {
+            Log::warn("Prefab is not set, nothing to spawn!");
+            return None;
+        };
+
+        // Take rotation and position for the point.
+        let (rotation, position) = scene.graph.global_rotation_position_no_scale(self_handle);
+
+        let character_root_node_handle = model.instantiate(scene);
+
+        let character_node = &mut scene.graph[character_root_node_handle];
+
+        // Rotate the character accordingly.
+        character_node
+            .local_transform_mut()
+            .set_position(position + offset)
+            .set_rotation(rotation);
+
+        // Give some default weapons.
+        for weapon in self.default_weapons.iter() {
+            if let Some(model) = weapon.clone() {
+                message_sender.send_to_target(
+                    character_root_node_handle,
+                    CharacterMessage {
+                        character: character_root_node_handle,
+                        data: CharacterMessageData::AddWeapon(model),
+                    },
+                )
+            }
         }
+
+        Some(character_root_node_handle)
+    }
+
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.amount == 0
     }
 }
 
 impl ScriptTrait for CharacterSpawnPoint {
+    fn on_init(&mut self, ctx: &mut ScriptContext) {
+        let game = ctx.plugins.get_mut::<Game>();
+
+        let multiplier = game.config.difficulty.bot_spawn_multiplier();
+        self.pending_extra =
+            ((self.amount as f32 * multiplier).ceil() as usize).saturating_sub(self.amount);
+
+        if let Some(level) = game.level.as_mut() {
+            level.waves.spawn_points.push(ctx.handle);
+        }
+    }
+
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        ctx.message_dispatcher
+            .subscribe_to::<SpawnPointMessage>(ctx.handle);
+    }
+
+    fn on_deinit(&mut self, ctx: &mut ScriptDeinitContext) {
+        if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+            if let Some(position) = level
+                .waves
+                .spawn_points
+                .iter()
+                .position(|s| *s == ctx.node_handle)
+            {
+                level.waves.spawn_points.remove(position);
+            }
+        }
+    }
+
+    fn on_message(
+        &mut self,
+        message: &mut dyn ScriptMessagePayload,
+        ctx: &mut ScriptMessageContext,
+    ) {
+        if let Some(SpawnPointMessage::Spawn) = message.downcast_ref::<SpawnPointMessage>() {
+            // Characters spawned on demand (reinforcements, for example) are not the part of any
+            // wave.
+            self.spawn(
+                ctx.handle,
+                Default::default(),
+                ctx.scene,
+                ctx.message_sender,
+            );
+        }
+    }
+
     fn on_update(&mut self, ctx: &mut ScriptContext) {
+        let level = some_or_return!(ctx.plugins.get_mut::<Game>().level.as_mut());
+
+        if level.waves.current_wave != self.wave {
+            return;
+        }
+
+        if self.delay_timer < self.delay {
+            self.delay_timer += ctx.dt;
+            return;
+        }
+
         self.timer -= ctx.dt;
         if self.timer <= 0.0 && self.amount > 0 {
             self.timer = self.interval;
+
+            // Extra characters are distributed evenly among the spawns.
+            let extra = self.pending_extra.div_ceil(self.amount);
+            self.pending_extra -= extra;
             self.amount -= 1;
 
-            if let Some(model) = self.prefab.as_ref() {
-                // Take rotation and position for the point.
-                let (rotation, position) = ctx
-                    .scene
-                    .graph
-                    .global_rotation_position_no_scale(ctx.handle);
-
-                let character_root_node_handle = model.instantiate(ctx.scene);
-
-                let character_node = &mut ctx.scene.graph[character_root_node_handle];
-
-                // Rotate the character accordingly.
-                character_node
-                    .local_transform_mut()
-                    .set_position(position)
-                    .set_rotation(rotation);
-
-                // Give some default weapons.
-                for weapon in self.default_weapons.iter() {
-                    if let Some(model) = weapon.clone() {
-                        ctx.message_sender.send_to_target(
-                            character_root_node_handle,
-                            CharacterMessage {
-                                character: character_root_node_handle,
-                                data: CharacterMessageData::AddWeapon(model),
-                            },
-                        )
-                    }
+            for i in 0..=extra {
+                let offset = if i == 0 {
+                    Default::default()
+                } else {
+                    self.extra_offset(&ctx.scene.graph, ctx.handle, i, extra)
+                };
+
+                if let Some(character) =
+                    self.spawn(ctx.handle, offset, ctx.scene, ctx.message_sender)
+                {
+                    level.waves.wave_actors.push(character);
                 }
-            } else {
-                Log::warn("Prefab is not set, nothing to spawn!")
             }
         }
     }
 }
+
+/// Marks the place where the player appears when a level is started. The player is moved there
+/// (and turned in the direction of the point) only on fresh levels, saved games keep the position
+/// of the player.
+#[derive(Visit, Reflect, Default, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "d3b1c7a2-5e4f-4f0a-9c86-2b7e1f3a6d58")]
+#[visit(optional)]
+pub struct PlayerSpawnPoint;
+
+impl ScriptTrait for PlayerSpawnPoint {}
+
+/// Searches for the first player spawn point of the graph and returns its position and rotation.
+pub fn find_player_spawn_point(graph: &Graph) -> Option<(Vector3<f32>, UnitQuaternion<f32>)> {
+    graph
+        .find_from_root(&mut |node| node.try_get_script::<PlayerSpawnPoint>().is_some())
+        .map(|(handle, _)| {
+            let (rotation, position) = graph.global_rotation_position_no_scale(handle);
+            (position, rotation)
+        })
+}
@@ -1,15 +1,17 @@
 use crate::{
     character::{try_get_character_ref, Character},
+    door::Door,
+    level::spawn::SpawnPointMessage,
     message::Message,
-    Game,
+    utils, Game,
 };
 use fyrox::{
     core::{
-        math::aabb::AxisAlignedBoundingBox, pool::Handle, reflect::prelude::*, stub_uuid_provider,
-        type_traits::prelude::*, visitor::prelude::*,
+        algebra::Vector3, math::aabb::AxisAlignedBoundingBox, pool::Handle, reflect::prelude::*,
+        stub_uuid_provider, type_traits::prelude::*, visitor::prelude::*,
     },
     fxhash::FxHashSet,
-    graph::BaseSceneGraph,
+    graph::{BaseSceneGraph, SceneGraph},
     scene::node::Node,
     script::{ScriptContext, ScriptTrait},
 };
@@ -31,8 +33,31 @@ pub enum TriggerAction {
     LoadLevel {
         path: PathBuf,
     },
+    LoadSubLevel {
+        path: PathBuf,
+        position: Vector3<f32>,
+    },
     BotCounter(BotCounter),
     EndGame,
+    /// Spawns a character at each of the spawn points.
+    SpawnBots {
+        spawn_points: Vec<Handle<Node>>,
+    },
+    OpenDoor {
+        door: Handle<Node>,
+        /// Unlocks the door if it is locked, otherwise locked doors stay closed.
+        unlock: bool,
+    },
+    PlaySound {
+        sound: Handle<Node>,
+    },
+    /// Exit of the level, loads the next level of the campaign.
+    LoadNextLevel,
+    /// Raises the station-wide alarm for the given duration (in seconds), zero duration means
+    /// that the alarm lasts until its console is destroyed.
+    RaiseAlarm {
+        duration: f32,
+    },
 }
 
 stub_uuid_provider!(TriggerAction);
@@ -42,6 +67,29 @@ stub_uuid_provider!(TriggerAction);
 #[visit(optional)]
 pub struct Trigger {
     kind: TriggerAction,
+    #[reflect(
+        description = "Whether the trigger fires only once. Otherwise it fires every time the \
+    player enters the volume."
+    )]
+    once: bool,
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) after the player left the volume, during which the \
+    trigger cannot fire again. Has no effect if the trigger fires only once."
+    )]
+    rearm_delay: f32,
+    #[reflect(hidden)]
+    fired: bool,
+    #[reflect(hidden)]
+    contained_player: bool,
+    #[reflect(hidden)]
+    rearm_timer: f32,
+}
+
+impl Trigger {
+    fn is_armed(&self) -> bool {
+        !(self.once && self.fired) && self.rearm_timer <= 0.0
+    }
 }
 
 impl ScriptTrait for Trigger {
@@ -56,18 +104,78 @@ impl ScriptTrait for Trigger {
                 .map(|c| c.position(&ctx.scene.graph))
                 .is_some_and(|pos| this_bounds.is_contains_point(pos));
 
+            // Actions are performed once the player enters the volume, not every frame while the
+            // player is inside.
+            let entered = contains_player && !self.contained_player && self.is_armed();
+            if entered {
+                self.fired = true;
+            }
+            if contains_player {
+                self.rearm_timer = self.rearm_delay;
+            } else {
+                self.rearm_timer -= ctx.dt;
+            }
+            self.contained_player = contains_player;
+            let player_position = try_get_character_ref(level.player, &ctx.scene.graph)
+                .map(|player| player.position(&ctx.scene.graph));
+
             match self.kind {
                 TriggerAction::LoadLevel { ref path } => {
-                    if contains_player {
+                    if entered {
                         game.message_sender
                             .send(Message::LoadLevel { path: path.clone() })
                     }
                 }
+                TriggerAction::LoadSubLevel { ref path, position } => {
+                    if entered {
+                        game.message_sender.send(Message::LoadSubLevel {
+                            path: path.clone(),
+                            position,
+                        })
+                    }
+                }
+                TriggerAction::RaiseAlarm { duration } => {
+                    if entered {
+                        if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+                            level
+                                .alarm
+                                .raise(duration, player_position, ctx.message_sender);
+                        }
+                    }
+                }
+                TriggerAction::LoadNextLevel => {
+                    if entered {
+                        game.message_sender.send(Message::LoadNextLevel)
+                    }
+                }
                 TriggerAction::EndGame => {
-                    if contains_player {
+                    if entered {
                         game.message_sender.send(Message::EndGame)
                     }
                 }
+                TriggerAction::SpawnBots { ref spawn_points } => {
+                    if entered {
+                        for &spawn_point in spawn_points {
+                            ctx.message_sender
+                                .send_to_target(spawn_point, SpawnPointMessage::Spawn);
+                        }
+                    }
+                }
+                TriggerAction::OpenDoor { door, unlock } => {
+                    if entered {
+                        if let Some(door) = ctx.scene.graph.try_get_script_of_mut::<Door>(door) {
+                            if unlock {
+                                door.unlock();
+                            }
+                            door.try_open(None);
+                        }
+                    }
+                }
+                TriggerAction::PlaySound { sound } => {
+                    if entered {
+                        utils::try_play_sound(sound, &mut ctx.scene.graph);
+                    }
+                }
                 TriggerAction::None => {}
                 TriggerAction::BotCounter(ref mut bot_counter) => {
                     let mut despawn_list = Vec::new();
@@ -1,8 +1,11 @@
 use crate::{
-    character::try_get_character_ref, sound::SoundManager, weapon::projectile::Projectile, Game,
-    Player,
+    character::try_get_character_ref,
+    level::hit_box::HitBoxMessage,
+    sound::SoundManager,
+    weapon::projectile::{Projectile, ProjectilePool},
+    Game, Player,
 };
-use fyrox::graph::SceneGraphNode;
+use fyrox::graph::{SceneGraph, SceneGraphNode};
 use fyrox::{
     core::{
         algebra::{Matrix4, Point3, UnitQuaternion, Vector3},
@@ -12,21 +15,22 @@ use fyrox::{
         pool::Handle,
         rand::{seq::SliceRandom, thread_rng},
         reflect::prelude::*,
-        stub_uuid_provider,
+        some_or_return, stub_uuid_provider,
         type_traits::prelude::*,
         variable::InheritableVariable,
         visitor::{Visit, VisitResult, Visitor},
     },
-    resource::model::ModelResource,
+    resource::model::{ModelResource, ModelResourceExtension},
     scene::{
         collider::{Collider, ColliderShape, InteractionGroups},
         debug::SceneDrawingContext,
-        graph::physics::RayCastOptions,
+        graph::{physics::RayCastOptions, Graph},
         light::BaseLight,
         node::Node,
+        sound::{Sound, Status},
         Scene,
     },
-    script::{ScriptContext, ScriptTrait},
+    script::{ScriptContext, ScriptMessageContext, ScriptMessagePayload, ScriptTrait},
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
@@ -107,6 +111,141 @@ pub struct Turret {
     collider: InheritableVariable<Handle<Node>>,
     shoot_interval: f32,
 
+    #[reflect(
+        description = "Whether the turret should aim ahead of moving targets. Useful for turrets \
+        that shoot slow projectiles."
+    )]
+    prediction_enabled: bool,
+
+    #[reflect(
+        description = "Assumed speed (in m/s) of the projectiles of the turret. It is used to \
+        predict the position of a moving target."
+    )]
+    projectile_speed: f32,
+
+    #[reflect(
+        description = "Amount of shots that the turret can make before reloading. A volley of \
+        all barrels counts as one shot. Zero means that the turret never reloads."
+    )]
+    ammo_capacity: u32,
+
+    #[reflect(description = "Time (in seconds) that is needed to reload the turret.")]
+    reload_time: f32,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) the turret spins up after it has spotted a target before \
+        the first shot. The warm-up restarts when the target is lost."
+    )]
+    warm_up_time: f32,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Warm-up time multiplier while the station-wide alarm is active."
+    )]
+    alarm_warm_up_factor: f32,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    alarm: bool,
+
+    #[reflect(
+        description = "A node that spins while the turret warms up and shoots (usually a barrel \
+        assembly). It rotates around the axis of the barrels."
+    )]
+    warm_up_spinner: Handle<Node>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Spin speed (in degrees per second) of the spinner when the turret is \
+        fully warmed up."
+    )]
+    warm_up_spin_speed: f32,
+
+    #[reflect(
+        description = "A looped sound that is played while the turret has a target. Its pitch \
+        rises as the turret warms up."
+    )]
+    warm_up_sound: Handle<Node>,
+
+    #[reflect(hidden)]
+    warm_up_timer: f32,
+
+    #[reflect(
+        min_value = -180.0,
+        max_value = 180.0,
+        description = "Min yaw angle (in degrees) of the arc that the turret scans when it has no \
+        target. The angle is relative to the model of the turret."
+    )]
+    sweep_min_angle: f32,
+
+    #[reflect(
+        min_value = -180.0,
+        max_value = 180.0,
+        description = "Max yaw angle (in degrees) of the arc that the turret scans when it has no \
+        target. The turret spins endlessly if the arc covers the full circle."
+    )]
+    sweep_max_angle: f32,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Rotation speed (in degrees per second) of the turret while it scans the arc."
+    )]
+    sweep_speed: f32,
+
+    // Either 1.0 or -1.0.
+    #[reflect(hidden)]
+    sweep_direction: f32,
+
+    #[reflect(hidden)]
+    spinner_angle: f32,
+
+    #[reflect(
+        description = "A protective cover (usually a shield with a collider) that is raised \
+        between bursts. The turret cannot be damaged while the cover is raised."
+    )]
+    shield: Handle<Node>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) during which a shielded turret is exposed and shoots. Zero \
+        disables the shield."
+    )]
+    exposed_duration: f32,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) during which a shielded turret stays behind its cover \
+        between bursts."
+    )]
+    covered_duration: f32,
+
+    #[reflect(hidden)]
+    covered: bool,
+
+    #[reflect(hidden)]
+    shield_timer: f32,
+
+    #[reflect(hidden)]
+    shots_fired: u32,
+
+    #[reflect(hidden)]
+    reload_timer: f32,
+
+    #[reflect(
+        description = "Maximum health of the turret. The turret receives damage from its hit boxes."
+    )]
+    max_health: f32,
+
+    #[reflect(description = "A prefab that will be instantiated when the turret is destroyed.")]
+    destruction_effect: Option<ModelResource>,
+
+    #[reflect(hidden)]
+    damage_taken: f32,
+
+    #[reflect(hidden)]
+    destroyed: bool,
+
     #[reflect(hidden)]
     shoot_timer: f32,
 
@@ -123,6 +262,14 @@ pub struct Turret {
     #[reflect(hidden)]
     #[visit(skip)]
     frustum: Frustum,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    target_last_position: Vector3<f32>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    target_velocity: Vector3<f32>,
 }
 
 impl Default for Turret {
@@ -152,34 +299,113 @@ impl Default for Turret {
             target_check_timer: 0.0,
             collider: Default::default(),
             shoot_interval: 0.2,
+            prediction_enabled: false,
+            projectile_speed: 30.0,
+            ammo_capacity: 0,
+            reload_time: 4.0,
+            warm_up_time: 0.75,
+            alarm_warm_up_factor: 0.25,
+            alarm: false,
+            warm_up_spinner: Default::default(),
+            warm_up_spin_speed: 720.0,
+            warm_up_sound: Default::default(),
+            warm_up_timer: 0.0,
+            sweep_min_angle: -60.0,
+            sweep_max_angle: 60.0,
+            sweep_speed: 50.0,
+            sweep_direction: 1.0,
+            spinner_angle: 0.0,
+            shield: Default::default(),
+            exposed_duration: 0.0,
+            covered_duration: 2.0,
+            covered: false,
+            shield_timer: 0.0,
+            shots_fired: 0,
+            reload_timer: 0.0,
+            max_health: 100.0,
+            destruction_effect: None,
+            damage_taken: 0.0,
+            destroyed: false,
+            target_last_position: Default::default(),
+            target_velocity: Default::default(),
         }
     }
 }
 
 impl ScriptTrait for Turret {
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        ctx.message_dispatcher
+            .subscribe_to::<HitBoxMessage>(ctx.handle);
+    }
+
+    fn on_message(
+        &mut self,
+        message: &mut dyn ScriptMessagePayload,
+        _ctx: &mut ScriptMessageContext,
+    ) {
+        if let HitBoxMessage::Damage(hit_box_damage) =
+            some_or_return!(message.downcast_ref::<HitBoxMessage>())
+        {
+            if !self.destroyed && !self.covered {
+                self.damage_taken += hit_box_damage.damage;
+            }
+        }
+    }
+
     fn on_update(&mut self, ctx: &mut ScriptContext) {
-        let level_ref = ctx
-            .plugins
-            .get::<Game>()
-            .level
-            .as_ref()
-            .expect("Level must exist!");
+        if !self.destroyed && self.health() <= 0.0 {
+            self.destroy(ctx.scene);
+        }
+
+        if self.destroyed {
+            // Destroyed turret just hangs down.
+            self.pitch.set_target(90.0f32.to_radians());
+            self.yaw.set_target(self.yaw.angle());
+            self.update_orientation(ctx.scene, ctx.dt);
+            return;
+        }
+
+        let game = ctx.plugins.get_mut::<Game>();
+        let level_ref = game.level.as_mut().expect("Level must exist!");
+        let target_check_interval =
+            Self::TARGET_CHECK_INTERVAL * game.config.difficulty.reaction_time_multiplier();
 
         self.update_frustum(ctx.scene);
 
         self.shoot_timer -= ctx.dt;
         self.target_check_timer -= ctx.dt;
 
+        if self.is_reloading() {
+            self.reload_timer -= ctx.dt;
+            if self.reload_timer <= 0.0 {
+                self.reload_timer = 0.0;
+                self.shots_fired = 0;
+            }
+        }
+
         if self.target_check_timer <= 0.0 {
+            let elapsed = target_check_interval - self.target_check_timer;
+            let prev_target = self.target;
             self.select_target(ctx.scene, &level_ref.actors);
-            self.target_check_timer = 0.15;
+            self.update_target_velocity(prev_target, &ctx.scene.graph, elapsed);
+            self.target_check_timer = target_check_interval;
         }
 
+        self.alarm = level_ref.alarm.is_active();
+        self.update_warm_up(&mut ctx.scene.graph, ctx.dt);
+        self.update_shield(&mut ctx.scene.graph, ctx.dt);
+
         if let Some(target) = try_get_character_ref(self.target, &ctx.scene.graph) {
-            let target_position = target.most_vulnerable_point(&ctx.scene.graph);
+            let mut target_position = target.most_vulnerable_point(&ctx.scene.graph);
 
             let position = ctx.scene.graph[self.model].global_position();
 
+            if self.prediction_enabled && self.projectile_speed > 0.0 {
+                let time_to_target =
+                    target_position.metric_distance(&position) / self.projectile_speed;
+                target_position += self.target_velocity.scale(time_to_target);
+            }
+
             let d = target_position - position;
 
             // Aim horizontally.
@@ -201,7 +427,11 @@ impl ScriptTrait for Turret {
                 self.pitch.set_target(d_body_rel.dot(&Vector3::y()).acos());
             }
 
-            if self.shoot_timer <= 0.0 {
+            if self.shoot_timer <= 0.0
+                && !self.is_reloading()
+                && self.is_warmed_up()
+                && !self.covered
+            {
                 self.shoot_timer = self.shoot_interval;
 
                 match self.shoot_mode {
@@ -212,6 +442,7 @@ impl ScriptTrait for Turret {
                                 ctx.scene,
                                 target_position,
                                 &level_ref.sound_manager,
+                                &mut level_ref.projectile_pool,
                             );
                             self.barrel_index += 1;
                             if self.barrel_index >= self.barrels.len() as u32 {
@@ -226,10 +457,16 @@ impl ScriptTrait for Turret {
                                 ctx.scene,
                                 target_position,
                                 &level_ref.sound_manager,
+                                &mut level_ref.projectile_pool,
                             );
                         }
                     }
                 }
+
+                self.shots_fired += 1;
+                if self.ammo_capacity > 0 && self.shots_fired >= self.ammo_capacity {
+                    self.reload_timer = self.reload_time;
+                }
             }
 
             for barrel in self.barrels.iter_mut() {
@@ -237,8 +474,7 @@ impl ScriptTrait for Turret {
             }
         } else {
             self.pitch.set_target(90.0f32.to_radians());
-            self.yaw
-                .set_target(self.yaw.angle() + 50.0f32.to_radians() * ctx.dt);
+            self.update_sweep(ctx.dt);
         }
 
         if let Some(projector) = ctx
@@ -247,28 +483,21 @@ impl ScriptTrait for Turret {
             .try_get_mut(self.projector)
             .and_then(|p| p.component_mut::<BaseLight>())
         {
-            projector.set_color(if self.target.is_some() {
+            projector.set_color(if self.is_reloading() {
+                // Blink while reloading.
+                if (self.reload_timer * 4.0).fract() < 0.5 {
+                    Color::opaque(255, 127, 40)
+                } else {
+                    Color::BLACK
+                }
+            } else if self.target.is_some() {
                 Color::opaque(255, 0, 0)
             } else {
                 Color::opaque(255, 127, 40)
             });
         }
 
-        self.pitch.update(ctx.dt);
-        self.yaw.update(ctx.dt);
-
-        ctx.scene.graph[self.body]
-            .local_transform_mut()
-            .set_rotation(UnitQuaternion::from_axis_angle(
-                &Vector3::y_axis(),
-                90.0f32.to_radians() + self.yaw.angle(),
-            ));
-        ctx.scene.graph[self.barrel_stand]
-            .local_transform_mut()
-            .set_rotation(UnitQuaternion::from_axis_angle(
-                &Vector3::z_axis(),
-                self.pitch.angle() - std::f32::consts::FRAC_PI_2,
-            ));
+        self.update_orientation(ctx.scene, ctx.dt);
     }
 }
 
@@ -293,6 +522,7 @@ impl Barrel {
         scene: &mut Scene,
         target_position: Vector3<f32>,
         sound_manager: &SoundManager,
+        projectile_pool: &mut ProjectilePool,
     ) {
         self.offset = Vector3::new(-20.0, 0.0, 0.0);
 
@@ -306,6 +536,7 @@ impl Barrel {
                 shot_position,
                 owner_handle,
                 Default::default(),
+                Some(projectile_pool),
             );
         }
 
@@ -319,6 +550,7 @@ impl Barrel {
             &mut scene.graph,
             sounds.choose(&mut thread_rng()).unwrap(),
             shot_position,
+            owner_handle,
             1.0,
             1.0,
             3.0,
@@ -335,6 +567,190 @@ impl Barrel {
 }
 
 impl Turret {
+    const TARGET_CHECK_INTERVAL: f32 = 0.15;
+
+    pub fn is_reloading(&self) -> bool {
+        self.reload_timer > 0.0
+    }
+
+    fn has_shield(&self) -> bool {
+        self.exposed_duration > 0.0
+    }
+
+    /// Returns `true` if the turret is behind its cover and cannot be damaged.
+    pub fn is_covered(&self) -> bool {
+        self.covered
+    }
+
+    fn update_shield(&mut self, graph: &mut Graph, dt: f32) {
+        if !self.has_shield() {
+            return;
+        }
+
+        if self.target.is_some() {
+            self.shield_timer -= dt;
+            if self.shield_timer <= 0.0 {
+                self.covered = !self.covered;
+                self.shield_timer = if self.covered {
+                    self.covered_duration
+                } else {
+                    self.exposed_duration
+                };
+            }
+        } else {
+            // Idle turrets stay behind the cover and peek out as soon as they spot a target.
+            self.covered = true;
+            self.shield_timer = 0.0;
+        }
+
+        if let Some(shield) = graph.try_get_mut(self.shield) {
+            shield.set_enabled(self.covered);
+        }
+    }
+
+    /// Returns warm-up time of the turret, the turret warms up faster when the alarm is active.
+    fn effective_warm_up_time(&self) -> f32 {
+        if self.alarm {
+            self.warm_up_time * self.alarm_warm_up_factor
+        } else {
+            self.warm_up_time
+        }
+    }
+
+    pub fn is_warmed_up(&self) -> bool {
+        self.warm_up_timer >= self.effective_warm_up_time()
+    }
+
+    fn warm_up_progress(&self) -> f32 {
+        let warm_up_time = self.effective_warm_up_time();
+        if warm_up_time > 0.0 {
+            (self.warm_up_timer / warm_up_time).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    fn update_warm_up(&mut self, graph: &mut Graph, dt: f32) {
+        let has_target = self.target.is_some();
+
+        if has_target {
+            self.warm_up_timer = (self.warm_up_timer + dt).min(self.effective_warm_up_time());
+        } else {
+            self.warm_up_timer = 0.0;
+        }
+
+        let progress = self.warm_up_progress();
+
+        if let Some(spinner) = graph.try_get_mut(self.warm_up_spinner) {
+            if has_target {
+                self.spinner_angle += (self.warm_up_spin_speed * progress).to_radians() * dt;
+                spinner
+                    .local_transform_mut()
+                    .set_rotation(UnitQuaternion::from_axis_angle(
+                        &Vector3::x_axis(),
+                        self.spinner_angle,
+                    ));
+            }
+        }
+
+        if let Some(sound) = graph.try_get_mut_of_type::<Sound>(self.warm_up_sound) {
+            if has_target {
+                sound.set_pitch(1.0 + progress as f64);
+                if sound.status() != Status::Playing {
+                    sound.play();
+                }
+            } else if sound.status() == Status::Playing {
+                sound.stop();
+            }
+        }
+    }
+
+    /// Rotates the turret back and forth between the sweep angles.
+    fn update_sweep(&mut self, dt: f32) {
+        let min = self.sweep_min_angle.to_radians();
+        let max = self.sweep_max_angle.to_radians();
+        let step = self.sweep_speed.to_radians() * dt;
+
+        if max - min >= std::f32::consts::TAU - f32::EPSILON {
+            self.yaw.set_target(self.yaw.angle() + step);
+            return;
+        }
+
+        // Bring the angle to [-pi; pi] range, it could be anything after a full turn.
+        let angle = (self.yaw.angle() + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+            - std::f32::consts::PI;
+
+        if angle >= max {
+            self.sweep_direction = -1.0;
+        } else if angle <= min {
+            self.sweep_direction = 1.0;
+        }
+
+        self.yaw
+            .set_target((angle + step * self.sweep_direction).clamp(min, max));
+    }
+
+    fn update_orientation(&mut self, scene: &mut Scene, dt: f32) {
+        self.pitch.update(dt);
+        self.yaw.update(dt);
+
+        scene.graph[self.body]
+            .local_transform_mut()
+            .set_rotation(UnitQuaternion::from_axis_angle(
+                &Vector3::y_axis(),
+                90.0f32.to_radians() + self.yaw.angle(),
+            ));
+        scene.graph[self.barrel_stand]
+            .local_transform_mut()
+            .set_rotation(UnitQuaternion::from_axis_angle(
+                &Vector3::z_axis(),
+                self.pitch.angle() - std::f32::consts::FRAC_PI_2,
+            ));
+    }
+
+    pub fn health(&self) -> f32 {
+        self.max_health - self.damage_taken
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.destroyed
+    }
+
+    fn destroy(&mut self, scene: &mut Scene) {
+        self.destroyed = true;
+        self.target = Default::default();
+        // Stops the warm-up sound.
+        self.update_warm_up(&mut scene.graph, 0.0);
+
+        self.covered = false;
+        if let Some(shield) = scene.graph.try_get_mut(self.shield) {
+            shield.set_enabled(false);
+        }
+
+        if let Some(projector) = scene.graph.try_get_mut(self.projector) {
+            projector.set_enabled(false);
+        }
+
+        if let Some(destruction_effect) = self.destruction_effect.as_ref() {
+            let position = scene.graph[self.model].global_position();
+            destruction_effect.instantiate_at(scene, position, Default::default());
+        }
+    }
+
+    fn update_target_velocity(&mut self, prev_target: Handle<Node>, graph: &Graph, elapsed: f32) {
+        if let Some(target) = try_get_character_ref(self.target, graph) {
+            let position = target.position(graph);
+            self.target_velocity = if prev_target == self.target && elapsed > 0.0 {
+                (position - self.target_last_position).scale(1.0 / elapsed)
+            } else {
+                Vector3::default()
+            };
+            self.target_last_position = position;
+        } else {
+            self.target_velocity = Vector3::default();
+        }
+    }
+
     pub fn debug_draw(&self, context: &mut SceneDrawingContext) {
         context.draw_frustum(&self.frustum, Color::from_rgba(0, 200, 0, 255));
     }
@@ -354,6 +770,42 @@ impl Turret {
             Frustum::from_view_projection_matrix(projection_matrix * view_matrix).unwrap();
     }
 
+    fn is_point_visible(
+        &self,
+        graph: &Graph,
+        point: Vector3<f32>,
+        self_position: Vector3<f32>,
+    ) -> bool {
+        let mut query_buffer = ArrayVec::<_, 128>::new();
+
+        let ray = Ray::from_two_points(point, self_position);
+        graph.physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(ray.origin),
+                ray_direction: ray.dir,
+                groups: InteractionGroups::default(),
+                max_len: ray.dir.norm(),
+                sort_results: true,
+            },
+            &mut query_buffer,
+        );
+
+        for hit in query_buffer.iter() {
+            if *self.collider == hit.collider {
+                continue;
+            }
+
+            if let Some(collider) = graph[hit.collider].cast::<Collider>() {
+                if !matches!(collider.shape(), ColliderShape::Capsule(_)) {
+                    // The point is behind something.
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
     fn select_target(&mut self, scene: &Scene, actors: &[Handle<Node>]) {
         let graph = &scene.graph;
         let self_position = graph[self.model].global_position();
@@ -377,40 +829,26 @@ impl Turret {
                     continue;
                 }
 
-                let mut query_buffer = ArrayVec::<_, 128>::new();
-
                 let actor_position = actor.position(&scene.graph);
 
-                if !self.frustum.is_contains_point(actor_position) {
+                // Check multiple points of the actor, this way partially covered actors will
+                // still be visible.
+                let sample_points = [
+                    actor.most_vulnerable_point(&scene.graph),
+                    actor_position + Vector3::new(0.0, 0.5, 0.0),
+                    actor_position,
+                    actor_position - Vector3::new(0.0, 0.5, 0.0),
+                ];
+
+                let is_visible = sample_points.iter().any(|point| {
+                    self.frustum.is_contains_point(*point)
+                        && self.is_point_visible(graph, *point, self_position)
+                });
+
+                if !is_visible {
                     continue 'target_loop;
                 }
 
-                let ray = Ray::from_two_points(actor_position, self_position);
-                scene.graph.physics.cast_ray(
-                    RayCastOptions {
-                        ray_origin: Point3::from(ray.origin),
-                        ray_direction: ray.dir,
-                        groups: InteractionGroups::default(),
-                        max_len: ray.dir.norm(),
-                        sort_results: true,
-                    },
-                    &mut query_buffer,
-                );
-
-                'hit_loop: for hit in query_buffer.iter() {
-                    if *self.collider == hit.collider {
-                        continue 'hit_loop;
-                    }
-
-                    if let Some(collider) = &scene.graph[hit.collider].cast::<Collider>() {
-                        if !matches!(collider.shape(), ColliderShape::Capsule(_)) {
-                            self.target = Default::default();
-                            // Target is behind something.
-                            continue 'target_loop;
-                        }
-                    }
-                }
-
                 let distance = actor_position.metric_distance(&self_position);
                 if distance < closest_distance {
                     closest_distance = distance;
@@ -4,6 +4,7 @@ pub mod bot;
 pub mod character;
 pub mod config;
 pub mod control_scheme;
+pub mod difficulty;
 pub mod door;
 pub mod effects;
 pub mod elevator;
@@ -15,18 +16,23 @@ pub mod light;
 pub mod message;
 pub mod player;
 pub mod sound;
+pub mod status_effect;
 pub mod utils;
 pub mod weapon;
 
 pub use fyrox;
 
 use crate::level::hit_box::LimbType;
+use crate::level::LevelStats;
 use crate::{
-    bot::{Bot, BotHostility},
+    bot::{
+        boss::{Boss, BossPhase},
+        Bot, BotHostility, LootEntry, PatrolMode,
+    },
     character::Character,
     config::Config,
     door::Door,
-    effects::{beam::Beam, rail::Rail},
+    effects::{beam::Beam, muzzle_flash::MuzzleFlash, rail::Rail, trail::Trail},
     elevator::{
         call_button::{CallButton, CallButtonKind},
         Elevator,
@@ -39,31 +45,38 @@ use crate::{
     highlight::HighlightRenderPass,
     inventory::{Inventory, ItemEntry},
     level::{
+        alarm::AlarmConsole,
         arrival::enemy_trap::EnemyTrap,
-        death_zone::DeathZone,
         decal::Decal,
         explosion::Explosion,
         explosive_barrel::ExplosiveBarrel,
+        hazard_zone::{HazardKind, HazardZone},
         hit_box::HitBox,
         item::Item,
-        item::ItemAction,
+        item::{ItemAction, KeyTier},
+        music::LevelMusic,
         point_of_interest::PointOfInterest,
-        spawn::CharacterSpawnPoint,
+        proximity_mine::ProximityMine,
+        quick_save::QuickSave,
+        reverb_zone::ReverbZone,
+        spawn::{CharacterSpawnPoint, PlayerSpawnPoint},
         trigger::BotCounter,
         trigger::{Trigger, TriggerAction},
         turret::{Barrel, Hostility, ShootMode, Turret},
         Level,
     },
-    light::AnimatedLight,
+    light::{AnimatedLight, FlickeringLight},
     message::Message,
-    player::{camera::CameraController, Player},
-    sound::SoundManager,
+    player::{camera::CameraController, Player, PlayerPersistentData},
+    sound::{MaterialType, SoundManager},
+    status_effect::StatusEffectKind,
     utils::use_hrtf,
     weapon::{
+        dropped::DroppedWeapon,
         kinetic::KineticGun,
-        projectile::{Damage, Projectile},
+        projectile::{Damage, MaterialImpactEffect, Projectile},
         sight::LaserSight,
-        CombatWeaponKind, Weapon,
+        CombatWeaponKind, Weapon, WeaponAttachment,
     },
 };
 use fyrox::{
@@ -78,6 +91,7 @@ use fyrox::{
     dpi::LogicalSize,
     engine::GraphicsContext,
     event::{ElementState, Event, WindowEvent},
+    graph::SceneGraph,
     gui::{
         button::ButtonMessage,
         check_box::CheckBoxMessage,
@@ -131,6 +145,14 @@ pub struct Game {
     journal_display: JournalDisplay,
     #[visit(skip)]
     highlighter: Option<Rc<RefCell<HighlightRenderPass>>>,
+    #[visit(skip)]
+    quick_save: Option<QuickSave>,
+    // Index of the level of the campaign that is being loaded or played.
+    #[visit(skip)]
+    level_index: usize,
+    // State of the player of the previous level, that will be applied to the player of the new one.
+    #[visit(skip)]
+    player_persistent_data: Option<PlayerPersistentData>,
 }
 
 impl Default for Game {
@@ -152,6 +174,9 @@ impl Default for Game {
             item_display: Default::default(),
             journal_display: Default::default(),
             highlighter: Default::default(),
+            quick_save: None,
+            level_index: 0,
+            player_persistent_data: None,
         }
     }
 }
@@ -241,7 +266,9 @@ impl Game {
             .build(&mut context.user_interfaces.first_mut().build_ctx());
     }
 
-    pub fn save_game(&mut self, path: &Path, context: &mut PluginContext) -> VisitResult {
+    /// Saves the scene of the current level together with the state of the level (actors, doors,
+    /// spawn points, etc.) to the given path. Does nothing if there's no level.
+    pub fn save_to(&mut self, path: &Path, context: &mut PluginContext) -> VisitResult {
         if let Some(level) = self.level.as_mut() {
             let mut visitor = Visitor::new();
 
@@ -257,11 +284,50 @@ impl Game {
 
             visitor.save_binary(path)
         } else {
+            Log::warn("There is no level to save!");
             Ok(())
         }
     }
 
-    pub fn load_game(&mut self, context: &mut PluginContext, path: &Path) {
+    /// Takes a snapshot of the actors of the current level and keeps it in memory. Only one quick
+    /// save is kept, the previous one is overwritten.
+    pub fn quick_save(&mut self, context: &mut PluginContext) {
+        if let Some(level) = self.level.as_ref() {
+            self.quick_save = QuickSave::take(level, &context.scenes[level.scene]);
+            if self.quick_save.is_some() {
+                Log::info("Quick save was made.");
+            }
+        } else {
+            Log::warn("There is no level to quick save!");
+        }
+    }
+
+    /// Restores the actors of the current level from the quick save (if any).
+    pub fn quick_load(&mut self, context: &mut PluginContext) {
+        let Some(level) = self.level.as_ref() else {
+            Log::warn("There is no level to quick load!");
+            return;
+        };
+        let Some(quick_save) = self.quick_save.as_ref() else {
+            Log::warn("There is no quick save!");
+            return;
+        };
+
+        quick_save.restore(level, &mut context.scenes[level.scene]);
+    }
+
+    /// Requests loading of a saved game from the given path. The current level is destroyed and
+    /// the saved scene is loaded asynchronously, the state of the level is restored from the same
+    /// file when the scene is loaded (see [`Plugin::on_scene_loaded`]).
+    pub fn load_from(&mut self, path: &Path, context: &mut PluginContext) {
+        if !path.exists() {
+            Log::err(format!(
+                "Unable to load a saved game: {path:?} does not exist!"
+            ));
+            return;
+        }
+
+        self.destroy_level(context);
         context.async_scene_loader.request_raw(path);
     }
 
@@ -272,11 +338,58 @@ impl Game {
         }
     }
 
+    /// Returns `true` if gore effects (blood, dismemberment, etc.) should be shown.
+    pub fn is_gore_enabled(&self) -> bool {
+        self.config.gore_enabled
+    }
+
+    /// Returns `true` if characters of the same team can damage each other.
+    pub fn is_friendly_fire_enabled(&self) -> bool {
+        self.config.friendly_fire
+    }
+
+    /// Returns utilization of the containers of the current level, if any.
+    pub fn level_stats(&self) -> Option<LevelStats> {
+        self.level.as_ref().map(|level| level.stats())
+    }
+
+    /// Releases excess memory of the containers of the current level, if any.
+    pub fn shrink_level_containers(&mut self) {
+        if let Some(level) = self.level.as_mut() {
+            level.shrink_to_fit();
+        }
+    }
+
     pub fn load_level(&mut self, path: PathBuf, context: &mut PluginContext) {
+        // Levels outside of the campaign sequence keep the current index.
+        if let Some(index) = Level::SEQUENCE.iter().position(|p| Path::new(p) == path) {
+            self.level_index = index;
+        }
         self.destroy_level(context);
         context.async_scene_loader.request(path);
     }
 
+    /// Loads the next level of the campaign and carries the state of the player (health,
+    /// inventory and weapons) over to it. Ends the game if the current level is the last one.
+    pub fn load_next_level(&mut self, context: &mut PluginContext) {
+        let Some(level) = self.level.as_ref() else {
+            Log::warn("There is no level to leave!");
+            return;
+        };
+
+        let Some(path) = Level::SEQUENCE.get(level.sequence_index + 1) else {
+            self.message_sender.send(Message::EndGame);
+            return;
+        };
+
+        let graph = &context.scenes[level.scene].graph;
+        self.player_persistent_data = graph
+            .try_get_script_of::<Player>(level.player)
+            .filter(|player| !player.is_dead(graph))
+            .map(|player| player.persistent_data(graph));
+        self.load_level(path.into(), context);
+    }
+
     pub fn set_menu_visible(&mut self, visible: bool, context: &mut PluginContext) {
         self.menu.set_visible(context, visible);
     }
@@ -317,6 +430,8 @@ impl Game {
             ctx.scenes[level.scene]
                 .enabled
                 .set_value_silent(!self.menu.is_visible(ui));
+
+            level.update(ctx);
         }
 
         self.weapon_display.update(ctx.dt);
@@ -346,16 +461,32 @@ impl Game {
         while let Ok(message) = self.message_receiver.try_recv() {
             match &message {
                 Message::StartNewGame => {
-                    self.load_level(Level::ARRIVAL_PATH.into(), context);
+                    self.player_persistent_data = None;
+                    self.load_level(Level::SEQUENCE[0].into(), context);
                 }
-                Message::SaveGame(path) => match self.save_game(path, context) {
+                Message::SaveGame(path) => match self.save_to(path, context) {
                     Ok(_) => Log::info("Successfully saved"),
                     Err(e) => Log::err(format!("Failed to make a save at {path:?}, reason: {e}")),
                 },
+                Message::QuickSave => self.quick_save(context),
+                Message::QuickLoad => {
+                    self.player_persistent_data = None;
+                    self.quick_load(context)
+                }
                 Message::LoadGame(path) => {
-                    self.load_game(context, path);
+                    self.player_persistent_data = None;
+                    self.load_from(path, context);
+                }
+                Message::LoadLevel { path } => {
+                    self.player_persistent_data = None;
+                    self.load_level(path.clone(), context)
+                }
+                Message::LoadNextLevel => self.load_next_level(context),
+                Message::LoadSubLevel { path, position } => {
+                    if let Some(level) = self.level.as_mut() {
+                        level.load_additive(path, *position, &context.resource_manager);
+                    }
                 }
-                Message::LoadLevel { path } => self.load_level(path.clone(), context),
                 Message::QuitGame => {
                     self.destroy_level(context);
                     self.running = false;
@@ -374,7 +505,9 @@ impl Game {
                 }
                 Message::SetMusicVolume(volume) => {
                     self.config.sound.music_volume = *volume;
-                    // TODO: Apply to sound manager of level when it will handle music!
+                    if let Some(level) = self.level.as_mut() {
+                        level.sound_manager.set_music_volume(*volume);
+                    }
                     context.scenes[self.menu.scene.scene].graph[self.menu.scene.music]
                         .as_sound_mut()
                         .set_gain(*volume);
@@ -401,6 +534,9 @@ impl Game {
                 Message::SetMasterVolume(volume) => {
                     self.config.sound.master_volume = *volume;
                 }
+                Message::SetDifficulty(difficulty) => {
+                    self.config.difficulty = *difficulty;
+                }
                 Message::ToggleMainMenu => {
                     self.menu.set_visible(context, true);
                     let ui = context.user_interfaces.first();
@@ -456,8 +592,9 @@ impl Game {
                 if let Some(ref mut level) = self.level {
                     write!(
                         self.debug_string,
-                        "Node Count: {}",
-                        ctx.scenes[level.scene].graph.node_count()
+                        "Node Count: {}\n{}",
+                        ctx.scenes[level.scene].graph.node_count(),
+                        level.stats()
                     )
                     .unwrap();
                 }
@@ -537,6 +674,10 @@ impl Game {
             if let ElementState::Pressed = input.state {
                 if input.physical_key == KeyCode::Escape && self.level.is_some() {
                     self.set_menu_visible(!self.is_any_menu_visible(context), context);
+                } else if input.physical_key == KeyCode::F5 {
+                    self.message_sender.send(Message::QuickSave);
+                } else if input.physical_key == KeyCode::F9 {
+                    self.message_sender.send(Message::QuickLoad);
                 }
             }
         }
@@ -554,26 +695,36 @@ impl Plugin for Game {
             .add::<Door>("Door")
             .add::<Turret>("Turret")
             .add::<Weapon>("Weapon")
+            .add::<DroppedWeapon>("Dropped Weapon")
             .add::<Item>("Item")
             .add::<Decal>("Decal")
             .add::<Player>("Player")
             .add::<CameraController>("Camera Controller")
             .add::<Bot>("Bot")
+            .add::<Boss>("Boss")
             .add::<CharacterSpawnPoint>("Character Spawn Point")
-            .add::<DeathZone>("Death Zone")
+            .add::<PlayerSpawnPoint>("Player Spawn Point")
+            .add::<HazardZone>("Hazard Zone")
+            .add::<ReverbZone>("Reverb Zone")
+            .add::<LevelMusic>("Level Music")
             .add::<AnimatedLight>("Animated Light")
+            .add::<FlickeringLight>("Flickering Light")
+            .add::<AlarmConsole>("Alarm Console")
             .add::<Elevator>("Elevator")
             .add::<CallButton>("Call Button")
             .add::<Projectile>("Projectile")
             .add::<LaserSight>("LaserSight")
             .add::<Rail>("Rail")
+            .add::<MuzzleFlash>("Muzzle Flash")
             .add::<Explosion>("Explosion")
             .add::<Beam>("Beam")
+            .add::<Trail>("Trail")
             .add::<KineticGun>("KineticGun")
             .add::<EnemyTrap>("ArrivalEnemyTrap")
             .add::<PointOfInterest>("Point Of Interest")
             .add::<Trigger>("Trigger")
             .add::<ExplosiveBarrel>("ExplosiveBarrel")
+            .add::<ProximityMine>("Proximity Mine")
             .add::<HitBox>("HitBox");
 
         context.widget_constructors.add::<InventoryItem>();
@@ -584,14 +735,23 @@ impl Plugin for Game {
         container.register_inheritable_enum::<Hostility, _>();
         container.register_inheritable_enum::<ShootMode, _>();
         container.register_inheritable_enum::<CombatWeaponKind, _>();
+        container.register_inheritable_enum::<WeaponAttachment, _>();
         container.register_inheritable_enum::<CallButtonKind, _>();
         container.register_inheritable_enum::<Damage, _>();
+        container.register_inheritable_enum::<MaterialType, _>();
         container.register_inheritable_enum::<TriggerAction, _>();
         container.register_inheritable_enum::<BotHostility, _>();
+        container.register_inheritable_enum::<PatrolMode, _>();
         container.register_inheritable_enum::<ItemAction, _>();
+        container.register_inheritable_enum::<KeyTier, _>();
         container.register_inheritable_enum::<LimbType, _>();
+        container.register_inheritable_enum::<HazardKind, _>();
+        container.register_inheritable_enum::<StatusEffectKind, _>();
         container.register_inheritable_inspectable::<Inventory>();
         container.register_inheritable_inspectable::<ItemEntry>();
+        container.register_inheritable_inspectable::<LootEntry>();
+        container.register_inheritable_inspectable::<BossPhase>();
+        container.register_inheritable_inspectable::<MaterialImpactEffect>();
         container.register_inheritable_inspectable::<Barrel>();
         container.register_inheritable_inspectable::<Character>();
         container.register_inheritable_inspectable::<CameraController>();
@@ -600,6 +760,7 @@ impl Plugin for Game {
         container.register_inheritable_inspectable::<BotCounter>();
         container.register_inheritable_vec_collection::<Barrel>();
         container.register_inheritable_vec_collection::<ItemEntry>();
+        container.register_inheritable_vec_collection::<WeaponAttachment>();
         container
     }
 
@@ -642,6 +803,9 @@ impl Plugin for Game {
             message_receiver: rx,
             message_sender,
             highlighter: None,
+            quick_save: None,
+            level_index: 0,
+            player_persistent_data: None,
         };
 
         self.create_debug_ui(&mut context);
@@ -690,6 +854,7 @@ impl Plugin for Game {
         if let Some(level) = self.level.as_mut() {
             let scene = &mut context.scenes[level.scene];
             level.sound_manager = SoundManager::new(scene, context.resource_manager.clone());
+            level.restore_music(scene, self.config.sound.music_volume);
         }
     }
 
@@ -762,16 +927,20 @@ impl Plugin for Game {
             if level.visit("Level", &mut visitor).is_ok() {
                 // Means that we're loading a saved game.
                 level.scene = scene;
+                self.level_index = level.sequence_index;
                 level.resolve(ctx, self.message_sender.clone());
+                level.restore_music(&ctx.scenes[scene], self.config.sound.music_volume);
                 self.level = Some(level);
             } else {
-                self.level = Some(Level::from_existing_scene(
+                let mut level = Level::from_existing_scene(
                     &mut ctx.scenes[scene],
                     scene,
                     self.message_sender.clone(),
                     self.config.sound.clone(),
                     ctx.resource_manager.clone(),
-                ));
+                );
+                level.sequence_index = self.level_index;
+                self.level = Some(level);
             }
         }
 
@@ -1,12 +1,17 @@
+use crate::level::alarm::AlarmEvent;
 use fyrox::{
     core::{
+        algebra::Vector2,
+        color::Color,
         rand::Rng,
         reflect::prelude::*,
+        some_or_return,
         type_traits::prelude::*,
         visitor::{Visit, VisitResult, Visitor},
     },
     rand::thread_rng,
-    script::{ScriptContext, ScriptTrait},
+    scene::light::BaseLight,
+    script::{ScriptContext, ScriptMessageContext, ScriptMessagePayload, ScriptTrait},
 };
 
 #[derive(Visit, Reflect, Default, Debug, Clone, TypeUuidProvider, ComponentProvider)]
@@ -28,3 +33,114 @@ impl ScriptTrait for AnimatedLight {
         }
     }
 }
+
+/// Randomly changes intensity of a light. The light could flicker all the time (damaged lights),
+/// or only while the alarm is raised. In the latter case the light also switches to the emergency
+/// color.
+#[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "5b8e2f61-0c4d-4a7e-9f13-6d2a8c7e4b90")]
+#[visit(optional)]
+pub struct FlickeringLight {
+    #[reflect(
+        description = "Whether the light flickers all the time. Otherwise it flickers only while \
+        the alarm is raised."
+    )]
+    always_flicker: bool,
+    #[reflect(
+        min_value = 0.0,
+        description = "Average amount of intensity changes per second."
+    )]
+    frequency: f32,
+    #[reflect(description = "Range of intensity of the light, relative to its initial intensity.")]
+    intensity_range: Vector2<f32>,
+    #[reflect(description = "Color of the light while the alarm is raised.")]
+    emergency_color: Color,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Intensity multiplier of the light while the alarm is raised."
+    )]
+    emergency_dimming: f32,
+    // The alarm is not saved, so this flag is not saved too.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    alarm: bool,
+    #[reflect(hidden)]
+    timer: f32,
+    #[reflect(hidden)]
+    intensity_factor: f32,
+    // Initial parameters of the light, captured on the first update.
+    #[reflect(hidden)]
+    base_intensity: Option<f32>,
+    #[reflect(hidden)]
+    base_color: Color,
+}
+
+impl Default for FlickeringLight {
+    fn default() -> Self {
+        Self {
+            always_flicker: false,
+            frequency: 10.0,
+            intensity_range: Vector2::new(0.1, 1.0),
+            emergency_color: Color::opaque(255, 30, 20),
+            emergency_dimming: 0.6,
+            alarm: false,
+            timer: 0.0,
+            intensity_factor: 1.0,
+            base_intensity: None,
+            base_color: Color::WHITE,
+        }
+    }
+}
+
+impl ScriptTrait for FlickeringLight {
+    fn on_start(&mut self, ctx: &mut ScriptContext) {
+        ctx.message_dispatcher
+            .subscribe_to::<AlarmEvent>(ctx.handle);
+    }
+
+    fn on_message(
+        &mut self,
+        message: &mut dyn ScriptMessagePayload,
+        _ctx: &mut ScriptMessageContext,
+    ) {
+        if let Some(alarm) = message.downcast_ref::<AlarmEvent>() {
+            self.alarm = alarm.active;
+        }
+    }
+
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        let light = some_or_return!(ctx.scene.graph[ctx.handle].component_mut::<BaseLight>());
+
+        let base_intensity = match self.base_intensity {
+            Some(base_intensity) => base_intensity,
+            None => {
+                self.base_color = light.color();
+                *self.base_intensity.insert(light.intensity())
+            }
+        };
+
+        if self.always_flicker || self.alarm {
+            self.timer -= ctx.dt;
+            if self.timer <= 0.0 {
+                let mut rng = thread_rng();
+                // Randomize intervals, so lights won't flicker in sync.
+                self.timer = rng.gen_range(0.5..1.5) / self.frequency.max(f32::EPSILON);
+                let min = self.intensity_range.x.min(self.intensity_range.y);
+                let max = self.intensity_range.x.max(self.intensity_range.y);
+                self.intensity_factor = rng.gen_range(min..=max);
+            }
+        } else {
+            self.intensity_factor = 1.0;
+        }
+
+        let (color, dimming) = if self.alarm {
+            (self.emergency_color, self.emergency_dimming)
+        } else {
+            (self.base_color, 1.0)
+        };
+
+        light.set_color(color);
+        light.set_intensity(base_intensity * dimming * self.intensity_factor);
+    }
+}
@@ -3,16 +3,34 @@
 //! required entity. This is very effective decoupling mechanism that works perfectly with
 //! strict ownership rules of Rust.
 
+use crate::difficulty::Difficulty;
+use fyrox::core::algebra::Vector3;
 use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum Message {
-    Play2DSound { path: PathBuf, gain: f32 },
+    Play2DSound {
+        path: PathBuf,
+        gain: f32,
+    },
     SaveGame(PathBuf),
     LoadGame(PathBuf),
+    /// Takes an in-memory snapshot of the actors of the current level.
+    QuickSave,
+    /// Restores the actors of the current level from the snapshot taken by [`Message::QuickSave`].
+    QuickLoad,
     StartNewGame,
     QuitGame,
-    LoadLevel { path: PathBuf },
+    LoadLevel {
+        path: PathBuf,
+    },
+    /// Loads the next level of the campaign, the state of the player is carried over to it.
+    LoadNextLevel,
+    /// Loads a scene chunk and adds it to the current level at the given position.
+    LoadSubLevel {
+        path: PathBuf,
+        position: Vector3<f32>,
+    },
     ToggleMainMenu,
     EndMatch,
     EndGame,
@@ -21,4 +39,5 @@ pub enum Message {
     SetMusicVolume(f32),
     SetUseHrtf(bool),
     SetMasterVolume(f32),
+    SetDifficulty(Difficulty),
 }
@@ -12,6 +12,7 @@ use fyrox::{
     },
     rand,
     scene::{
+        camera::{Camera, Projection},
         graph::physics::{Intersection, RayCastOptions},
         node::Node,
         Scene,
@@ -35,6 +36,10 @@ pub struct CameraController {
     #[visit(skip)]
     #[reflect(hidden)]
     query_buffer: Vec<Intersection>,
+    // Field of view of the camera as it was set in the editor.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    default_fov: Option<f32>,
 }
 
 impl CameraController {
@@ -79,6 +84,21 @@ impl CameraController {
         }
     }
 
+    /// Smoothly narrows the field of view of the camera while the player aims down the sights.
+    fn update_zoom(&mut self, ads_factor: f32, ads_fov: Option<f32>, scene: &mut Scene) {
+        let Some(camera) = scene.graph.try_get_mut_of_type::<Camera>(self.camera) else {
+            return;
+        };
+
+        let mut projection = camera.projection().clone();
+        if let Projection::Perspective(ref mut perspective) = projection {
+            let default_fov = *self.default_fov.get_or_insert(perspective.fov);
+            let target_fov = ads_fov.map_or(default_fov, |fov| fov.to_radians());
+            perspective.fov = default_fov + (target_fov - default_fov) * ads_factor;
+            camera.set_projection(projection);
+        }
+    }
+
     fn update_shake(&mut self, dt: f32) {
         let xy_range = -0.027..0.027;
         let z_range = 0.01..0.05;
@@ -100,18 +120,27 @@ impl CameraController {
 
 impl ScriptTrait for CameraController {
     fn on_update(&mut self, context: &mut ScriptContext) {
-        let (is_aiming, yaw, pitch) = context
-            .scene
-            .graph
+        let graph = &context.scene.graph;
+        let (is_aiming, yaw, pitch, crouch_offset, ads_factor, ads_fov) = graph
             .try_get(self.player)
             .and_then(|p| p.try_get_script::<Player>())
-            .map(|p| (p.is_aiming(), p.target_yaw, p.target_pitch))
+            .map(|p| {
+                (
+                    p.is_aiming(),
+                    p.target_yaw,
+                    p.target_pitch,
+                    p.crouch_camera_offset(),
+                    p.ads_factor(),
+                    p.ads_fov(graph),
+                )
+            })
             .unwrap_or_default();
 
         self.target_camera_offset.x = 0.0;
-        self.target_camera_offset.y = 0.0;
+        self.target_camera_offset.y = -crouch_offset;
         self.target_camera_offset.z = if is_aiming { 0.2 } else { 0.8 };
 
+        self.update_zoom(ads_factor, ads_fov, context.scene);
         self.update_shake(context.dt);
         self.check_occlusion(self.ignorable_collider, context.scene);
 
@@ -0,0 +1,220 @@
+//! Detection of usable objects (doors, items, elevator controls) in front of the player.
+
+use crate::{
+    door::Door, elevator::call_button::CallButton, level::Level, weapon::dropped::DroppedWeapon,
+    Elevator, Item,
+};
+use fyrox::{
+    core::{
+        algebra::{Point3, Vector3},
+        pool::Handle,
+    },
+    graph::{BaseSceneGraph, SceneGraph, SceneGraphNode},
+    scene::{
+        graph::{physics::RayCastOptions, Graph},
+        node::Node,
+    },
+};
+
+/// Max distance (in meters) between the player and an object the player is looking at, that
+/// still allows to interact with the object.
+pub const INTERACTION_REACH: f32 = 2.0;
+
+/// Max distances at which objects could be used even if the player does not look at them
+/// directly.
+const ITEM_PROXIMITY: f32 = 0.75;
+const DOOR_PROXIMITY: f32 = 1.25;
+const ELEVATOR_PROXIMITY: f32 = 0.75;
+const CALL_BUTTON_PROXIMITY: f32 = 0.75;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InteractionTargetKind {
+    Door,
+    Item,
+    Elevator,
+    CallButton,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InteractionTarget {
+    pub kind: InteractionTargetKind,
+    pub handle: Handle<Node>,
+}
+
+/// A global event, that is sent every time when the usable object in front of the player changes.
+/// `target` is `None` when there's nothing to interact with anymore.
+#[derive(Debug, Clone)]
+pub struct InteractionPromptEvent {
+    pub player: Handle<Node>,
+    pub target: Option<InteractionTarget>,
+}
+
+impl InteractionTarget {
+    fn new(kind: InteractionTargetKind, handle: Handle<Node>) -> Self {
+        Self { kind, handle }
+    }
+
+    /// Searches for a usable object up in the hierarchy starting from the given node. Colliders are
+    /// usually attached somewhere deep in the hierarchy of an object, while the script is assigned
+    /// to its root.
+    fn find_up(graph: &Graph, mut handle: Handle<Node>) -> Option<Self> {
+        while let Some(node) = graph.try_get(handle) {
+            if node.try_get_script::<Door>().is_some() {
+                return Some(Self::new(InteractionTargetKind::Door, handle));
+            } else if node.try_get_script_component::<Item>().is_some() {
+                return Some(Self::new(InteractionTargetKind::Item, handle));
+            } else if node.try_get_script::<CallButton>().is_some() {
+                return Some(Self::new(InteractionTargetKind::CallButton, handle));
+            } else if let Some(dropped_weapon) = node.try_get_script::<DroppedWeapon>() {
+                // The weapon is a child of its physical body, the body is what the player sees.
+                return Some(Self::new(
+                    InteractionTargetKind::Item,
+                    dropped_weapon.weapon,
+                ));
+            }
+
+            handle = node.parent();
+        }
+
+        None
+    }
+
+    fn is_usable(&self, graph: &Graph) -> bool {
+        let Some(node) = graph.try_get(self.handle) else {
+            return false;
+        };
+
+        match self.kind {
+            InteractionTargetKind::Door => node
+                .try_get_script::<Door>()
+                .is_some_and(|door| !door.is_broken()),
+            InteractionTargetKind::Item => {
+                node.is_globally_enabled()
+                    && node
+                        .try_get_script_component::<Item>()
+                        .is_some_and(|item| item.enabled)
+            }
+            InteractionTargetKind::Elevator | InteractionTargetKind::CallButton => true,
+        }
+    }
+}
+
+fn is_part_of(graph: &Graph, mut handle: Handle<Node>, root: Handle<Node>) -> bool {
+    while let Some(node) = graph.try_get(handle) {
+        if handle == root {
+            return true;
+        }
+        handle = node.parent();
+    }
+    false
+}
+
+/// Finds an object, that the player is looking at. Obstacles between the eyes and the object
+/// prevent the interaction.
+fn look_at_target(
+    graph: &Graph,
+    player: Handle<Node>,
+    player_position: Vector3<f32>,
+    eye_position: Vector3<f32>,
+    look_direction: Vector3<f32>,
+) -> Option<InteractionTarget> {
+    let look_direction = look_direction.try_normalize(f32::EPSILON)?;
+
+    // The camera could be placed behind the player, so the ray should be long enough to reach
+    // objects in front of the player.
+    let reach = eye_position.metric_distance(&player_position) + INTERACTION_REACH;
+
+    let mut intersections = Vec::new();
+    graph.physics.cast_ray(
+        RayCastOptions {
+            ray_origin: Point3::from(eye_position),
+            ray_direction: look_direction,
+            max_len: reach,
+            groups: Default::default(),
+            sort_results: true,
+        },
+        &mut intersections,
+    );
+
+    // Only the closest obstacle matters, everything behind it is not visible.
+    let intersection = intersections
+        .iter()
+        .find(|i| !is_part_of(graph, i.collider, player))?;
+
+    if intersection
+        .position
+        .coords
+        .metric_distance(&player_position)
+        > INTERACTION_REACH
+    {
+        return None;
+    }
+
+    InteractionTarget::find_up(graph, intersection.collider).filter(|t| t.is_usable(graph))
+}
+
+/// Finds the closest usable object around the given position.
+fn nearby_target(
+    graph: &Graph,
+    level: &Level,
+    position: Vector3<f32>,
+) -> Option<InteractionTarget> {
+    let items = level
+        .items
+        .iter()
+        .map(|&h| (InteractionTargetKind::Item, h, ITEM_PROXIMITY));
+    let doors = level
+        .doors_container
+        .doors
+        .iter()
+        .map(|&h| (InteractionTargetKind::Door, h, DOOR_PROXIMITY));
+    let elevators = level
+        .elevators
+        .iter()
+        .map(|&h| (InteractionTargetKind::Elevator, h, ELEVATOR_PROXIMITY));
+    let call_buttons = level
+        .elevators
+        .iter()
+        .filter_map(|&h| graph.try_get_script_of::<Elevator>(h))
+        .flat_map(|elevator| elevator.call_buttons.iter())
+        .map(|&h| (InteractionTargetKind::CallButton, h, CALL_BUTTON_PROXIMITY));
+
+    items
+        .chain(doors)
+        .chain(elevators)
+        .chain(call_buttons)
+        .filter_map(|(kind, handle, max_distance)| {
+            let target = InteractionTarget::new(kind, handle);
+            if !target.is_usable(graph) {
+                return None;
+            }
+
+            let target_position = match kind {
+                // Doors are moving when opening, so use their initial position to not make the
+                // reach dependent on the current door state.
+                InteractionTargetKind::Door => graph
+                    .try_get_script_of::<Door>(handle)
+                    .map(|door| door.initial_position())?,
+                _ => graph.try_get(handle)?.global_position(),
+            };
+
+            let distance = target_position.metric_distance(&position);
+            (distance < max_distance).then_some((target, distance))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(target, _)| target)
+}
+
+/// Searches for an object the player could interact with. An object the player is looking at has
+/// priority over the objects that are just nearby.
+pub fn find_interaction_target(
+    graph: &Graph,
+    level: &Level,
+    player: Handle<Node>,
+    player_position: Vector3<f32>,
+    eye_position: Vector3<f32>,
+    look_direction: Vector3<f32>,
+) -> Option<InteractionTarget> {
+    look_at_target(graph, player, player_position, eye_position, look_direction)
+        .or_else(|| nearby_target(graph, level, player_position))
+}
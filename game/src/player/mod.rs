@@ -1,31 +1,38 @@
 use crate::{
     character::{Character, CharacterMessage, CharacterMessageData},
     control_scheme::ControlButton,
-    door::{door_mut, DoorContainer},
+    difficulty::Difficulty,
+    door::door_mut,
     elevator::call_button::{CallButton, CallButtonKind},
     gui::inventory::InventoryInterface,
     gui::journal::Journal,
     inventory::Inventory,
-    level::hit_box::HitBoxMessage,
+    level::hit_box::{DamageEvent, HitBox, HitBoxDamage, HitBoxHeal, HitBoxMessage, KillEvent},
     level::item::ItemAction,
+    level::proximity_mine::ProximityMine,
+    level::spawn::find_player_spawn_point,
+    level::Level,
     message::Message,
-    player::state_machine::{StateMachine, StateMachineInput},
+    player::{
+        interaction::{InteractionPromptEvent, InteractionTarget, InteractionTargetKind},
+        state_machine::{StateMachine, StateMachineInput},
+    },
     sound::SoundManager,
     utils::{self},
     weapon::{
-        projectile::Projectile, weapon_ref, CombatWeaponKind, Weapon, WeaponMessage,
-        WeaponMessageData,
+        projectile::Projectile, weapon_mut, weapon_ref, CombatWeaponKind, LowAmmoWarning, Weapon,
+        WeaponMessage, WeaponMessageData,
     },
     CameraController, Elevator, Game, Item, MessageSender,
 };
 use fyrox::{
     asset::manager::ResourceManager,
     core::{
-        algebra::{UnitQuaternion, Vector2, Vector3},
+        algebra::{Point3, UnitQuaternion, Vector2, Vector3},
         color::Color,
         futures::executor::block_on,
         log::Log,
-        math::{SmoothAngle, Vector2Ext},
+        math::{vector_to_quat, SmoothAngle, Vector2Ext},
         pool::Handle,
         reflect::prelude::*,
         type_traits::prelude::*,
@@ -45,7 +52,8 @@ use fyrox::{
     },
     scene::{
         animation::{absm, absm::prelude::*, prelude::*},
-        graph::Graph,
+        collider::{CapsuleShape, Collider, ColliderShape},
+        graph::{physics::RayCastOptions, Graph},
         node::Node,
         sprite::Sprite,
         Scene,
@@ -59,8 +67,56 @@ use fyrox::{
 use std::ops::{Deref, DerefMut};
 
 pub mod camera;
+pub mod interaction;
 mod state_machine;
 
+const GRENADE_PROJECTILE: &str = "data/models/grenade/grenade_proj.rgs";
+/// Initial speed (in m/s) of a thrown grenade.
+const GRENADE_THROW_SPEED: f32 = 10.0;
+/// Fuse time (in seconds) of a grenade, whose projectile prefab has no fuse.
+const DEFAULT_GRENADE_FUSE_TIME: f32 = 3.0;
+/// Distance (in meters) in front of the player at which a proximity mine is placed.
+const MINE_PLACEMENT_DISTANCE: f32 = 0.75;
+
+/// A global event, that is sent every time when the player takes damage. It could be used by a HUD
+/// to draw an arrow pointing at the threat.
+#[derive(Debug, Clone)]
+pub struct DamageIndicatorEvent {
+    pub player: Handle<Node>,
+    pub amount: f32,
+    /// An angle (in radians) between the look direction of the player and the direction to the
+    /// source of the damage, measured around the up axis in the range of `[-pi; pi]`. `None` for
+    /// damage without a known source (hazard zones, falls, etc.).
+    pub angle: Option<f32>,
+}
+
+/// A global event, that is sent every frame with the current effective spread of the weapon of the
+/// player. It could be used by a HUD to expand and contract the crosshair.
+#[derive(Debug, Clone)]
+pub struct CrosshairSpreadEvent {
+    pub player: Handle<Node>,
+    pub weapon: Handle<Node>,
+    /// Max angle (in degrees) of deviation of shots from the aiming direction.
+    pub spread: f32,
+}
+
+/// A global event, that is sent when a shot (or a melee attack) of the player hits an actor.
+/// Multiple hits in a single frame (pellets of a shotgun, for example) are merged into a single
+/// event. It could be used by a HUD to show a hit marker.
+#[derive(Debug, Clone)]
+pub struct HitMarkerEvent {
+    pub player: Handle<Node>,
+    /// The last actor that was hit.
+    pub victim: Handle<Node>,
+    pub is_critical: bool,
+    /// `true` if the hit killed the victim.
+    pub is_kill: bool,
+}
+
+fn capsule_height(capsule: &CapsuleShape) -> f32 {
+    (capsule.end - capsule.begin).norm() + 2.0 * capsule.radius
+}
+
 #[derive(Default, Debug)]
 pub struct InputController {
     walk_forward: bool,
@@ -69,9 +125,11 @@ pub struct InputController {
     walk_right: bool,
     jump: bool,
     aim: bool,
+    aim_down_sights: bool,
     toss_grenade: bool,
     shoot: bool,
     run: bool,
+    crouch: bool,
     action: bool,
     cursor_up: bool,
     cursor_down: bool,
@@ -97,6 +155,7 @@ pub enum RequiredWeapon {
     Next,
     Previous,
     Specific(ModelResource),
+    Last,
 }
 
 impl RequiredWeapon {
@@ -111,10 +170,12 @@ impl Default for RequiredWeapon {
     }
 }
 
-#[derive(Clone)]
+/// State of the player, that is carried over to the next level of the campaign.
+#[derive(Clone, Debug)]
 pub struct PlayerPersistentData {
     pub inventory: Inventory,
-    pub current_weapon: usize,
+    /// Prefab of the weapon that was selected.
+    pub current_weapon: Option<ModelResource>,
     pub weapons: Vec<ModelResource>,
     pub hit_box_health: FxHashMap<Handle<Node>, f32>,
 }
@@ -184,6 +245,222 @@ pub struct Player {
     #[reflect(hidden)]
     pub script_message_sender: Option<ScriptMessageSender>,
     pub grenade_item: InheritableVariable<Option<ModelResource>>,
+    #[reflect(description = "An item that is consumed when the player places a proximity mine.")]
+    pub mine_item: InheritableVariable<Option<ModelResource>>,
+    #[reflect(description = "A prefab of a proximity mine, that will be placed by the player.")]
+    pub mine_prefab: InheritableVariable<Option<ModelResource>>,
+    #[reflect(description = "Max amount of proximity mines the player can have placed at once.")]
+    pub max_active_mines: InheritableVariable<usize>,
+    #[reflect(
+        description = "A sound that will be played when the player kills someone with a \
+    head shot."
+    )]
+    head_shot_sound: InheritableVariable<Handle<Node>>,
+    #[reflect(
+        description = "A sound that will be played when a shot of the player hits an actor."
+    )]
+    hit_marker_sound: InheritableVariable<Handle<Node>>,
+    #[reflect(
+        description = "A sound that will be played when the player kills someone. Head shot kills \
+    play the head shot sound instead."
+    )]
+    kill_marker_sound: InheritableVariable<Handle<Node>>,
+    // Hits of the current frame, that are not reported yet.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    hit_marker: Option<HitMarkerEvent>,
+
+    #[reflect(min_value = 0.0)]
+    max_stamina: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of stamina (per second) that is consumed while sprinting."
+    )]
+    stamina_drain_rate: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of stamina (per second) that is restored while not sprinting."
+    )]
+    stamina_regen_rate: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of stamina that must be restored after full exhaustion before \
+        the player will be able to sprint again."
+    )]
+    stamina_recovery_threshold: InheritableVariable<f32>,
+
+    #[reflect(hidden)]
+    stamina: f32,
+
+    #[reflect(hidden)]
+    exhausted: bool,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) without taking damage after which the player starts to \
+        regenerate health."
+    )]
+    regen_delay: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of health (per second) that is restored by passive regeneration. \
+        Zero disables the regeneration."
+    )]
+    regen_rate: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Fraction of the full health up to which the player can regenerate. One \
+        means that the player can regenerate up to full health."
+    )]
+    regen_cap: InheritableVariable<f32>,
+
+    // Time (in seconds) since the last damage.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    regen_timer: f32,
+
+    // Combined health of all hit boxes at the start of the level.
+    #[reflect(hidden)]
+    full_health: f32,
+
+    #[reflect(min_value = 0.0)]
+    flash_light_battery_capacity: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of charge (per second) that is consumed while the flash light is on."
+    )]
+    flash_light_drain_rate: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of charge (per second) that is restored while the flash light is off."
+    )]
+    flash_light_recharge_rate: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of charge that must be restored after full discharge before \
+        the flash light could be turned on again."
+    )]
+    flash_light_recovery_threshold: InheritableVariable<f32>,
+
+    #[reflect(hidden)]
+    flash_light_charge: f32,
+
+    #[reflect(hidden)]
+    flash_light_depleted: bool,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Total height of the collision capsule (in meters) while crouching."
+    )]
+    crouch_height: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Movement speed multiplier while crouching."
+    )]
+    crouch_speed_factor: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Weapon recoil multiplier while crouching."
+    )]
+    crouch_recoil_factor: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Fraction of the weapon recoil that kicks the view of the player on every \
+        shot."
+    )]
+    camera_kick_factor: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Speed (in deg/s) at which the view returns to its orientation before the \
+        recoil kick. Moving the mouse cancels the recovery."
+    )]
+    recoil_recovery_speed: InheritableVariable<f32>,
+    // Yaw and pitch (in radians) of the recoil kick that is not recovered yet.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    recoil_kick: Vector2<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Speed (in 1/s) of transition between standing and crouching poses."
+    )]
+    crouch_transition_speed: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Movement speed multiplier while using an item (for example, a med kit)."
+    )]
+    item_use_speed_factor: InheritableVariable<f32>,
+
+    #[reflect(hidden)]
+    crouch_factor: f32,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Defines how much the weapon lags behind the rotation of the camera. Zero \
+        disables the sway."
+    )]
+    weapon_sway_amount: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Max offset (in meters) of the weapon while walking. Zero disables the bob."
+    )]
+    weapon_bob_amplitude: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Frequency (in Hz) of the weapon bob at normal walking speed."
+    )]
+    weapon_bob_frequency: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Movement speed multiplier while aiming down the sights."
+    )]
+    ads_speed_factor: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Speed (in 1/s) of transition to and from aiming down the sights."
+    )]
+    ads_transition_speed: InheritableVariable<f32>,
+
+    #[reflect(hidden)]
+    ads_factor: f32,
+
+    #[reflect(hidden)]
+    weapon_sway: Vector2<f32>,
+    #[reflect(hidden)]
+    prev_camera_angles: Vector2<f32>,
+    #[reflect(hidden)]
+    weapon_bob_phase: f32,
+    #[reflect(hidden)]
+    weapon_bob_factor: f32,
+    #[reflect(hidden)]
+    #[visit(skip)]
+    weapon_pivot_origin: Option<Vector3<f32>>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    standing_capsule: Option<CapsuleShape>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    interaction_target: Option<InteractionTarget>,
+
+    #[reflect(hidden)]
+    #[visit(skip)]
+    grenade_cook_time: Option<f32>,
+
+    // Fuse time of the grenade that is being cooked, it is resolved once when the grenade is drawn.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    grenade_fuse_time: f32,
+
+    #[reflect(hidden)]
+    active_mines: Vec<Handle<Node>>,
 }
 
 impl Default for Player {
@@ -258,9 +535,58 @@ impl Default for Player {
             glock_weapon: None,
             plasma_gun_weapon: None,
             grenade_item: Default::default(),
+            mine_item: Default::default(),
+            mine_prefab: Default::default(),
+            max_active_mines: 3.into(),
+            head_shot_sound: Default::default(),
+            hit_marker_sound: Default::default(),
+            kill_marker_sound: Default::default(),
+            hit_marker: None,
             target_pitch: 0.0,
             inventory_gui: Default::default(),
             item_display_prefab: None,
+            max_stamina: 100.0.into(),
+            stamina_drain_rate: 20.0.into(),
+            stamina_regen_rate: 15.0.into(),
+            stamina_recovery_threshold: 25.0.into(),
+            stamina: 100.0,
+            exhausted: false,
+            regen_delay: 5.0.into(),
+            regen_rate: 0.0.into(),
+            regen_cap: 1.0.into(),
+            regen_timer: 0.0,
+            full_health: 0.0,
+            flash_light_battery_capacity: 100.0.into(),
+            flash_light_drain_rate: 1.5.into(),
+            flash_light_recharge_rate: 1.0.into(),
+            flash_light_recovery_threshold: 20.0.into(),
+            flash_light_charge: 100.0,
+            flash_light_depleted: false,
+            crouch_height: 1.1.into(),
+            crouch_speed_factor: 0.5.into(),
+            crouch_recoil_factor: 0.5.into(),
+            camera_kick_factor: 0.5.into(),
+            recoil_recovery_speed: 15.0.into(),
+            recoil_kick: Default::default(),
+            crouch_transition_speed: 5.0.into(),
+            item_use_speed_factor: 0.4.into(),
+            crouch_factor: 0.0,
+            weapon_sway_amount: 0.03.into(),
+            weapon_bob_amplitude: 0.01.into(),
+            weapon_bob_frequency: 1.8.into(),
+            ads_speed_factor: 0.6.into(),
+            ads_transition_speed: 8.0.into(),
+            ads_factor: 0.0,
+            weapon_sway: Default::default(),
+            prev_camera_angles: Default::default(),
+            weapon_bob_phase: 0.0,
+            weapon_bob_factor: 0.0,
+            weapon_pivot_origin: None,
+            standing_capsule: None,
+            interaction_target: None,
+            grenade_cook_time: None,
+            grenade_fuse_time: DEFAULT_GRENADE_FUSE_TIME,
+            active_mines: Default::default(),
         }
     }
 }
@@ -307,14 +633,259 @@ impl Clone for Player {
             glock_weapon: self.glock_weapon.clone(),
             plasma_gun_weapon: self.plasma_gun_weapon.clone(),
             grenade_item: self.grenade_item.clone(),
+            mine_item: self.mine_item.clone(),
+            mine_prefab: self.mine_prefab.clone(),
+            max_active_mines: self.max_active_mines.clone(),
+            head_shot_sound: self.head_shot_sound.clone(),
+            hit_marker_sound: self.hit_marker_sound.clone(),
+            kill_marker_sound: self.kill_marker_sound.clone(),
+            hit_marker: self.hit_marker.clone(),
             target_pitch: self.target_pitch,
             inventory_gui: self.inventory_gui.clone(),
             item_display_prefab: self.item_display_prefab.clone(),
+            max_stamina: self.max_stamina.clone(),
+            stamina_drain_rate: self.stamina_drain_rate.clone(),
+            stamina_regen_rate: self.stamina_regen_rate.clone(),
+            stamina_recovery_threshold: self.stamina_recovery_threshold.clone(),
+            stamina: self.stamina,
+            exhausted: self.exhausted,
+            regen_delay: self.regen_delay.clone(),
+            regen_rate: self.regen_rate.clone(),
+            regen_cap: self.regen_cap.clone(),
+            regen_timer: self.regen_timer,
+            full_health: self.full_health,
+            flash_light_battery_capacity: self.flash_light_battery_capacity.clone(),
+            flash_light_drain_rate: self.flash_light_drain_rate.clone(),
+            flash_light_recharge_rate: self.flash_light_recharge_rate.clone(),
+            flash_light_recovery_threshold: self.flash_light_recovery_threshold.clone(),
+            flash_light_charge: self.flash_light_charge,
+            flash_light_depleted: self.flash_light_depleted,
+            crouch_height: self.crouch_height.clone(),
+            crouch_speed_factor: self.crouch_speed_factor.clone(),
+            crouch_recoil_factor: self.crouch_recoil_factor.clone(),
+            camera_kick_factor: self.camera_kick_factor.clone(),
+            recoil_recovery_speed: self.recoil_recovery_speed.clone(),
+            recoil_kick: self.recoil_kick,
+            crouch_transition_speed: self.crouch_transition_speed.clone(),
+            item_use_speed_factor: self.item_use_speed_factor.clone(),
+            crouch_factor: self.crouch_factor,
+            weapon_sway_amount: self.weapon_sway_amount.clone(),
+            weapon_bob_amplitude: self.weapon_bob_amplitude.clone(),
+            weapon_bob_frequency: self.weapon_bob_frequency.clone(),
+            ads_speed_factor: self.ads_speed_factor.clone(),
+            ads_transition_speed: self.ads_transition_speed.clone(),
+            ads_factor: self.ads_factor,
+            weapon_sway: self.weapon_sway,
+            prev_camera_angles: self.prev_camera_angles,
+            weapon_bob_phase: self.weapon_bob_phase,
+            weapon_bob_factor: self.weapon_bob_factor,
+            weapon_pivot_origin: self.weapon_pivot_origin,
+            standing_capsule: self.standing_capsule.clone(),
+            interaction_target: self.interaction_target,
+            grenade_cook_time: self.grenade_cook_time,
+            grenade_fuse_time: self.grenade_fuse_time,
+            active_mines: self.active_mines.clone(),
         }
     }
 }
 
 impl Player {
+    /// Calculates an angle between the look direction of the player and the direction to the
+    /// source of the damage. If the dealer is unknown (explosions, for example), the source is
+    /// restored from the position of the damage. Damage without both has no direction.
+    fn damage_source_angle(
+        &self,
+        damage: &HitBoxDamage,
+        graph: &Graph,
+        self_handle: Handle<Node>,
+    ) -> Option<f32> {
+        // Self-inflicted damage (i.e. by a grenade) has no direction as well.
+        if damage.dealer.entity == self_handle {
+            return None;
+        }
+
+        let source_position = match damage.dealer.as_character(graph) {
+            Some((_, character)) => character.position(graph),
+            None => match graph.try_get(damage.dealer.entity) {
+                Some(dealer) => dealer.global_position(),
+                None => damage
+                    .position
+                    .map(|position| position.point - position.direction)?,
+            },
+        };
+
+        let look = graph
+            .try_get(self.camera_controller)
+            .and_then(|c| c.try_get_script::<CameraController>())
+            .map(|c| graph[c.camera()].look_vector())?;
+        let to_source = source_position - self.position(graph);
+
+        let look = Vector3::new(look.x, 0.0, look.z).try_normalize(f32::EPSILON)?;
+        let to_source = Vector3::new(to_source.x, 0.0, to_source.z).try_normalize(f32::EPSILON)?;
+
+        Some(look.cross(&to_source).y.atan2(look.dot(&to_source)))
+    }
+
+    /// Takes a grenade from the inventory and launches it with the given speed. The fuse of the
+    /// grenade is shortened by the time the grenade was cooked.
+    fn throw_grenade(
+        &mut self,
+        scene: &mut Scene,
+        resource_manager: &ResourceManager,
+        self_handle: Handle<Node>,
+        speed: f32,
+    ) {
+        let cook_time = self.grenade_cook_time.take().unwrap_or_default();
+        self.controller.toss_grenade = false;
+
+        let position = scene.graph[self.weapon_pivot].global_position();
+
+        let direction = scene
+            .graph
+            .try_get(self.camera_controller)
+            .and_then(|c| c.try_get_script::<CameraController>())
+            .map(|c| scene.graph[c.camera()].look_vector())
+            .unwrap_or_default();
+
+        let Some(grenade_item) = self.grenade_item.deref().clone() else {
+            return;
+        };
+
+        if self.inventory.try_extract_exact_items(&grenade_item, 1) != 1 {
+            return;
+        }
+
+        if let Ok(grenade) = block_on(resource_manager.request::<Model>(GRENADE_PROJECTILE)) {
+            let grenade = Projectile::spawn(
+                &grenade,
+                scene,
+                direction,
+                position,
+                self_handle,
+                direction.scale(speed),
+                None,
+            );
+
+            if let Some(projectile) = scene.graph.try_get_script_of_mut::<Projectile>(grenade) {
+                projectile.set_fuse_time(self.grenade_fuse_time);
+                projectile.cook(cook_time);
+            }
+        }
+    }
+
+    /// Places a proximity mine on the ground in front of the player. Does nothing if there's no
+    /// suitable ground, if the player has no mines, or if too many mines are placed already.
+    fn place_mine(&mut self, scene: &mut Scene, self_handle: Handle<Node>) {
+        let Some(mine_prefab) = self.mine_prefab.deref().clone() else {
+            return;
+        };
+        let Some(mine_item) = self.mine_item.deref().clone() else {
+            return;
+        };
+
+        // Forget about mines, that have exploded already.
+        self.active_mines.retain(|mine| {
+            scene
+                .graph
+                .try_get_script_of::<ProximityMine>(*mine)
+                .is_some()
+        });
+
+        if self.active_mines.len() >= *self.max_active_mines
+            || self.inventory.item_count(&mine_item) == 0
+        {
+            return;
+        }
+
+        let forward = scene
+            .graph
+            .try_get(self.camera_controller)
+            .and_then(|c| c.try_get_script::<CameraController>())
+            .and_then(|c| {
+                let look = scene.graph[c.camera()].look_vector();
+                Vector3::new(look.x, 0.0, look.z).try_normalize(f32::EPSILON)
+            })
+            .unwrap_or_default();
+
+        let ray_origin = self.position(&scene.graph)
+            + forward.scale(MINE_PLACEMENT_DISTANCE)
+            + Vector3::new(0.0, 0.5, 0.0);
+
+        let mut query_buffer = Vec::default();
+        scene.graph.physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(ray_origin),
+                ray_direction: -Vector3::y(),
+                max_len: 1.5,
+                groups: Default::default(),
+                sort_results: true,
+            },
+            &mut query_buffer,
+        );
+
+        let Some(ground) = query_buffer.iter().find(|intersection| {
+            intersection.collider != self.capsule_collider
+                && !self.hit_boxes.contains(&intersection.collider)
+                && scene
+                    .graph
+                    .try_get_of_type::<Collider>(intersection.collider)
+                    .is_some_and(|collider| !collider.is_sensor())
+        }) else {
+            return;
+        };
+
+        if self.inventory.try_extract_exact_items(&mine_item, 1) != 1 {
+            return;
+        }
+
+        let mine = mine_prefab.instantiate_at(
+            scene,
+            ground.position.coords,
+            vector_to_quat(ground.normal),
+        );
+        if let Some(mine_script) = scene.graph.try_get_script_of_mut::<ProximityMine>(mine) {
+            mine_script.set_owner(self_handle);
+        }
+        self.active_mines.push(mine);
+    }
+
+    /// Starts cooking a grenade. The fuse time is taken from the grenade projectile prefab, or
+    /// the default one is used if the prefab has no fuse.
+    fn draw_grenade(&mut self, resource_manager: &ResourceManager) {
+        let fuse_time = block_on(resource_manager.request::<Model>(GRENADE_PROJECTILE))
+            .ok()
+            .map(|grenade| {
+                Projectile::from_resource(&grenade, |p| p.map_or(0.0, |p| p.fuse_time()))
+            })
+            .unwrap_or_default();
+
+        self.grenade_fuse_time = if fuse_time > 0.0 {
+            fuse_time
+        } else {
+            DEFAULT_GRENADE_FUSE_TIME
+        };
+        self.grenade_cook_time = Some(0.0);
+    }
+
+    fn update_grenade_cooking(
+        &mut self,
+        scene: &mut Scene,
+        resource_manager: &ResourceManager,
+        self_handle: Handle<Node>,
+        dt: f32,
+    ) {
+        let Some(cook_time) = self.grenade_cook_time.as_mut() else {
+            return;
+        };
+
+        *cook_time += dt;
+
+        if *cook_time >= self.grenade_fuse_time {
+            // Cooked for too long, the grenade explodes right in the hands.
+            self.throw_grenade(scene, resource_manager, self_handle, 0.0);
+        }
+    }
+
     pub fn persistent_data(&self, graph: &Graph) -> PlayerPersistentData {
         PlayerPersistentData {
             inventory: self.inventory.clone(),
@@ -324,7 +895,10 @@ impl Player {
                     (graph[node_handle].original_handle_in_resource(), *hb.health)
                 })
                 .collect::<FxHashMap<_, _>>(),
-            current_weapon: self.current_weapon,
+            current_weapon: self
+                .weapons
+                .get(self.current_weapon)
+                .and_then(|w| graph[*w].root_resource()),
             weapons: self
                 .weapons
                 .iter()
@@ -333,6 +907,101 @@ impl Player {
         }
     }
 
+    /// Restores the state of the player, that was taken on another level. Weapons are added using
+    /// messages, so they will appear on the next frame.
+    pub fn apply_persistent_data(
+        &mut self,
+        data: &PlayerPersistentData,
+        self_handle: Handle<Node>,
+        graph: &mut Graph,
+        script_message_sender: &ScriptMessageSender,
+    ) {
+        // Handles are different on every level, so hit boxes are matched by their handles in the
+        // prefab of the player.
+        let hit_boxes = self
+            .hit_box_iter(graph)
+            .map(|(handle, _)| handle)
+            .collect::<Vec<_>>();
+        for handle in hit_boxes {
+            let original_handle = graph[handle].original_handle_in_resource();
+            if let Some(health) = data.hit_box_health.get(&original_handle) {
+                if let Some(hit_box) = graph.try_get_script_of_mut::<HitBox>(handle) {
+                    *hit_box.health = *health;
+                }
+            }
+        }
+
+        self.inventory = data.inventory.clone();
+
+        for weapon in data.weapons.iter() {
+            let has_weapon = self
+                .weapons
+                .iter()
+                .any(|&w| graph[w].root_resource().as_ref() == Some(weapon));
+            if !has_weapon {
+                script_message_sender.send_to_target(
+                    self_handle,
+                    CharacterMessage {
+                        character: self_handle,
+                        data: CharacterMessageData::AddWeapon(weapon.clone()),
+                    },
+                );
+            }
+        }
+
+        if let Some(current_weapon) = data.current_weapon.as_ref() {
+            script_message_sender.send_to_target(
+                self_handle,
+                CharacterMessage {
+                    character: self_handle,
+                    data: CharacterMessageData::SelectWeapon(current_weapon.clone()),
+                },
+            );
+        }
+    }
+
+    fn update_interaction_target(
+        &mut self,
+        scene: &Scene,
+        level: &Level,
+        self_handle: Handle<Node>,
+        script_message_sender: &ScriptMessageSender,
+    ) {
+        let graph = &scene.graph;
+
+        let Some(camera) = graph
+            .try_get(self.camera_controller)
+            .and_then(|c| c.try_get_script::<CameraController>())
+            .and_then(|c| graph.try_get(c.camera()))
+        else {
+            return;
+        };
+
+        let target = interaction::find_interaction_target(
+            graph,
+            level,
+            self_handle,
+            graph[self.body].global_position(),
+            camera.global_position(),
+            camera.look_vector(),
+        );
+
+        if target != self.interaction_target {
+            self.interaction_target = target;
+
+            script_message_sender.send_global(InteractionPromptEvent {
+                player: self_handle,
+                target,
+            });
+        }
+    }
+
+    fn interaction_target_of_kind(&self, kind: InteractionTargetKind) -> Option<Handle<Node>> {
+        self.interaction_target
+            .filter(|target| target.kind == kind)
+            .map(|target| target.handle)
+    }
+
     fn check_items(
         &mut self,
         game: &mut Game,
@@ -340,86 +1009,71 @@ impl Player {
         self_handle: Handle<Node>,
         script_message_sender: &ScriptMessageSender,
     ) {
-        let items = &game.level.as_ref().unwrap().items;
-        for &item_handle in items.iter() {
-            if let Some(item_node) = scene.graph.try_get(item_handle) {
-                if !item_node.is_globally_enabled() {
-                    continue;
-                }
-
-                let item = item_node.try_get_script_component::<Item>().unwrap();
+        let Some(item_handle) = self.interaction_target_of_kind(InteractionTargetKind::Item) else {
+            return;
+        };
 
-                if !item.enabled {
-                    continue;
-                }
+        let Some(item_node) = scene.graph.try_get(item_handle) else {
+            return;
+        };
 
-                let self_position = scene.graph[self.body].global_position();
-                let item_position = item_node.global_position();
+        let Some(item) = item_node.try_get_script_component::<Item>() else {
+            return;
+        };
 
-                let distance = (item_position - self_position).norm();
-                if distance < 0.75 {
-                    if let Some(resource) = item_node.root_resource() {
-                        game.item_display.sync_to_model(
-                            resource,
-                            *item.stack_size,
-                            &game.config.controls,
-                        );
-                    }
+        let item_position = item_node.global_position();
 
-                    if self.controller.action {
-                        script_message_sender.send_to_target(
-                            self_handle,
-                            CharacterMessage {
-                                character: self_handle,
-                                data: CharacterMessageData::PickupItem(item_handle),
-                            },
-                        );
+        if let Some(resource) = item_node.root_resource() {
+            game.item_display
+                .sync_to_model(resource, *item.stack_size, &game.config.controls);
+        }
 
-                        self.controller.action = false;
-                    }
+        if self.controller.action {
+            script_message_sender.send_to_target(
+                self_handle,
+                CharacterMessage {
+                    character: self_handle,
+                    data: CharacterMessageData::PickupItem(item_handle),
+                },
+            );
 
-                    if let Some(display) = scene.graph.try_get_mut(self.item_display) {
-                        display
-                            .local_transform_mut()
-                            .set_position(item_position + Vector3::new(0.0, 0.2, 0.0));
-                        display.set_visibility(true);
-                    }
+            self.controller.action = false;
+        }
 
-                    break;
-                }
-            }
+        if let Some(display) = scene.graph.try_get_mut(self.item_display) {
+            display
+                .local_transform_mut()
+                .set_position(item_position + Vector3::new(0.0, 0.2, 0.0));
+            display.set_visibility(true);
         }
     }
 
-    fn check_doors(&mut self, scene: &mut Scene, door_container: &DoorContainer) {
-        let self_position = self.position(&scene.graph);
+    fn check_doors(&mut self, scene: &mut Scene) {
+        if !self.controller.action {
+            return;
+        }
 
-        if self.controller.action {
-            for &door_handle in &door_container.doors {
-                let door = door_mut(door_handle, &mut scene.graph);
-                let close_enough = self_position.metric_distance(&door.initial_position()) < 1.25;
-                if close_enough {
-                    door.try_open(Some(&self.inventory));
-                }
-            }
+        if let Some(door_handle) = self.interaction_target_of_kind(InteractionTargetKind::Door) {
+            door_mut(door_handle, &mut scene.graph).try_open(Some(&self.inventory));
         }
     }
 
     fn check_elevators(&self, scene: &mut Scene, elevators: &[Handle<Node>]) {
         let graph = &mut scene.graph;
-        let self_position = graph[self.body].global_position();
 
         for &elevator_handle in elevators.iter() {
             let mbc = graph.begin_multi_borrow();
 
             let mut elevator_node = mbc.try_get_mut(elevator_handle).unwrap();
-            let elevator_position = elevator_node.global_position();
 
             let elevator_script = elevator_node.try_get_script_mut::<Elevator>().unwrap();
 
             // Handle floors.
             let mut requested_floor = None;
-            if (elevator_position - self_position).norm() < 0.75 && self.controller.action {
+            if self.interaction_target_of_kind(InteractionTargetKind::Elevator)
+                == Some(elevator_handle)
+                && self.controller.action
+            {
                 let last_index = elevator_script.point_handles.len().saturating_sub(1) as u32;
                 if elevator_script.current_floor == last_index {
                     requested_floor = Some(0);
@@ -430,34 +1084,36 @@ impl Player {
 
             // Handle call buttons
             for &call_button_handle in elevator_script.call_buttons.iter() {
-                if let Ok(mut call_button_node) = mbc.try_get_mut(call_button_handle) {
-                    let button_position = call_button_node.global_position();
+                if !elevator_script.call_button_activated
+                    || self.interaction_target_of_kind(InteractionTargetKind::CallButton)
+                        != Some(call_button_handle)
+                {
+                    continue;
+                }
 
+                if let Ok(mut call_button_node) = mbc.try_get_mut(call_button_handle) {
                     let call_button_script =
                         call_button_node.try_get_script_mut::<CallButton>().unwrap();
 
-                    let distance = (button_position - self_position).norm();
-                    if distance < 0.75 {
-                        if let CallButtonKind::FloorSelector = call_button_script.kind {
-                            let new_floor = if self.controller.cursor_down {
-                                Some(call_button_script.floor.saturating_sub(1))
-                            } else if self.controller.cursor_up {
-                                Some(call_button_script.floor.saturating_add(1).min(
-                                    (elevator_script.point_handles.len() as u32).saturating_sub(1),
-                                ))
-                            } else {
-                                None
-                            };
-
-                            if let Some(new_floor) = new_floor {
-                                call_button_script.floor = new_floor;
-                            }
-                        }
+                    if let CallButtonKind::FloorSelector = call_button_script.kind {
+                        let new_floor = if self.controller.cursor_down {
+                            Some(call_button_script.floor.saturating_sub(1))
+                        } else if self.controller.cursor_up {
+                            Some(call_button_script.floor.saturating_add(1).min(
+                                (elevator_script.point_handles.len() as u32).saturating_sub(1),
+                            ))
+                        } else {
+                            None
+                        };
 
-                        if self.controller.action {
-                            requested_floor = Some(call_button_script.floor);
+                        if let Some(new_floor) = new_floor {
+                            call_button_script.floor = new_floor;
                         }
                     }
+
+                    if self.controller.action {
+                        requested_floor = Some(call_button_script.floor);
+                    }
                 } else {
                     Log::warn(format!("Unable to get call button {call_button_handle:?}!"));
                 }
@@ -533,6 +1189,7 @@ impl Player {
                             RequiredWeapon::None => (),
                             RequiredWeapon::Next => self.next_weapon(&mut scene.graph),
                             RequiredWeapon::Previous => self.prev_weapon(&mut scene.graph),
+                            RequiredWeapon::Last => self.switch_to_last_weapon(&mut scene.graph),
                             RequiredWeapon::Specific(weapon_resource) => {
                                 script_message_sender.send_to_target(
                                     self_handle,
@@ -548,32 +1205,12 @@ impl Player {
 
                         self.weapon_change_direction = RequiredWeapon::None;
                     } else if event.name == StateMachine::TOSS_GRENADE_SIGNAL {
-                        let position = scene.graph[self.weapon_pivot].global_position();
-
-                        let direction = scene
-                            .graph
-                            .try_get(self.camera_controller)
-                            .and_then(|c| c.try_get_script::<CameraController>())
-                            .map(|c| scene.graph[c.camera()].look_vector())
-                            .unwrap_or_default();
-
-                        if let Some(grenade_item) = self.grenade_item.deref().clone() {
-                            if self.inventory.try_extract_exact_items(&grenade_item, 1) == 1 {
-                                if let Ok(grenade) = block_on(
-                                    resource_manager
-                                        .request::<Model>("data/models/grenade/grenade_proj.rgs"),
-                                ) {
-                                    Projectile::spawn(
-                                        &grenade,
-                                        scene,
-                                        direction,
-                                        position,
-                                        self_handle,
-                                        direction.scale(10.0),
-                                    );
-                                }
-                            }
-                        }
+                        self.throw_grenade(
+                            scene,
+                            resource_manager,
+                            self_handle,
+                            GRENADE_THROW_SPEED,
+                        );
                     } else if event.name == StateMachine::HIT_STARTED_SIGNAL {
                         self.melee_attack_context = Some(Default::default());
                     } else if event.name == StateMachine::HIT_ENDED_SIGNAL {
@@ -586,8 +1223,12 @@ impl Player {
                         let begin = position + Vector3::new(0.0, 0.5, 0.0);
 
                         if is_walking && has_ground_contact {
-                            self.character
-                                .footstep_ray_check(begin, scene, sound_manager);
+                            self.character.footstep_ray_check(
+                                self_handle,
+                                begin,
+                                scene,
+                                sound_manager,
+                            );
                         }
                     }
                 }
@@ -625,6 +1266,15 @@ impl Player {
                 .scale(1.0 / dt);
         }
 
+        let mut speed_factor = 1.0 + (*self.crouch_speed_factor - 1.0) * self.crouch_factor;
+        if self.is_using_item() {
+            speed_factor *= *self.item_use_speed_factor;
+        }
+        speed_factor *= self.status_effects.movement_speed_factor();
+        speed_factor *= 1.0 + (*self.ads_speed_factor - 1.0) * self.ads_factor;
+        self.velocity.x *= speed_factor;
+        self.velocity.z *= speed_factor;
+
         let body = scene.graph[self.body].as_rigid_body_mut();
 
         body.set_ang_vel(Default::default());
@@ -661,16 +1311,21 @@ impl Player {
             is_walking,
             is_jumping,
             has_ground_contact: self.in_air_time <= 0.3,
-            is_aiming: self.controller.aim && !self.character.weapons.is_empty(),
+            is_aiming: self.is_aiming() && !self.character.weapons.is_empty(),
             run_factor: self.run_factor,
+            crouch_factor: self.crouch_factor,
             is_dead: self.is_dead(&scene.graph),
             // TODO: Handle stun properly.
             should_be_stunned: false,
-            melee_attack: self.controller.shoot && !self.controller.aim,
+            melee_attack: self.controller.shoot
+                && (!self.is_aiming() || weapon_kind == CombatWeaponKind::Melee),
             machine: self.machine,
             weapon_kind,
             toss_grenade: self.controller.toss_grenade,
             change_weapon: self.weapon_change_direction != RequiredWeapon::None,
+            // The weapon is changed at the grab signal of the remove animation, the rest of the
+            // switch is spent on drawing the new weapon.
+            draw_weapon: self.character.is_switching_weapon(),
             scene,
             local_velocity: self.local_velocity,
             hit_something: self
@@ -682,7 +1337,7 @@ impl Player {
     }
 
     fn calculate_model_angle(&self) -> f32 {
-        if self.controller.aim {
+        if self.is_aiming() {
             if self.controller.walk_left {
                 if self.controller.walk_backward {
                     -45.0
@@ -721,8 +1376,54 @@ impl Player {
         }
     }
 
+    /// Reports the hits of the previous frame and plays the hit sound. Kills have their own sounds,
+    /// that are played immediately.
+    fn report_hit_marker(
+        &mut self,
+        graph: &mut Graph,
+        script_message_sender: &ScriptMessageSender,
+    ) {
+        let Some(hit_marker) = self.hit_marker.take() else {
+            return;
+        };
+
+        if !hit_marker.is_kill {
+            utils::try_play_sound(*self.hit_marker_sound, graph);
+        }
+
+        script_message_sender.send_global(hit_marker);
+    }
+
+    /// Kicks the view of the player by the given recoil angles (in radians). The kick is
+    /// recovered over time by [`Self::update_recoil_recovery`].
+    fn kick_view(&mut self, h_recoil: f32, v_recoil: f32) {
+        let yaw = h_recoil * *self.camera_kick_factor;
+        let pitch = self.target_pitch;
+        self.target_yaw += yaw;
+        self.target_pitch = (self.target_pitch - v_recoil * *self.camera_kick_factor)
+            .clamp(-90.0f32.to_radians(), 90.0f32.to_radians());
+        self.recoil_kick += Vector2::new(yaw, self.target_pitch - pitch);
+    }
+
+    /// Pulls the view back towards its orientation before the recoil kick.
+    fn update_recoil_recovery(&mut self, dt: f32) {
+        let kick = self.recoil_kick.norm();
+        if kick <= f32::EPSILON {
+            return;
+        }
+
+        let step = self
+            .recoil_kick
+            .scale((self.recoil_recovery_speed.to_radians() * dt).min(kick) / kick);
+        self.recoil_kick -= step;
+        self.target_yaw -= step.x;
+        self.target_pitch =
+            (self.target_pitch - step.y).clamp(-90.0f32.to_radians(), 90.0f32.to_radians());
+    }
+
     fn update_shooting(
         &mut self,
+        self_handle: Handle<Node>,
         scene: &mut Scene,
         dt: f32,
         elapsed_time: f32,
@@ -730,10 +1431,26 @@ impl Player {
     ) {
         self.v_recoil.update(dt);
         self.h_recoil.update(dt);
+        self.update_recoil_recovery(dt);
 
         if let Some(&current_weapon_handle) =
             self.character.weapons.get(self.character.current_weapon)
         {
+            // Moving, aiming down the sights and recoil affect accuracy.
+            let ads_spread_factor =
+                self.ads_weapon_property(&scene.graph, |weapon| weapon.ads_spread_factor());
+            let speed = Vector2::new(self.velocity.x, self.velocity.z).norm();
+            let recoil_angle =
+                self.v_recoil.angle().abs().to_degrees() + self.h_recoil.angle().abs().to_degrees();
+            let weapon = weapon_mut(current_weapon_handle, &mut scene.graph);
+            let spread_factor = weapon.spread_factor(ads_spread_factor, speed, recoil_angle);
+            weapon.set_spread_factor(spread_factor);
+            script_message_sender.send_global(CrosshairSpreadEvent {
+                player: self_handle,
+                weapon: current_weapon_handle,
+                spread: weapon.current_spread(),
+            });
+
             let aiming = self
                 .state_machine
                 .upper_body_layer(&scene.graph)
@@ -750,45 +1467,65 @@ impl Player {
                     .set_position(ammo_indicator_offset);
 
                 let current_weapon = weapon_ref(current_weapon_handle, &scene.graph);
-                if self.controller.shoot && current_weapon.can_shoot(elapsed_time) {
-                    let ammo_per_shot = *current_weapon.ammo_consumption_per_shot;
-
-                    // A weapon could have infinite ammo, in this case ammo item is not specified.
-                    let enough_ammo = current_weapon.ammo_item.as_ref().is_none_or(|ammo_item| {
-                        self.inventory
-                            .try_extract_exact_items(ammo_item, ammo_per_shot)
-                            == ammo_per_shot
-                    });
-
-                    if enough_ammo {
-                        script_message_sender.send_to_target(
-                            current_weapon_handle,
-                            WeaponMessage {
-                                weapon: current_weapon_handle,
-                                data: WeaponMessageData::Shoot {
-                                    direction: Default::default(),
-                                },
+
+                // Charged weapons accumulate charge while the trigger is held and fire when it
+                // is released.
+                if self.controller.shoot
+                    && current_weapon.is_chargeable()
+                    && !current_weapon.is_charging()
+                    && current_weapon.can_shoot(elapsed_time)
+                {
+                    weapon_mut(current_weapon_handle, &mut scene.graph).begin_charge();
+                }
+
+                let current_weapon = weapon_ref(current_weapon_handle, &scene.graph);
+                let fire = if current_weapon.is_chargeable() {
+                    !self.controller.shoot && current_weapon.is_charging()
+                } else {
+                    self.controller.shoot && current_weapon.can_shoot(elapsed_time)
+                };
+
+                if fire {
+                    script_message_sender.send_to_target(
+                        current_weapon_handle,
+                        WeaponMessage {
+                            weapon: current_weapon_handle,
+                            data: WeaponMessageData::Shoot {
+                                direction: Default::default(),
+                                spread_factor,
                             },
-                        );
+                        },
+                    );
 
-                        if *current_weapon.shake_camera_on_shot {
-                            self.v_recoil
-                                .set_target(current_weapon.gen_v_recoil_angle());
-                            self.h_recoil
-                                .set_target(current_weapon.gen_h_recoil_angle());
-
-                            if let Some(camera_controller) = scene
-                                .graph
-                                .try_get_mut(self.camera_controller)
-                                .and_then(|c| c.try_get_script_mut::<CameraController>())
-                            {
-                                camera_controller.request_shake_camera();
-                            }
+                    if *current_weapon.shake_camera_on_shot {
+                        let recoil_factor =
+                            1.0 + (*self.crouch_recoil_factor - 1.0) * self.crouch_factor;
+                        let v_recoil = current_weapon.gen_v_recoil_angle() * recoil_factor;
+                        let h_recoil = current_weapon.gen_h_recoil_angle() * recoil_factor;
+                        self.v_recoil.set_target(v_recoil);
+                        self.h_recoil.set_target(h_recoil);
+                        self.kick_view(h_recoil, v_recoil);
+
+                        if let Some(camera_controller) = scene
+                            .graph
+                            .try_get_mut(self.camera_controller)
+                            .and_then(|c| c.try_get_script_mut::<CameraController>())
+                        {
+                            camera_controller.request_shake_camera();
                         }
                     }
+                } else if self.controller.shoot
+                    && current_weapon.is_magazine_empty()
+                    && !current_weapon.is_reloading()
+                {
+                    // Reload automatically when trying to shoot with an empty magazine.
+                    weapon_mut(current_weapon_handle, &mut scene.graph).reload(&self.inventory);
                 }
             } else {
                 scene.graph[self.weapon_display].set_visibility(false);
+
+                // Lowering the weapon drops the charge.
+                weapon_mut(current_weapon_handle, &mut scene.graph).cancel_charge();
             }
         }
     }
@@ -805,8 +1542,63 @@ impl Player {
         }
     }
 
-    fn apply_weapon_angular_correction(&mut self, scene: &mut Scene, can_move: bool, dt: f32) {
-        if self.controller.aim {
+    /// Calculates procedural offsets of the weapon: the sway (lag behind the rotation of the
+    /// camera) and the bob (sinusoidal offset while walking). Returns sway angles (yaw and pitch)
+    /// and bob offset.
+    fn update_weapon_sway_and_bob(
+        &mut self,
+        is_walking: bool,
+        dt: f32,
+    ) -> (Vector2<f32>, Vector3<f32>) {
+        // Max angle (in radians) of the sway.
+        const MAX_SWAY: f32 = 0.1;
+        // Horizontal speed (in m/s) at which the bob has its nominal frequency and amplitude.
+        const NOMINAL_WALK_SPEED: f32 = 1.5;
+
+        let camera_angles = Vector2::new(self.target_yaw, self.target_pitch);
+        let angular_velocity = if dt > 0.0 {
+            (camera_angles - self.prev_camera_angles).scale(1.0 / dt)
+        } else {
+            Vector2::default()
+        };
+        self.prev_camera_angles = camera_angles;
+
+        let target_sway = (-angular_velocity * *self.weapon_sway_amount)
+            .map(|angle| angle.clamp(-MAX_SWAY, MAX_SWAY));
+        self.weapon_sway += (target_sway - self.weapon_sway) * (10.0 * dt).min(1.0);
+
+        let speed = Vector2::new(self.velocity.x, self.velocity.z).norm();
+        let target_bob_factor = if is_walking {
+            (speed / NOMINAL_WALK_SPEED).min(2.0)
+        } else {
+            0.0
+        };
+        // Smoothly fade the bob in and out, so the weapon won't jump when the player stops.
+        self.weapon_bob_factor +=
+            (target_bob_factor - self.weapon_bob_factor) * (5.0 * dt).min(1.0);
+        self.weapon_bob_phase = (self.weapon_bob_phase
+            + dt * *self.weapon_bob_frequency * std::f32::consts::TAU * self.weapon_bob_factor)
+            % std::f32::consts::TAU;
+
+        let amplitude = *self.weapon_bob_amplitude * self.weapon_bob_factor;
+        let bob = Vector3::new(
+            self.weapon_bob_phase.sin() * amplitude,
+            // Vertical bob has double frequency - a weapon goes down on every step.
+            -(self.weapon_bob_phase * 2.0).cos().abs() * amplitude,
+            0.0,
+        );
+
+        (self.weapon_sway, bob)
+    }
+
+    fn apply_weapon_angular_correction(
+        &mut self,
+        scene: &mut Scene,
+        can_move: bool,
+        is_walking: bool,
+        dt: f32,
+    ) {
+        if self.is_aiming() {
             let (pitch_correction, yaw_correction) = if let Some(weapon) = scene
                 .graph
                 .try_get_script_of::<Weapon>(self.current_weapon())
@@ -825,30 +1617,259 @@ impl Player {
             self.weapon_pitch_correction.set_target(8.0f32.to_radians());
         }
 
+        let (sway, bob) = self.update_weapon_sway_and_bob(is_walking && can_move, dt);
+
+        // Weapon is much steadier while aiming down the sights.
+        let steadiness = 1.0 - 0.75 * self.ads_factor;
+        let sway = sway.scale(steadiness);
+        let ads_offset = scene
+            .graph
+            .try_get_script_of::<Weapon>(self.current_weapon())
+            .map(|weapon| weapon.ads_offset.scale(self.ads_factor))
+            .unwrap_or_default();
+        let bob = bob.scale(steadiness) + ads_offset;
+
+        let Some(weapon_pivot) = scene.graph.try_get_mut(self.weapon_pivot) else {
+            return;
+        };
+
+        let origin = *self
+            .weapon_pivot_origin
+            .get_or_insert_with(|| **weapon_pivot.local_transform().position());
+        weapon_pivot
+            .local_transform_mut()
+            .set_position(origin + bob);
+
         if can_move {
-            let yaw_correction_angle = self.weapon_yaw_correction.update(dt).angle();
-            let pitch_correction_angle = self.weapon_pitch_correction.update(dt).angle();
-            scene.graph[self.weapon_pivot]
-                .local_transform_mut()
-                .set_rotation(
-                    UnitQuaternion::from_axis_angle(&Vector3::y_axis(), yaw_correction_angle)
-                        * UnitQuaternion::from_axis_angle(
-                            &Vector3::x_axis(),
-                            pitch_correction_angle,
-                        ),
-                );
+            let yaw_correction_angle = self.weapon_yaw_correction.update(dt).angle() + sway.x;
+            let pitch_correction_angle = self.weapon_pitch_correction.update(dt).angle() + sway.y;
+            weapon_pivot.local_transform_mut().set_rotation(
+                UnitQuaternion::from_axis_angle(&Vector3::y_axis(), yaw_correction_angle)
+                    * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), pitch_correction_angle),
+            );
+        }
+    }
+
+    pub fn stamina(&self) -> f32 {
+        self.stamina
+    }
+
+    pub fn max_stamina(&self) -> f32 {
+        *self.max_stamina
+    }
+
+    pub fn flash_light_charge(&self) -> f32 {
+        self.flash_light_charge
+    }
+
+    pub fn flash_light_battery_capacity(&self) -> f32 {
+        *self.flash_light_battery_capacity
+    }
+
+    /// Instantly restores the given amount of charge of the flash light battery.
+    pub fn recharge_flash_light(&mut self, amount: f32) {
+        self.flash_light_charge =
+            (self.flash_light_charge + amount).clamp(0.0, *self.flash_light_battery_capacity);
+    }
+
+    fn update_flash_light_battery(&mut self, dt: f32) {
+        if *self.flash_light_enabled {
+            self.flash_light_charge -= *self.flash_light_drain_rate * dt;
+        } else {
+            self.flash_light_charge += *self.flash_light_recharge_rate * dt;
+        }
+        self.flash_light_charge = self
+            .flash_light_charge
+            .clamp(0.0, *self.flash_light_battery_capacity);
+
+        if self.flash_light_charge <= 0.0 {
+            // Keep the flash light off until some charge is restored.
+            self.flash_light_depleted = true;
+            self.flash_light_enabled.set_value_and_mark_modified(false);
+        } else if self.flash_light_depleted
+            && self.flash_light_charge >= *self.flash_light_recovery_threshold
+        {
+            self.flash_light_depleted = false;
+        }
+    }
+
+    fn can_sprint(&self) -> bool {
+        !self.exhausted && self.stamina > 0.0
+    }
+
+    fn wants_to_sprint(&self) -> bool {
+        self.controller.run && !self.controller.crouch && self.can_sprint()
+    }
+
+    fn update_health_regen(
+        &mut self,
+        graph: &Graph,
+        script_message_sender: &ScriptMessageSender,
+        difficulty: Difficulty,
+        dt: f32,
+    ) {
+        self.regen_timer += dt;
+
+        let rate = *self.regen_rate * difficulty.health_regen_multiplier();
+        if rate <= 0.0 || self.regen_timer < *self.regen_delay {
+            return;
+        }
+
+        let max_health = self.full_health * *self.regen_cap;
+        let amount = (max_health - self.combined_health(graph)).min(rate * dt);
+        if amount <= 0.0 {
+            return;
+        }
+
+        if let Some(hit_box) = self.most_wounded_hit_box(graph) {
+            script_message_sender
+                .send_to_target(hit_box, HitBoxMessage::Heal(HitBoxHeal { hit_box, amount }));
+        }
+    }
+
+    fn update_stamina(&mut self, is_sprinting: bool, dt: f32) {
+        if is_sprinting {
+            self.stamina -= *self.stamina_drain_rate * dt;
+        } else {
+            self.stamina += *self.stamina_regen_rate * dt;
+        }
+        self.stamina = self.stamina.clamp(0.0, *self.max_stamina);
+
+        if self.stamina <= 0.0 {
+            // Force the player to walk until some stamina is restored.
+            self.exhausted = true;
+        } else if self.exhausted && self.stamina >= *self.stamina_recovery_threshold {
+            self.exhausted = false;
+        }
+    }
+
+    pub fn is_crouching(&self) -> bool {
+        self.crouch_factor > 0.5
+    }
+
+    /// Returns a distance (in meters) by which the camera should be lowered because of crouching.
+    pub fn crouch_camera_offset(&self) -> f32 {
+        self.standing_capsule
+            .as_ref()
+            .map(|capsule| {
+                let standing_height = capsule_height(capsule);
+                (standing_height - *self.crouch_height).max(0.0) * self.crouch_factor
+            })
+            .unwrap_or_default()
+    }
+
+    fn is_under_obstacle(&self, graph: &Graph, clearance: f32) -> bool {
+        let Some(collider) = graph.try_get_of_type::<Collider>(self.capsule_collider) else {
+            return false;
+        };
+        let ColliderShape::Capsule(capsule) = collider.shape() else {
+            return false;
+        };
+
+        let ray_origin = collider
+            .global_transform()
+            .transform_point(&Point3::from(capsule.end));
+        let ray_direction = Vector3::new(0.0, clearance + capsule.radius, 0.0);
+
+        let mut query_buffer = Vec::default();
+        graph.physics.cast_ray(
+            RayCastOptions {
+                ray_origin,
+                ray_direction,
+                max_len: ray_direction.norm(),
+                groups: Default::default(),
+                sort_results: true,
+            },
+            &mut query_buffer,
+        );
+
+        query_buffer.iter().any(|intersection| {
+            intersection.collider != self.capsule_collider
+                && !self.hit_boxes.contains(&intersection.collider)
+                && graph
+                    .try_get_of_type::<Collider>(intersection.collider)
+                    .is_some_and(|collider| !collider.is_sensor())
+        })
+    }
+
+    fn update_crouch(&mut self, graph: &mut Graph, dt: f32) {
+        let Some(standing_capsule) = self.standing_capsule.clone() else {
+            return;
+        };
+
+        let standing_height = capsule_height(&standing_capsule);
+        let height_difference = (standing_height - *self.crouch_height).max(0.0);
+
+        let mut target_factor = if self.controller.crouch { 1.0 } else { 0.0 };
+        if target_factor < self.crouch_factor
+            && self.is_under_obstacle(graph, height_difference * self.crouch_factor)
+        {
+            // Do not let the player stand up if there is an obstacle above.
+            target_factor = self.crouch_factor;
+        }
+
+        let step = *self.crouch_transition_speed * dt;
+        self.crouch_factor += (target_factor - self.crouch_factor).clamp(-step, step);
+
+        if let Some(collider) = graph.try_get_mut_of_type::<Collider>(self.capsule_collider) {
+            let mut capsule = standing_capsule;
+            // Keep the bottom of the capsule in place and lower its top.
+            capsule.end.y -= height_difference * self.crouch_factor;
+            if capsule.end.y < capsule.begin.y {
+                capsule.end.y = capsule.begin.y;
+            }
+            collider.set_shape(ColliderShape::Capsule(capsule));
         }
     }
 
     fn is_running(&self, scene: &Scene) -> bool {
         !self.is_dead(&scene.graph)
-            && self.controller.run
-            && !self.controller.aim
+            && self.wants_to_sprint()
+            && !self.is_aiming()
             && !self.state_machine.is_stunned(scene, self.animation_player)
     }
 
     pub fn is_aiming(&self) -> bool {
-        self.controller.aim
+        self.controller.aim || self.controller.aim_down_sights
+    }
+
+    /// Returns `true` if the player aims down the sights of a ranged weapon.
+    pub fn is_aiming_down_sights(&self, graph: &Graph) -> bool {
+        self.controller.aim_down_sights
+            && graph
+                .try_get_script_of::<Weapon>(self.current_weapon())
+                .is_some_and(|weapon| !weapon.is_melee())
+    }
+
+    /// Returns a value in `[0; 1]` range, where zero means that the player does not aim down the
+    /// sights and one means that the transition to the sights is fully done.
+    pub fn ads_factor(&self) -> f32 {
+        self.ads_factor
+    }
+
+    /// Returns the field of view (in degrees) of the camera that should be used when the player is
+    /// fully aiming down the sights of the current weapon.
+    pub fn ads_fov(&self, graph: &Graph) -> Option<f32> {
+        graph
+            .try_get_script_of::<Weapon>(self.current_weapon())
+            .map(|weapon| weapon.ads_fov())
+    }
+
+    fn ads_weapon_property(&self, graph: &Graph, func: impl FnOnce(&Weapon) -> f32) -> f32 {
+        let value = graph
+            .try_get_script_of::<Weapon>(self.current_weapon())
+            .map(func)
+            .unwrap_or(1.0);
+        1.0 + (value - 1.0) * self.ads_factor
+    }
+
+    fn update_ads(&mut self, graph: &Graph, dt: f32) {
+        let target = if self.is_aiming_down_sights(graph) {
+            1.0
+        } else {
+            0.0
+        };
+        self.ads_factor += (target - self.ads_factor) * (*self.ads_transition_speed * dt).min(1.0);
     }
 
     pub fn resolve(
@@ -924,6 +1945,13 @@ impl ScriptTrait for Player {
             self.inventory.add_item(&grenade_item, 10);
         }
 
+        // Scripts are initialized only on fresh levels, saved games keep the position of the player.
+        if let Some((position, rotation)) = find_player_spawn_point(&ctx.scene.graph) {
+            self.character.set_position(&mut ctx.scene.graph, position);
+            let look = rotation * Vector3::z();
+            self.target_yaw = look.x.atan2(look.z);
+        }
+
         let level = ctx.plugins.get_mut::<Game>().level.as_mut().unwrap();
 
         level.actors.push(ctx.handle);
@@ -942,10 +1970,26 @@ impl ScriptTrait for Player {
             .subscribe_to::<WeaponMessage>(ctx.handle);
         ctx.message_dispatcher
             .subscribe_to::<HitBoxMessage>(ctx.handle);
+        ctx.message_dispatcher.subscribe_to::<KillEvent>(ctx.handle);
+        ctx.message_dispatcher
+            .subscribe_to::<DamageEvent>(ctx.handle);
+        ctx.message_dispatcher
+            .subscribe_to::<LowAmmoWarning>(ctx.handle);
 
         self.script_message_sender = Some(ctx.message_sender.clone());
         self.state_machine = StateMachine::new(self.machine, &ctx.scene.graph).unwrap();
+        self.standing_capsule = ctx
+            .scene
+            .graph
+            .try_get_of_type::<Collider>(self.capsule_collider)
+            .and_then(|collider| match collider.shape() {
+                ColliderShape::Capsule(capsule) => Some(capsule.clone()),
+                _ => None,
+            });
         self.inventory_gui = InventoryInterface::new();
+        if self.full_health <= 0.0 {
+            self.full_health = self.combined_health(&ctx.scene.graph);
+        }
 
         self.resolve(
             ctx.scene,
@@ -954,6 +1998,11 @@ impl ScriptTrait for Player {
             game.item_display.render_target.clone(),
             game.journal_display.render_target.clone(),
         );
+
+        // Health, inventory and weapons of the player on the previous level of the campaign.
+        if let Some(data) = ctx.plugins.get_mut::<Game>().player_persistent_data.take() {
+            self.apply_persistent_data(&data, ctx.handle, &mut ctx.scene.graph, ctx.message_sender);
+        }
     }
 
     fn on_deinit(&mut self, ctx: &mut ScriptDeinitContext) {
@@ -1013,7 +2062,15 @@ impl ScriptTrait for Player {
                     Some((ControlButton::Mouse(button as u16), state))
                 }
                 DeviceEvent::MouseMotion { delta } => {
-                    let mouse_sens = control_scheme.mouse_sens * ctx.dt;
+                    let mouse_sens = control_scheme.mouse_sens
+                        * ctx.dt
+                        * self.ads_weapon_property(&ctx.scene.graph, |weapon| {
+                            *weapon.ads_sensitivity_factor
+                        });
+                    if delta.0 != 0.0 || delta.1 != 0.0 {
+                        // The player takes control over the view, do not fight with them.
+                        self.recoil_kick = Default::default();
+                    }
                     self.target_yaw -= (delta.0 as f32) * mouse_sens;
                     let pitch_direction = if control_scheme.mouse_y_inverse {
                         -1.0
@@ -1055,6 +2112,12 @@ impl ScriptTrait for Player {
                     ctx.scene.graph[self.inventory_display].set_visibility(false);
                     ctx.scene.graph[self.journal_display].set_visibility(false);
                 }
+            } else if button == control_scheme.aim_down_sights.button {
+                self.controller.aim_down_sights = state == ElementState::Pressed;
+                if state == ElementState::Pressed {
+                    ctx.scene.graph[self.inventory_display].set_visibility(false);
+                    ctx.scene.graph[self.journal_display].set_visibility(false);
+                }
             } else if button == control_scheme.move_forward.button {
                 self.controller.walk_forward = state == ElementState::Pressed;
             } else if button == control_scheme.move_backward.button {
@@ -1067,11 +2130,15 @@ impl ScriptTrait for Player {
                 self.controller.jump = state == ElementState::Pressed && can_jump;
             } else if button == control_scheme.run.button {
                 self.controller.run = state == ElementState::Pressed;
+            } else if button == control_scheme.crouch.button {
+                self.controller.crouch = state == ElementState::Pressed;
             } else if button == control_scheme.flash_light.button {
                 if state == ElementState::Pressed {
                     let enabled = *self.flash_light_enabled;
-                    self.flash_light_enabled
-                        .set_value_and_mark_modified(!enabled);
+                    if enabled || !self.flash_light_depleted {
+                        self.flash_light_enabled
+                            .set_value_and_mark_modified(!enabled);
+                    }
                 }
             } else if button == control_scheme.grab_ak47.button && can_change_weapon {
                 if current_weapon_kind != self.ak47_weapon {
@@ -1108,15 +2175,32 @@ impl ScriptTrait for Player {
                 if state == ElementState::Pressed && self.current_weapon > 0 && can_change_weapon {
                     weapon_change_direction = Some(RequiredWeapon::Previous);
                 }
+            } else if button == control_scheme.last_weapon.button {
+                if state == ElementState::Pressed && self.has_last_weapon() && can_change_weapon {
+                    weapon_change_direction = Some(RequiredWeapon::Last);
+                }
             } else if button == control_scheme.toss_grenade.button {
-                if let Some(grenade_item) = self.grenade_item.as_ref() {
-                    if self.inventory.item_count(grenade_item) > 0 {
-                        self.controller.toss_grenade = state == ElementState::Pressed;
+                if state == ElementState::Pressed {
+                    // The grenade is cooked while the button is held and thrown on release.
+                    if let Some(grenade_item) = self.grenade_item.as_ref() {
+                        if self.grenade_cook_time.is_none()
+                            && !self.controller.toss_grenade
+                            && self.inventory.item_count(grenade_item) > 0
+                        {
+                            self.draw_grenade(ctx.resource_manager);
+                        }
                     }
+                } else if self.grenade_cook_time.is_some() {
+                    self.controller.toss_grenade = true;
+                }
+            } else if button == control_scheme.place_mine.button {
+                if state == ElementState::Pressed && !self.is_using_item() {
+                    self.place_mine(ctx.scene, ctx.handle);
                 }
             } else if button == control_scheme.quick_heal.button {
                 let most_wounded = self.most_wounded_hit_box(&ctx.scene.graph);
-                if state == ElementState::Pressed && most_wounded.is_some() {
+                if state == ElementState::Pressed && most_wounded.is_some() && !self.is_using_item()
+                {
                     let mut min_health = f32::MAX;
                     let mut suitable_item = None;
                     for item in self.inventory.items() {
@@ -1140,13 +2224,24 @@ impl ScriptTrait for Player {
                             == 1
                         {
                             Item::from_resource(&suitable_item, |item| {
-                                self.use_item(item.unwrap(), &ctx.scene.graph, ctx.message_sender);
+                                self.begin_use_item(
+                                    &suitable_item,
+                                    item.unwrap(),
+                                    &ctx.scene.graph,
+                                    ctx.message_sender,
+                                );
                             })
                         }
                     }
                 }
             } else if button == control_scheme.shoot.button {
                 self.controller.shoot = state == ElementState::Pressed;
+            } else if button == control_scheme.reload.button {
+                if state == ElementState::Pressed {
+                    if let Some(&current_weapon) = self.weapons.get(self.current_weapon) {
+                        weapon_mut(current_weapon, &mut ctx.scene.graph).reload(&self.inventory);
+                    }
+                }
             } else if button == control_scheme.cursor_up.button {
                 self.controller.cursor_up = state == ElementState::Pressed;
             } else if button == control_scheme.cursor_down.button {
@@ -1155,7 +2250,7 @@ impl ScriptTrait for Player {
                 self.controller.action = state == ElementState::Pressed;
             } else if button == control_scheme.inventory.button
                 && state == ElementState::Pressed
-                && !self.controller.aim
+                && !self.is_aiming()
             {
                 ctx.scene.graph[self.journal_display].set_visibility(false);
 
@@ -1164,7 +2259,7 @@ impl ScriptTrait for Player {
                 inventory.set_visibility(new_visibility);
             } else if button == control_scheme.journal.button
                 && state == ElementState::Pressed
-                && !self.controller.aim
+                && !self.is_aiming()
             {
                 ctx.scene.graph[self.inventory_display].set_visibility(false);
 
@@ -1204,12 +2299,61 @@ impl ScriptTrait for Player {
         } else if let Some(weapon_message) = message.downcast_ref() {
             self.character
                 .on_weapon_message(weapon_message, &mut ctx.scene.graph);
+        } else if let Some(HitBoxMessage::Damage(damage)) = message.downcast_ref::<HitBoxMessage>()
+        {
+            // Taking damage interrupts healing.
+            self.character.interrupt_item_use();
+            self.regen_timer = 0.0;
+
+            ctx.message_sender.send_global(DamageIndicatorEvent {
+                player: ctx.handle,
+                amount: damage.damage,
+                angle: self.damage_source_angle(damage, &ctx.scene.graph, ctx.handle),
+            });
+        } else if let Some(damage) = message.downcast_ref::<DamageEvent>() {
+            if damage.shooter == ctx.handle
+                && damage.victim.is_some()
+                && damage.victim != ctx.handle
+            {
+                let hit_marker = self.hit_marker.get_or_insert(HitMarkerEvent {
+                    player: ctx.handle,
+                    victim: damage.victim,
+                    is_critical: false,
+                    is_kill: false,
+                });
+                hit_marker.victim = damage.victim;
+                hit_marker.is_critical |= damage.is_critical;
+            }
+        } else if let Some(kill) = message.downcast_ref::<KillEvent>() {
+            if kill.killer == ctx.handle {
+                if let Some(hit_marker) = self.hit_marker.as_mut() {
+                    hit_marker.is_kill = true;
+                }
+
+                if kill.is_head_shot {
+                    ctx.plugins
+                        .get_mut::<Game>()
+                        .weapon_display
+                        .show_head_shot_marker();
+                    utils::try_play_sound(*self.head_shot_sound, &mut ctx.scene.graph);
+                } else {
+                    utils::try_play_sound(*self.kill_marker_sound, &mut ctx.scene.graph);
+                }
+            }
+        } else if let Some(warning) = message.downcast_ref::<LowAmmoWarning>() {
+            if warning.owner == ctx.handle {
+                ctx.plugins
+                    .get_mut::<Game>()
+                    .weapon_display
+                    .flash_low_ammo_warning();
+            }
         }
     }
 
     fn on_update(&mut self, ctx: &mut ScriptContext) {
         self.inventory_gui.update(ctx.dt, &self.character.inventory);
         self.render_offscreen_ui(ctx);
+        self.report_hit_marker(&mut ctx.scene.graph, ctx.message_sender);
 
         let game = ctx.plugins.get_mut::<Game>();
         game.weapon_display.sync_to_model(self, &ctx.scene.graph);
@@ -1220,24 +2364,24 @@ impl ScriptTrait for Player {
 
         self.target_local_velocity = Vector2::default();
         if self.controller.walk_forward
-            || (!self.controller.aim && (self.controller.walk_left || self.controller.walk_right))
+            || (!self.is_aiming() && (self.controller.walk_left || self.controller.walk_right))
         {
-            self.target_local_velocity.y = if self.controller.run && !self.controller.aim {
+            self.target_local_velocity.y = if self.wants_to_sprint() && !self.is_aiming() {
                 1.0
             } else {
                 0.5
             };
         }
         if self.controller.walk_backward {
-            self.target_local_velocity.y = if self.controller.aim {
+            self.target_local_velocity.y = if self.is_aiming() {
                 -1.0
-            } else if self.controller.run {
+            } else if self.wants_to_sprint() {
                 1.0
             } else {
                 0.5
             };
         }
-        if self.controller.aim {
+        if self.is_aiming() {
             if self.controller.walk_left {
                 self.target_local_velocity.x = -1.0;
             }
@@ -1309,6 +2453,22 @@ impl ScriptTrait for Player {
             }
             self.run_factor += (self.target_run_factor - self.run_factor) * 0.1;
 
+            self.update_stamina(is_running && is_walking, ctx.dt);
+            self.update_flash_light_battery(ctx.dt);
+            self.update_crouch(&mut ctx.scene.graph, ctx.dt);
+            self.update_ads(&ctx.scene.graph, ctx.dt);
+            self.character
+                .update_item_use(&ctx.scene.graph, ctx.message_sender, ctx.dt);
+            self.character.update_hazards(
+                &level.hazard_zones,
+                &ctx.scene.graph,
+                ctx.message_sender,
+                ctx.dt,
+            );
+            self.character
+                .update_status_effects(&ctx.scene.graph, ctx.message_sender, ctx.dt);
+            self.character.update_weapon_switch(ctx.dt);
+
             let can_move = self.can_move(&ctx.scene.graph);
             self.update_velocity(ctx.scene, ctx.dt);
 
@@ -1316,7 +2476,7 @@ impl ScriptTrait for Player {
                 flash_light.set_visibility(*self.flash_light_enabled);
             }
 
-            let attacking_in_direction = self.controller.aim || self.melee_attack_context.is_some();
+            let attacking_in_direction = self.is_aiming() || self.melee_attack_context.is_some();
 
             if attacking_in_direction {
                 self.spine_pitch.set_target(self.target_pitch);
@@ -1343,7 +2503,7 @@ impl ScriptTrait for Player {
 
                 self.model_yaw.set_target(angle.to_radians()).update(ctx.dt);
 
-                if self.controller.aim {
+                if self.is_aiming() {
                     ctx.scene.graph[self.model_sub_pivot]
                         .local_transform_mut()
                         .set_rotation(UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.0));
@@ -1387,7 +2547,7 @@ impl ScriptTrait for Player {
                 }
             }
 
-            self.apply_weapon_angular_correction(ctx.scene, can_move, ctx.dt);
+            self.apply_weapon_angular_correction(ctx.scene, can_move, is_walking, ctx.dt);
 
             if has_ground_contact {
                 self.in_air_time = 0.0;
@@ -1399,9 +2559,23 @@ impl ScriptTrait for Player {
                 item_display.set_visibility(false);
             }
 
-            self.check_doors(ctx.scene, &level.doors_container);
+            self.update_interaction_target(ctx.scene, level, ctx.handle, ctx.message_sender);
+            self.update_health_regen(
+                &ctx.scene.graph,
+                ctx.message_sender,
+                game.config.difficulty,
+                ctx.dt,
+            );
+            self.check_doors(ctx.scene);
             self.check_elevators(ctx.scene, &level.elevators);
-            self.update_shooting(ctx.scene, ctx.dt, ctx.elapsed_time, ctx.message_sender);
+            self.update_shooting(
+                ctx.handle,
+                ctx.scene,
+                ctx.dt,
+                ctx.elapsed_time,
+                ctx.message_sender,
+            );
+            self.update_grenade_cooking(ctx.scene, ctx.resource_manager, ctx.handle, ctx.dt);
             self.check_items(
                 ctx.plugins.get_mut::<Game>(),
                 ctx.scene,
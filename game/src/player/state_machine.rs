@@ -1,7 +1,7 @@
 use crate::{utils, weapon::CombatWeaponKind};
 use fyrox::graph::SceneGraph;
 use fyrox::{
-    core::{algebra::Vector2, pool::Handle},
+    core::{algebra::Vector2, log::Log, pool::Handle},
     scene::{
         animation::{absm::prelude::*, prelude::*},
         graph::Graph,
@@ -14,11 +14,13 @@ pub struct StateMachineInput<'a> {
     pub is_walking: bool,
     pub is_jumping: bool,
     pub run_factor: f32,
+    pub crouch_factor: f32,
     pub has_ground_contact: bool,
     pub is_aiming: bool,
     pub toss_grenade: bool,
     pub weapon_kind: CombatWeaponKind,
     pub change_weapon: bool,
+    pub draw_weapon: bool,
     pub is_dead: bool,
     pub should_be_stunned: bool,
     pub melee_attack: bool,
@@ -63,6 +65,12 @@ impl StateMachine {
         let (upper_body_layer_index, upper_body) = machine.find_layer_by_name_ref("UpperBody")?;
         assert_eq!(upper_body_layer_index, Self::UPPER_BODY_LAYER_INDEX);
 
+        // The new weapon is raised in the `DrawWeapon` state, which is entered from the state
+        // with the grab animation while the `DrawWeapon` rule is set. Older assets do not have it.
+        if upper_body.find_state_by_name_ref("DrawWeapon").is_none() {
+            Log::warn("Player state machine has no DrawWeapon state, weapons are drawn instantly!");
+        }
+
         Some(Self {
             machine_handle,
             jump_animation: animations.find_by_name_ref("agent_jump")?.0,
@@ -123,11 +131,13 @@ impl StateMachine {
             is_walking,
             is_jumping,
             run_factor,
+            crouch_factor,
             has_ground_contact,
             is_aiming,
             toss_grenade,
             weapon_kind,
             change_weapon,
+            draw_weapon,
             is_dead,
             should_be_stunned,
             melee_attack,
@@ -148,7 +158,9 @@ impl StateMachine {
 
         let current_hit_reaction_animation = match weapon_kind {
             CombatWeaponKind::Rifle => self.hit_reaction_rifle_animation,
-            CombatWeaponKind::Pistol => self.hit_reaction_pistol_animation,
+            CombatWeaponKind::Pistol | CombatWeaponKind::Melee => {
+                self.hit_reaction_pistol_animation
+            }
         };
 
         let recovered = !input.should_be_stunned
@@ -175,9 +187,11 @@ impl StateMachine {
             .set_parameter("Aim", Parameter::Rule(is_aiming))
             .set_parameter("WalkFactor", Parameter::Weight(1.0 - run_factor))
             .set_parameter("RunFactor", Parameter::Weight(run_factor))
+            .set_parameter("CrouchFactor", Parameter::Weight(crouch_factor))
             .set_parameter("TossGrenade", Parameter::Rule(toss_grenade))
             .set_parameter("ReactToHit", Parameter::Rule(should_be_stunned))
             .set_parameter("RemoveWeapon", Parameter::Rule(change_weapon))
+            .set_parameter("DrawWeapon", Parameter::Rule(draw_weapon))
             .set_parameter("Recovered", Parameter::Rule(recovered))
             .set_parameter("Velocity", Parameter::SamplingPoint(local_velocity))
             .set_parameter("HitSomething", Parameter::Rule(hit_something))
@@ -1,20 +1,32 @@
-use fyrox::graph::SceneGraph;
+use crate::{level::reverb_zone::ReverbZone, utils};
+use fyrox::graph::{BaseSceneGraph, SceneGraph};
 use fyrox::material::MaterialResourceBinding;
 use fyrox::{
     asset::manager::ResourceManager,
     core::{
-        algebra::Vector3,
+        algebra::{Point3, Vector3},
+        arrayvec::ArrayVec,
         futures::executor::block_on,
         log::{Log, MessageKind},
         pool::Handle,
+        reflect::prelude::*,
+        stub_uuid_provider,
+        visitor::prelude::*,
     },
     rand::{self, seq::SliceRandom},
     scene::{
         base::BaseBuilder,
-        graph::{physics::FeatureId, Graph},
+        collider::{Collider, ColliderShape},
+        graph::{
+            physics::{FeatureId, Intersection, RayCastOptions},
+            Graph,
+        },
         mesh::Mesh,
         node::Node,
-        sound::{reverb::Reverb, Effect, SoundBuffer, SoundBufferResource, SoundBuilder, Status},
+        sound::{
+            filter::LowPassFilterEffect, reverb::Reverb, AudioBus, Effect, Sound, SoundBuffer,
+            SoundBufferResource, SoundBuilder, Status,
+        },
         transform::TransformBuilder,
         Scene,
     },
@@ -22,6 +34,7 @@ use fyrox::{
 use serde::Deserialize;
 use std::fmt::{Debug, Formatter};
 use std::{collections::HashMap, fs::File, ops::Range, path::Path, path::PathBuf};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
 
 #[derive(Debug)]
 pub struct TriangleRange {
@@ -29,16 +42,34 @@ pub struct TriangleRange {
     material: MaterialType,
 }
 
-#[derive(Deserialize, Hash, Eq, PartialEq, Copy, Clone, Debug)]
+#[derive(
+    Deserialize,
+    Hash,
+    Eq,
+    PartialEq,
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Reflect,
+    Visit,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
 pub enum MaterialType {
     Grass,
     Metal,
+    #[default]
     Stone,
     Wood,
     Chain,
     Flesh,
+    Water,
 }
 
+stub_uuid_provider!(MaterialType);
+
 #[derive(Deserialize, Hash, Eq, PartialEq, Copy, Clone, Debug)]
 pub enum SoundKind {
     Impact,
@@ -173,11 +204,118 @@ impl SoundMap {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ReverbParameters {
+    pub dry: f32,
+    pub wet: f32,
+    pub decay_time: f32,
+}
+
+impl Default for ReverbParameters {
+    fn default() -> Self {
+        Self {
+            dry: 0.5,
+            wet: 0.5,
+            decay_time: 3.0,
+        }
+    }
+}
+
+impl ReverbParameters {
+    fn follow(&mut self, other: &Self, k: f32) {
+        self.dry += (other.dry - self.dry) * k;
+        self.wet += (other.wet - self.wet) * k;
+        self.decay_time += (other.decay_time - self.decay_time) * k;
+    }
+
+    fn apply(&self, reverb: &mut Reverb) {
+        reverb.set_dry(self.dry);
+        reverb.set_wet(self.wet);
+        reverb.set_decay_time(self.decay_time);
+    }
+}
+
+/// Music that changes smoothly between the ambient and the combat tracks.
+#[derive(Clone, Debug, Default)]
+pub struct DynamicMusic {
+    ambient: Handle<Node>,
+    combat: Handle<Node>,
+    combat_radius: f32,
+    fade_in_time: f32,
+    fade_out_time: f32,
+    combat_cooldown: f32,
+    // 0.0 - only the ambient track is audible, 1.0 - only the combat track is audible.
+    combat_weight: f32,
+    cooldown_timer: f32,
+}
+
+impl DynamicMusic {
+    pub fn new(
+        ambient: Handle<Node>,
+        combat: Handle<Node>,
+        combat_radius: f32,
+        fade_in_time: f32,
+        fade_out_time: f32,
+        combat_cooldown: f32,
+    ) -> Self {
+        Self {
+            ambient,
+            combat,
+            combat_radius,
+            fade_in_time,
+            fade_out_time,
+            combat_cooldown,
+            combat_weight: 0.0,
+            cooldown_timer: 0.0,
+        }
+    }
+
+    pub fn combat_radius(&self) -> f32 {
+        self.combat_radius
+    }
+
+    pub fn is_combat(&self) -> bool {
+        self.cooldown_timer > 0.0
+    }
+
+    fn update(&mut self, graph: &mut Graph, in_combat: bool, volume: f32, dt: f32) {
+        if in_combat {
+            self.cooldown_timer = self.combat_cooldown;
+        } else {
+            self.cooldown_timer = (self.cooldown_timer - dt).max(0.0);
+        }
+
+        let target = if self.is_combat() { 1.0 } else { 0.0 };
+        let fade_time = if target > self.combat_weight {
+            self.fade_in_time
+        } else {
+            self.fade_out_time
+        };
+        let step = if fade_time > 0.0 { dt / fade_time } else { 1.0 };
+        self.combat_weight += (target - self.combat_weight).clamp(-step, step);
+
+        for (handle, gain) in [
+            (self.ambient, volume * (1.0 - self.combat_weight)),
+            (self.combat, volume * self.combat_weight),
+        ] {
+            if let Some(sound) = graph.try_get_mut_of_type::<Sound>(handle) {
+                sound.set_gain(gain);
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct SoundManager {
     sound_base: SoundBase,
     sound_map: SoundMap,
     resource_manager: Option<ResourceManager>,
+    reverb: ReverbParameters,
+    /// Defines how much sounds behind obstacles are muffled, 0.0 - no muffling, 1.0 - sounds
+    /// behind obstacles are silent.
+    pub occlusion_strength: f32,
+    music: Option<DynamicMusic>,
+    music_volume: f32,
 }
 
 impl Debug for SoundManager {
@@ -188,10 +326,9 @@ impl Debug for SoundManager {
 
 impl SoundManager {
     pub fn new(scene: &mut Scene, resource_manager: ResourceManager) -> Self {
+        let reverb_parameters = ReverbParameters::default();
         let mut reverb = Reverb::new();
-        reverb.set_dry(0.5);
-        reverb.set_wet(0.5);
-        reverb.set_decay_time(3.0);
+        reverb_parameters.apply(&mut reverb);
         scene
             .graph
             .sound_context
@@ -200,12 +337,96 @@ impl SoundManager {
             .primary_bus_mut()
             .add_effect(Effect::Reverb(reverb));
 
+        // Sounds that are occluded by level geometry are routed to this bus.
+        let mut occluded_bus = AudioBus::new(Self::OCCLUDED_BUS.to_string());
+        occluded_bus.add_effect(Effect::LowPassFilter(LowPassFilterEffect::new(800.0)));
+        let mut state = scene.graph.sound_context.state();
+        let bus_graph = state.bus_graph_mut();
+        let primary_bus = bus_graph.primary_bus_handle();
+        bus_graph.add_bus(occluded_bus, primary_bus);
+        drop(state);
+
         let sound_base = SoundBase::load();
 
         Self {
             sound_map: SoundMap::new(scene, &sound_base),
             sound_base,
             resource_manager: Some(resource_manager),
+            reverb: reverb_parameters,
+            occlusion_strength: 0.6,
+            music: None,
+            music_volume: 1.0,
+        }
+    }
+
+    const OCCLUDED_BUS: &'static str = "Occluded";
+
+    fn is_occluded(graph: &Graph, position: Vector3<f32>, emitter: Handle<Node>) -> bool {
+        let listener_position = graph.sound_context.state().listener().position();
+
+        let ray_direction = listener_position - position;
+        let mut query_buffer = ArrayVec::<Intersection, 64>::new();
+        graph.physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(position),
+                ray_direction,
+                max_len: ray_direction.norm(),
+                groups: Default::default(),
+                sort_results: true,
+            },
+            &mut query_buffer,
+        );
+
+        // Characters should not block sounds, as well as the emitter itself (including its hit
+        // boxes and sensors).
+        query_buffer.iter().any(|intersection| {
+            !utils::is_descendant_of(graph, intersection.collider, emitter)
+                && graph
+                    .try_get_of_type::<Collider>(intersection.collider)
+                    .is_some_and(|collider| !matches!(collider.shape(), ColliderShape::Capsule(_)))
+        })
+    }
+
+    pub fn set_music(&mut self, music: DynamicMusic) {
+        self.music = Some(music);
+    }
+
+    pub fn music(&self) -> Option<&DynamicMusic> {
+        self.music.as_ref()
+    }
+
+    pub fn set_music_volume(&mut self, volume: f32) {
+        self.music_volume = volume;
+    }
+
+    /// Crossfades the music tracks depending on the combat state of the player. The combat track
+    /// keeps playing for some time after the combat, so brief skirmishes won't switch the music
+    /// back and forth.
+    pub fn update_music(&mut self, graph: &mut Graph, in_combat: bool, dt: f32) {
+        if let Some(music) = self.music.as_mut() {
+            music.update(graph, in_combat, self.music_volume, dt);
+        }
+    }
+
+    /// Smoothly changes parameters of the global reverb to match the reverb zones the listener
+    /// is in.
+    pub fn update_reverb(&mut self, scene: &Scene, reverb_zones: &[Handle<Node>], dt: f32) {
+        let mut state = scene.graph.sound_context.state();
+        let listener_position = state.listener().position();
+
+        let target =
+            ReverbZone::blend_at(reverb_zones, &scene.graph, listener_position).unwrap_or_default();
+
+        if self.reverb == target {
+            return;
+        }
+
+        self.reverb.follow(&target, (2.0 * dt).min(1.0));
+
+        for effect in state.bus_graph_mut().primary_bus_mut().effects_mut() {
+            if let Effect::Reverb(reverb) = effect {
+                self.reverb.apply(reverb);
+            }
         }
     }
 
@@ -214,27 +435,48 @@ impl SoundManager {
         graph: &mut Graph,
         buffer: Option<&SoundBufferResource>,
         position: Vector3<f32>,
+        emitter: Handle<Node>,
         gain: f32,
         rolloff_factor: f32,
         radius: f32,
     ) {
         if let Some(buffer) = buffer {
-            self.play_sound_buffer(graph, buffer, position, gain, rolloff_factor, radius)
+            self.play_sound_buffer(
+                graph,
+                buffer,
+                position,
+                emitter,
+                gain,
+                rolloff_factor,
+                radius,
+            )
         } else {
             Log::warn("Failed to play a sound!")
         }
     }
 
+    /// Plays the sound at the given position. The sound is muffled if there is level geometry
+    /// between it and the listener, colliders of the emitter (the node that has produced the
+    /// sound, if any) are ignored.
     pub fn play_sound_buffer(
         &self,
         graph: &mut Graph,
         buffer: &SoundBufferResource,
         position: Vector3<f32>,
+        emitter: Handle<Node>,
         gain: f32,
         rolloff_factor: f32,
         radius: f32,
     ) {
-        SoundBuilder::new(
+        let occluded = self.occlusion_strength > 0.0 && Self::is_occluded(graph, position, emitter);
+
+        let gain = if occluded {
+            gain * (1.0 - self.occlusion_strength.min(1.0))
+        } else {
+            gain
+        };
+
+        let mut builder = SoundBuilder::new(
             BaseBuilder::new().with_local_transform(
                 TransformBuilder::new()
                     .with_local_position(position)
@@ -246,8 +488,13 @@ impl SoundManager {
         .with_play_once(true)
         .with_gain(gain)
         .with_radius(radius)
-        .with_rolloff_factor(rolloff_factor)
-        .build(graph);
+        .with_rolloff_factor(rolloff_factor);
+
+        if occluded {
+            builder = builder.with_audio_bus(Self::OCCLUDED_BUS.to_string());
+        }
+
+        builder.build(graph);
     }
 
     pub fn play_sound<P: AsRef<Path>>(
@@ -255,6 +502,7 @@ impl SoundManager {
         graph: &mut Graph,
         path: P,
         position: Vector3<f32>,
+        emitter: Handle<Node>,
         gain: f32,
         rolloff_factor: f32,
         radius: f32,
@@ -265,7 +513,15 @@ impl SoundManager {
                 .unwrap()
                 .request::<SoundBuffer>(path.as_ref()),
         ) {
-            self.play_sound_buffer(graph, &buffer, position, gain, rolloff_factor, radius)
+            self.play_sound_buffer(
+                graph,
+                &buffer,
+                position,
+                emitter,
+                gain,
+                rolloff_factor,
+                radius,
+            )
         } else {
             Log::writeln(
                 MessageKind::Error,
@@ -274,18 +530,9 @@ impl SoundManager {
         }
     }
 
-    pub fn play_environment_sound(
-        &self,
-        graph: &mut Graph,
-        collider: Handle<Node>,
-        feature: FeatureId,
-        position: Vector3<f32>,
-        sound_kind: SoundKind,
-        gain: f32,
-        rolloff_factor: f32,
-        radius: f32,
-    ) {
-        let material = self.sound_map.ranges_of(collider).and_then(|ranges| {
+    /// Returns a material of the given feature (usually a triangle) of the collider.
+    pub fn material_of(&self, collider: Handle<Node>, feature: FeatureId) -> Option<MaterialType> {
+        self.sound_map.ranges_of(collider).and_then(|ranges| {
             match feature {
                 FeatureId::Face(idx) => {
                     let mut material = None;
@@ -304,13 +551,34 @@ impl SoundManager {
                     ranges.first().map(|first_range| first_range.material)
                 }
             }
-        });
+        })
+    }
 
-        if let Some(material) = material {
+    pub fn play_environment_sound(
+        &self,
+        graph: &mut Graph,
+        collider: Handle<Node>,
+        feature: FeatureId,
+        position: Vector3<f32>,
+        emitter: Handle<Node>,
+        sound_kind: SoundKind,
+        gain: f32,
+        rolloff_factor: f32,
+        radius: f32,
+    ) {
+        if let Some(material) = self.material_of(collider, feature) {
             if let Some(map) = self.sound_base.material_to_sound.get(&material) {
                 if let Some(sound_list) = map.get(&sound_kind) {
                     if let Some(sound) = sound_list.choose(&mut rand::thread_rng()) {
-                        self.play_sound(graph, sound, position, gain, rolloff_factor, radius);
+                        self.play_sound(
+                            graph,
+                            sound,
+                            position,
+                            emitter,
+                            gain,
+                            rolloff_factor,
+                            radius,
+                        );
                     }
                 } else {
                     Log::writeln(
@@ -0,0 +1,161 @@
+//! Lingering effects (poison, burning, slow, etc.) that affect a character for some time.
+
+use fyrox::core::{reflect::prelude::*, stub_uuid_provider, visitor::prelude::*};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+#[derive(
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+    Visit,
+    Reflect,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+)]
+pub enum StatusEffectKind {
+    #[default]
+    Poison,
+    Burning,
+    Slow,
+}
+
+stub_uuid_provider!(StatusEffectKind);
+
+#[derive(Clone, Debug, PartialEq, Visit, Reflect)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    #[reflect(
+        min_value = 0.0,
+        description = "Remaining time (in seconds) of the effect."
+    )]
+    pub duration: f32,
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of damage per second that the effect deals to the character."
+    )]
+    pub damage_per_second: f32,
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Movement speed multiplier of the character while the effect is active."
+    )]
+    pub movement_speed_factor: f32,
+}
+
+stub_uuid_provider!(StatusEffect);
+
+impl Default for StatusEffect {
+    fn default() -> Self {
+        Self {
+            kind: Default::default(),
+            duration: 5.0,
+            damage_per_second: 0.0,
+            movement_speed_factor: 1.0,
+        }
+    }
+}
+
+impl StatusEffect {
+    pub fn poison(damage_per_second: f32, duration: f32) -> Self {
+        Self {
+            kind: StatusEffectKind::Poison,
+            duration,
+            damage_per_second,
+            movement_speed_factor: 1.0,
+        }
+    }
+
+    pub fn burning(damage_per_second: f32, duration: f32) -> Self {
+        Self {
+            kind: StatusEffectKind::Burning,
+            duration,
+            damage_per_second,
+            movement_speed_factor: 1.0,
+        }
+    }
+
+    pub fn slow(movement_speed_factor: f32, duration: f32) -> Self {
+        Self {
+            kind: StatusEffectKind::Slow,
+            duration,
+            damage_per_second: 0.0,
+            movement_speed_factor,
+        }
+    }
+}
+
+/// A set of active status effects of a character. There could be only one effect of each kind,
+/// applying an effect of the same kind refreshes the existing one.
+#[derive(Default, Clone, Debug, Visit, Reflect)]
+#[visit(optional)]
+pub struct StatusEffects {
+    effects: Vec<StatusEffect>,
+    // Damage that was accumulated, but not yet dealt.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pending_damage: f32,
+}
+
+impl StatusEffects {
+    /// Minimal amount of damage that will be dealt at once. Prevents flooding the character with
+    /// tiny hits every frame.
+    const DAMAGE_PORTION: f32 = 5.0;
+
+    pub fn apply_effect(&mut self, effect: StatusEffect) {
+        if let Some(existing) = self.effects.iter_mut().find(|e| e.kind == effect.kind) {
+            existing.duration = existing.duration.max(effect.duration);
+            existing.damage_per_second = existing.damage_per_second.max(effect.damage_per_second);
+            existing.movement_speed_factor = existing
+                .movement_speed_factor
+                .min(effect.movement_speed_factor);
+        } else {
+            self.effects.push(effect);
+        }
+    }
+
+    pub fn clear_effects(&mut self) {
+        self.effects.clear();
+        self.pending_damage = 0.0;
+    }
+
+    pub fn effects(&self) -> &[StatusEffect] {
+        &self.effects
+    }
+
+    pub fn has_effect(&self, kind: StatusEffectKind) -> bool {
+        self.effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// Combined movement speed multiplier of all active effects.
+    pub fn movement_speed_factor(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|effect| effect.movement_speed_factor.clamp(0.0, 1.0))
+            .product()
+    }
+
+    /// Ticks the effects and removes expired ones. Returns an amount of damage that should be
+    /// dealt to the character (if any).
+    pub fn update(&mut self, dt: f32) -> Option<f32> {
+        for effect in self.effects.iter_mut() {
+            let dt = dt.min(effect.duration);
+            self.pending_damage += effect.damage_per_second * dt;
+            effect.duration -= dt;
+        }
+        self.effects.retain(|effect| effect.duration > 0.0);
+
+        // Deal the rest of the damage when the last effect is expired.
+        if self.pending_damage >= Self::DAMAGE_PORTION
+            || (self.effects.is_empty() && self.pending_damage > 0.0)
+        {
+            Some(std::mem::take(&mut self.pending_damage))
+        } else {
+            None
+        }
+    }
+}
@@ -23,21 +23,46 @@ pub mod model_map;
 #[derive(Clone, Debug)]
 struct ImpactEntry {
     k: f32,
+    // Time (in seconds) in which the additional rotation decays to zero.
+    duration: f32,
     source: UnitQuaternion<f32>,
 }
 
+impl ImpactEntry {
+    fn current_rotation(&self) -> UnitQuaternion<f32> {
+        self.source.nlerp(
+            &UnitQuaternion::default(),
+            (self.k / self.duration).min(1.0),
+        )
+    }
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct BodyImpactHandler {
     additional_rotations: HashMap<Handle<Node>, ImpactEntry>,
 }
 
 impl BodyImpactHandler {
+    /// Amount of damage that makes a body part turn by [`Self::BASE_IMPACT_ANGLE`].
+    const REFERENCE_IMPACT_DAMAGE: f32 = 20.0;
+    const BASE_IMPACT_ANGLE: f32 = 24.0;
+    const MAX_IMPACT_ANGLE: f32 = 60.0;
+    /// Max angle of a rotation that is accumulated from multiple impacts on the same body part.
+    const MAX_STACKED_IMPACT_ANGLE: f32 = 90.0;
+    const IMPACT_DURATION: f32 = 1.0;
+    const MAX_STACKED_IMPACT_DURATION: f32 = 2.0;
+
+    /// Turns the body part away from the impact point. The angle of the turn is proportional to
+    /// the damage, so heavy hits jolt the body part stronger than weak ones. Impacts on a body part
+    /// that is still recovering from previous ones are stacked, which makes bursts produce stronger
+    /// and longer reaction.
     pub fn handle_impact(
         &mut self,
         scene: &Scene,
         handle: Handle<Node>,
         impact_point: Vector3<f32>,
         direction: Vector3<f32>,
+        damage: f32,
     ) {
         if let Some(node) = scene.graph.try_get(handle) {
             let global_transform = node.global_transform().try_inverse().unwrap_or_default();
@@ -50,18 +75,29 @@ impl BodyImpactHandler {
                 .cross(&local_direction)
                 .try_normalize(f32::EPSILON)
             {
-                let additional_rotation = UnitQuaternion::from_axis_angle(
-                    &Unit::new_normalize(axis),
-                    24.0f32.to_radians(),
-                );
+                let angle = (Self::BASE_IMPACT_ANGLE * damage.max(0.0)
+                    / Self::REFERENCE_IMPACT_DAMAGE)
+                    .min(Self::MAX_IMPACT_ANGLE);
+                let additional_rotation =
+                    UnitQuaternion::from_axis_angle(&Unit::new_normalize(axis), angle.to_radians());
                 self.additional_rotations
                     .entry(handle)
                     .and_modify(|r| {
-                        r.source = additional_rotation;
+                        let mut source = r.current_rotation() * additional_rotation;
+                        if let Some((axis, angle)) = source.axis_angle() {
+                            let max_angle = Self::MAX_STACKED_IMPACT_ANGLE.to_radians();
+                            if angle > max_angle {
+                                source = UnitQuaternion::from_axis_angle(&axis, max_angle);
+                            }
+                        }
+                        r.source = source;
+                        r.duration = (r.duration - r.k + Self::IMPACT_DURATION)
+                            .min(Self::MAX_STACKED_IMPACT_DURATION);
                         r.k = 0.0;
                     })
                     .or_insert(ImpactEntry {
                         k: 0.0,
+                        duration: Self::IMPACT_DURATION,
                         source: additional_rotation,
                     });
             }
@@ -72,13 +108,13 @@ impl BodyImpactHandler {
 
     pub fn update_and_apply(&mut self, dt: f32, scene: &mut Scene) {
         for (body, entry) in self.additional_rotations.iter_mut() {
-            let additional_rotation = entry.source.nlerp(&UnitQuaternion::default(), entry.k);
+            let additional_rotation = entry.current_rotation();
             entry.k += dt;
             let transform = scene.graph[*body].local_transform_mut();
             let new_rotation = **transform.rotation() * additional_rotation;
             transform.set_rotation(new_rotation);
         }
-        self.additional_rotations.retain(|_, e| e.k < 1.0);
+        self.additional_rotations.retain(|_, e| e.k < e.duration);
     }
 
     pub fn is_affected(&self, handle: Handle<Node>) -> bool {
@@ -103,6 +139,28 @@ pub fn is_probability_event_occurred(probability: f32) -> bool {
     rand::thread_rng().gen_range(0.0..1.0) < probability.clamp(0.0, 1.0)
 }
 
+/// Randomly deviates the given direction within a cone with the given half-angle (in radians).
+pub fn random_direction_in_cone(direction: Vector3<f32>, half_angle: f32) -> Vector3<f32> {
+    if half_angle <= 0.0 {
+        return direction;
+    }
+
+    let side = if direction.y.abs() < 0.99 {
+        Vector3::y()
+    } else {
+        Vector3::x()
+    };
+    let Some(u) = direction.cross(&side).try_normalize(f32::EPSILON) else {
+        return direction;
+    };
+    let v = direction.cross(&u);
+
+    let mut rng = rand::thread_rng();
+    let roll = rng.gen_range(0.0..std::f32::consts::TAU);
+    let axis = Unit::new_normalize(u.scale(roll.cos()) + v.scale(roll.sin()));
+    UnitQuaternion::from_axis_angle(&axis, rng.gen_range(0.0..half_angle)) * direction
+}
+
 pub fn fetch_animation_container_ref(graph: &Graph, handle: Handle<Node>) -> &AnimationContainer {
     graph
         .try_get_of_type::<AnimationPlayer>(handle)
@@ -142,6 +200,22 @@ pub fn try_play_random_sound(sounds: &[Handle<Node>], graph: &mut Graph) -> bool
     }
 }
 
+/// Returns `true` if the node is the given ancestor or one of its descendants.
+pub fn is_descendant_of(graph: &Graph, mut node: Handle<Node>, ancestor: Handle<Node>) -> bool {
+    if ancestor.is_none() {
+        return false;
+    }
+
+    while let Some(node_ref) = graph.try_get(node) {
+        if node == ancestor {
+            return true;
+        }
+        node = node_ref.parent();
+    }
+
+    false
+}
+
 pub fn try_play_sound(sound_handle: Handle<Node>, graph: &mut Graph) {
     if let Some(node) = graph.try_get_mut(sound_handle) {
         if let Some(sound_ref) = node.component_mut::<Sound>() {
@@ -155,3 +229,41 @@ pub fn try_play_sound(sound_handle: Handle<Node>, graph: &mut Graph) {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::utils::BodyImpactHandler;
+    use fyrox::{
+        core::algebra::Vector3,
+        scene::{base::BaseBuilder, pivot::PivotBuilder, Scene},
+    };
+
+    #[test]
+    fn test_impacts_on_same_body_part_stack() {
+        let mut scene = Scene::new();
+        let bone = PivotBuilder::new(BaseBuilder::new()).build(&mut scene.graph);
+
+        let mut handler = BodyImpactHandler::default();
+        let mut prev_angle = 0.0;
+        for _ in 0..3 {
+            handler.handle_impact(
+                &scene,
+                bone,
+                Vector3::new(0.0, 0.0, 1.0),
+                Vector3::x(),
+                10.0,
+            );
+
+            let angle = handler.additional_rotations[&bone]
+                .current_rotation()
+                .angle();
+            assert!(
+                angle > prev_angle,
+                "{angle} must be larger than {prev_angle}"
+            );
+            prev_angle = angle;
+        }
+
+        assert!(prev_angle <= BodyImpactHandler::MAX_STACKED_IMPACT_ANGLE.to_radians() + 1.0e-4);
+    }
+}
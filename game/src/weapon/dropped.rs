@@ -0,0 +1,137 @@
+//! Weapons dropped by characters. A dropped weapon keeps its node (and thus the rounds in its
+//! magazine), the node is attached to a rigid body, so the weapon falls and settles on the ground.
+
+use crate::weapon::weapon_mut;
+use fyrox::{
+    core::{
+        algebra::{UnitQuaternion, Vector3},
+        pool::Handle,
+        reflect::prelude::*,
+        type_traits::prelude::*,
+        visitor::prelude::*,
+    },
+    graph::{BaseSceneGraph, SceneGraph},
+    scene::{
+        base::BaseBuilder,
+        collider::{ColliderBuilder, ColliderShape},
+        graph::Graph,
+        node::Node,
+        rigidbody::{RigidBody, RigidBodyBuilder},
+        transform::TransformBuilder,
+    },
+    script::{ScriptContext, ScriptTrait},
+};
+
+#[derive(Visit, Reflect, Default, Debug, Clone, TypeUuidProvider, ComponentProvider)]
+#[type_uuid(id = "8c2f4e71-3a9d-4b6e-a15f-6d0e7c9b2a43")]
+#[visit(optional)]
+pub struct DroppedWeapon {
+    pub weapon: Handle<Node>,
+    // Position where the weapon was dropped, the weapon is returned there if it falls out of the
+    // level (off a ledge into a pit, for example).
+    drop_position: Vector3<f32>,
+}
+
+impl DroppedWeapon {
+    /// Distance (in meters) below the drop position at which the weapon is considered lost.
+    const MAX_FALL_DISTANCE: f32 = 50.0;
+
+    /// Detaches the given weapon from its owner and attaches it to a new rigid body placed at the
+    /// given position. The weapon becomes an item that could be picked up.
+    pub fn spawn(
+        weapon: Handle<Node>,
+        position: Vector3<f32>,
+        velocity: Vector3<f32>,
+        graph: &mut Graph,
+    ) -> Handle<Node> {
+        let rotation = graph
+            .try_get(weapon)
+            .map_or(UnitQuaternion::identity(), |node| node.global_rotation());
+
+        let weapon_script = weapon_mut(weapon, graph);
+        weapon_script.set_owner(Handle::NONE);
+        weapon_script.cancel_reload();
+        let half_extents = *weapon_script.drop_half_extents;
+
+        let collider = ColliderBuilder::new(BaseBuilder::new())
+            .with_shape(ColliderShape::cuboid(
+                half_extents.x,
+                half_extents.y,
+                half_extents.z,
+            ))
+            .build(graph);
+
+        let body = RigidBodyBuilder::new(
+            BaseBuilder::new()
+                .with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_position(position)
+                        .with_local_rotation(rotation)
+                        .build(),
+                )
+                .with_children(&[collider])
+                .with_script(DroppedWeapon {
+                    weapon,
+                    drop_position: position,
+                }),
+        )
+        .with_lin_vel(velocity)
+        .with_ccd_enabled(true)
+        .build(graph);
+
+        graph.link_nodes(weapon, body);
+
+        // The body is already placed and rotated as the weapon was, the weapon itself must stay
+        // at the origin of the body to match the collider.
+        let weapon_node = &mut graph[weapon];
+        weapon_node
+            .local_transform_mut()
+            .set_position(Vector3::default())
+            .set_rotation(UnitQuaternion::identity());
+        weapon_node.set_enabled(true);
+
+        body
+    }
+
+    /// Returns a handle of the body of a dropped weapon, or `None` if the weapon is not dropped.
+    pub fn find_body(weapon: Handle<Node>, graph: &Graph) -> Option<Handle<Node>> {
+        let parent = graph.try_get(weapon)?.parent();
+        graph
+            .try_get_script_of::<DroppedWeapon>(parent)
+            .filter(|dropped| dropped.weapon == weapon)
+            .map(|_| parent)
+    }
+
+    /// Detaches a dropped weapon from its body and removes the body. Returns `false` if the weapon
+    /// is not dropped.
+    pub fn try_take_weapon(weapon: Handle<Node>, graph: &mut Graph) -> bool {
+        let Some(body) = Self::find_body(weapon, graph) else {
+            return false;
+        };
+
+        graph.unlink_node(weapon);
+        graph.remove_node(body);
+
+        true
+    }
+}
+
+impl ScriptTrait for DroppedWeapon {
+    fn on_update(&mut self, ctx: &mut ScriptContext) {
+        let graph = &mut ctx.scene.graph;
+
+        // The weapon was removed by someone else, the body is not needed anymore.
+        if !graph.is_valid_handle(self.weapon) {
+            graph[ctx.handle].set_lifetime(Some(0.0));
+            return;
+        }
+
+        if graph[ctx.handle].global_position().y < self.drop_position.y - Self::MAX_FALL_DISTANCE {
+            if let Some(body) = graph[ctx.handle].cast_mut::<RigidBody>() {
+                body.set_lin_vel(Default::default());
+                body.set_ang_vel(Default::default());
+                body.local_transform_mut().set_position(self.drop_position);
+            }
+        }
+    }
+}
@@ -304,7 +304,7 @@ impl ScriptTrait for KineticGun {
 
         if let Some(msg) = message.downcast_ref::<WeaponMessage>() {
             if msg.weapon == ctx.handle {
-                if let WeaponMessageData::Shoot { direction } = msg.data {
+                if let WeaponMessageData::Shoot { direction, .. } = msg.data {
                     match self.target.as_ref() {
                         Some(target) => {
                             let velocity = direction
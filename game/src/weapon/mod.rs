@@ -1,27 +1,55 @@
 //! Weapon related stuff.
 
-use crate::{character::Character, level::item::Item, weapon::projectile::Projectile};
+use crate::{
+    bot::NoiseMessage,
+    character::{
+        try_get_character_mut, try_get_character_ref, Character, DamageDealer, DamagePosition,
+    },
+    effects::muzzle_flash::MuzzleFlash,
+    inventory::Inventory,
+    level::{
+        hit_box::{HitBox, HitBoxDamage, HitBoxMessage},
+        item::Item,
+        Level,
+    },
+    utils,
+    weapon::projectile::Projectile,
+    Game,
+};
 use fyrox::graph::SceneGraph;
 use fyrox::{
     core::{
-        algebra::{Matrix3, Vector2, Vector3},
+        algebra::{Matrix3, Point3, Vector2, Vector3},
+        arrayvec::ArrayVec,
+        color::Color,
         math::{vector_to_quat, Matrix4Ext},
         pool::Handle,
         reflect::prelude::*,
-        stub_uuid_provider,
+        some_or_continue, some_or_return, stub_uuid_provider,
         type_traits::prelude::*,
         variable::InheritableVariable,
         visitor::prelude::*,
     },
     rand::{seq::SliceRandom, Rng},
     resource::model::{ModelResource, ModelResourceExtension},
-    scene::{graph::Graph, node::Node, Scene},
+    scene::{
+        collider::{Collider, ColliderShape},
+        graph::{
+            physics::{Intersection, RayCastOptions},
+            Graph,
+        },
+        node::Node,
+        rigidbody::RigidBody,
+        Scene,
+    },
     script::{
-        ScriptContext, ScriptDeinitContext, ScriptMessageContext, ScriptMessagePayload, ScriptTrait,
+        RoutingStrategy, ScriptContext, ScriptDeinitContext, ScriptMessageContext,
+        ScriptMessagePayload, ScriptMessageSender, ScriptTrait,
     },
 };
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+pub mod dropped;
 pub mod kinetic;
 pub mod projectile;
 pub mod sight;
@@ -38,19 +66,67 @@ pub struct WeaponMessage {
 
 #[derive(Debug)]
 pub enum WeaponMessageData {
-    Shoot { direction: Option<Vector3<f32>> },
+    Shoot {
+        direction: Option<Vector3<f32>>,
+        /// Multiplier of the spread of the weapon, for example the spread is tighter when the
+        /// player aims down the sights.
+        spread_factor: f32,
+    },
     Removed,
 }
 
+/// A global script message that is sent every time when the amount of ammo of a weapon changes
+/// (shot, reload, ammo pickup, etc.). Subscribe to it (`message_dispatcher.subscribe_to::<AmmoEvent>(..)`)
+/// to update ammo counters.
+#[derive(Debug, Clone)]
+pub struct AmmoEvent {
+    pub weapon: Handle<Node>,
+    pub owner: Handle<Node>,
+    pub weapon_kind: CombatWeaponKind,
+    pub rounds_in_magazine: u32,
+    pub magazine_capacity: u32,
+    /// Amount of rounds that could be loaded from the owner's inventory. [`u32::MAX`] means
+    /// infinite ammo.
+    pub reserve_rounds: u32,
+}
+
+/// A global script message that is sent when the amount of rounds in the magazine of a weapon
+/// drops below the low ammo threshold of the weapon.
+#[derive(Debug, Clone)]
+pub struct LowAmmoWarning {
+    pub weapon: Handle<Node>,
+    pub owner: Handle<Node>,
+    pub weapon_kind: CombatWeaponKind,
+    pub rounds_in_magazine: u32,
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug, Reflect, Visit, AsRefStr, EnumString, VariantNames)]
 #[repr(u32)]
 pub enum CombatWeaponKind {
     Pistol = 0,
     Rifle = 1,
+    Melee = 2,
 }
 
 stub_uuid_provider!(CombatWeaponKind);
 
+/// An attachment that modifies properties of a weapon. Every attachment occupies its own slot, so
+/// a weapon can have at most one attachment of each kind.
+#[derive(
+    Default, Eq, PartialEq, Copy, Clone, Debug, Reflect, Visit, AsRefStr, EnumString, VariantNames,
+)]
+pub enum WeaponAttachment {
+    /// Reduces the noise radius of shots and dims the muzzle flash.
+    #[default]
+    Suppressor,
+    /// Improves zoom and reduces spread while aiming down the sights.
+    Scope,
+    /// Increases the magazine capacity.
+    ExtendedMagazine,
+}
+
+stub_uuid_provider!(WeaponAttachment);
+
 #[derive(Visit, Reflect, Debug, Clone, TypeUuidProvider, ComponentProvider)]
 #[type_uuid(id = "bca0083b-b062-4d95-b241-db05bca65da7")]
 #[visit(optional)]
@@ -58,7 +134,18 @@ pub struct Weapon {
     #[component(include)]
     item: Item,
     shot_point: Handle<Node>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Legacy. Time (in seconds) between two consecutive shots, it is used only \
+        when `fire_rate` is zero. Use `fire_rate` for new weapons."
+    )]
     shoot_interval: InheritableVariable<f32>,
+    #[reflect(
+        min_value = 0.0,
+        description = "Fire rate of the weapon (in rounds per minute). Shots that are attempted \
+        faster than the rate are ignored. Zero means that `shoot_interval` is used instead."
+    )]
+    pub fire_rate: InheritableVariable<f32>,
     pub yaw_correction: InheritableVariable<f32>,
     pub pitch_correction: InheritableVariable<f32>,
     pub ammo_indicator_offset: InheritableVariable<Vector3<f32>>,
@@ -68,19 +155,248 @@ pub struct Weapon {
     projectile: Option<ModelResource>,
     pub weapon_type: CombatWeaponKind,
     pub ammo_item: InheritableVariable<Option<ModelResource>>,
+    #[reflect(
+        description = "Caliber of the weapon. The weapon could be loaded with any ammo item of \
+        the same caliber, so weapons of the same caliber share ammo. Empty caliber means that only \
+        `ammo_item` could be used."
+    )]
+    pub caliber: InheritableVariable<String>,
     pub shake_camera_on_shot: InheritableVariable<bool>,
 
+    #[reflect(
+        description = "Maximum amount of rounds in the magazine. Every shot takes one round, \
+        reloading takes `ammo_consumption_per_shot` ammo items from the owner's inventory per round."
+    )]
+    pub magazine_capacity: InheritableVariable<u32>,
+
+    #[reflect(description = "Time (in seconds) that is needed to reload the weapon.")]
+    pub reload_time: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Reach (in meters) of a melee weapon. Used only by melee weapons, the \
+        fire rate is used as a swing cooldown."
+    )]
+    pub melee_range: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Amount of damage dealt by a melee weapon swing."
+    )]
+    pub melee_damage: InheritableVariable<f32>,
+
+    #[reflect(description = "Whether a light flashes at the muzzle on every shot.")]
+    pub muzzle_flash_enabled: InheritableVariable<bool>,
+
+    #[reflect(description = "Color of the light that flashes on every shot.")]
+    pub muzzle_flash_color: InheritableVariable<Color>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Initial intensity of the muzzle flash light. Zero disables the light."
+    )]
+    pub muzzle_flash_intensity: InheritableVariable<f32>,
+
+    #[reflect(min_value = 0.0)]
+    pub muzzle_flash_radius: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) during which the muzzle flash light fades out."
+    )]
+    pub muzzle_flash_duration: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Radius (in meters) in which bots can hear shots of the weapon. Bots that hear \
+        a shot will notice the shooter even if it is out of their sight."
+    )]
+    pub shot_noise_radius: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Max angle (in degrees) of deviation of shots from the aiming direction."
+    )]
+    pub spread: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Additional spread multiplier per m/s of horizontal speed of the owner."
+    )]
+    pub movement_spread_penalty: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Additional spread multiplier per degree of the current recoil of the owner."
+    )]
+    pub recoil_spread_penalty: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 180.0,
+        description = "Field of view (in degrees) of the camera while aiming down the sights."
+    )]
+    pub ads_fov: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Mouse sensitivity multiplier while aiming down the sights."
+    )]
+    pub ads_sensitivity_factor: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Spread multiplier while aiming down the sights."
+    )]
+    pub ads_spread_factor: InheritableVariable<f32>,
+
+    #[reflect(
+        description = "Local offset of the weapon while aiming down the sights. Used to raise \
+        the weapon closer to the line of sight."
+    )]
+    pub ads_offset: InheritableVariable<Vector3<f32>>,
+
+    #[reflect(
+        description = "A prefab of a shell casing (usually a small rigid body) that will be ejected \
+        on every shot. Leave empty for weapons that do not eject anything (plasma guns, etc.)."
+    )]
+    pub shell_casing: InheritableVariable<Option<ModelResource>>,
+
+    #[reflect(description = "A point from which shell casings will be ejected.")]
+    pub shell_ejection_point: InheritableVariable<Handle<Node>>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Speed (in m/s) at which shell casings will be ejected."
+    )]
+    pub shell_ejection_speed: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) after which an ejected shell casing will be removed."
+    )]
+    pub shell_casing_lifetime: InheritableVariable<f32>,
+
     #[reflect(
         description = "A list of VFX resources that will be randomly instantiated on shot. Usually it is some sort of muzzle flash."
     )]
     shot_vfx: InheritableVariable<Vec<Option<ModelResource>>>,
 
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Fraction of the magazine capacity below which the low ammo warning will \
+        be issued."
+    )]
+    pub low_ammo_threshold: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) that is needed to put the weapon away when switching \
+        to another weapon. Heavy weapons should have larger values."
+    )]
+    pub holster_time: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) that is needed to draw the weapon when switching to \
+        it. The weapon cannot shoot until it is drawn."
+    )]
+    pub draw_time: InheritableVariable<f32>,
+
+    #[reflect(description = "Attachments that are installed on the weapon.")]
+    attachments: InheritableVariable<Vec<WeaponAttachment>>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "A multiplier for the shot noise radius when a suppressor is installed."
+    )]
+    pub suppressor_noise_factor: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "A multiplier for the muzzle flash intensity when a suppressor is installed."
+    )]
+    pub suppressor_flash_factor: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 180.0,
+        description = "Field of view (in degrees) of the camera while aiming down the sights \
+        when a scope is installed."
+    )]
+    pub scope_ads_fov: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Spread multiplier while aiming down the sights when a scope is installed."
+    )]
+    pub scope_ads_spread_factor: InheritableVariable<f32>,
+
+    #[reflect(
+        description = "Additional rounds in the magazine when an extended magazine is installed."
+    )]
+    pub extended_magazine_rounds: InheritableVariable<u32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) the trigger must be held before a charged shot could be \
+        fired. Releasing the trigger earlier cancels the shot."
+    )]
+    pub charge_time_min: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) that is needed to fully charge a shot. Zero disables \
+        charging, the weapon fires as soon as the trigger is pulled."
+    )]
+    pub charge_time_max: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Damage multiplier of a fully charged shot."
+    )]
+    pub charge_damage_scale: InheritableVariable<f32>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Projectile speed multiplier of a fully charged shot. Affects only \
+        kinematic projectiles."
+    )]
+    pub charge_speed_scale: InheritableVariable<f32>,
+
+    #[reflect(
+        description = "Half extents (in meters) of the box collider that is used for the physical \
+        body of the weapon when it is dropped on the ground."
+    )]
+    pub drop_half_extents: InheritableVariable<Vector3<f32>>,
+
+    // Time (in seconds) the trigger is held, `None` if the weapon is not charging.
+    #[reflect(hidden)]
+    charge_timer: Option<f32>,
+
     #[reflect(hidden)]
     owner: Handle<Node>,
 
+    // Spread multiplier that was set by the owner the last time.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    spread_factor: f32,
+
     #[reflect(hidden)]
     last_shot_time: f32,
 
+    // Amount of rounds in the magazine and reserve rounds that were reported the last time.
+    #[reflect(hidden)]
+    #[visit(skip)]
+    reported_ammo: Option<(u32, u32)>,
+
+    #[reflect(hidden)]
+    rounds_in_magazine: u32,
+
+    #[reflect(hidden)]
+    reload_timer: f32,
+
     #[reflect(hidden)]
     #[visit(skip)]
     self_handle: Handle<Node>,
@@ -94,6 +410,7 @@ impl Default for Weapon {
             last_shot_time: 0.0,
             owner: Handle::NONE,
             shoot_interval: 0.15.into(),
+            fire_rate: 0.0.into(),
             projectile: None,
             self_handle: Default::default(),
             yaw_correction: (-4.0).into(),
@@ -105,7 +422,48 @@ impl Default for Weapon {
             shot_vfx: Default::default(),
             weapon_type: CombatWeaponKind::Pistol,
             ammo_item: Default::default(),
+            caliber: Default::default(),
             shake_camera_on_shot: true.into(),
+            magazine_capacity: 30.into(),
+            reload_time: 1.5.into(),
+            muzzle_flash_enabled: false.into(),
+            muzzle_flash_color: Color::opaque(255, 200, 120).into(),
+            muzzle_flash_intensity: 2.0.into(),
+            muzzle_flash_radius: 4.0.into(),
+            muzzle_flash_duration: 0.06.into(),
+            shot_noise_radius: 20.0.into(),
+            spread: 0.0.into(),
+            movement_spread_penalty: 0.25.into(),
+            recoil_spread_penalty: 0.1.into(),
+            spread_factor: 1.0,
+            ads_fov: 45.0.into(),
+            ads_sensitivity_factor: 0.6.into(),
+            ads_spread_factor: 0.25.into(),
+            ads_offset: Vector3::new(0.0, 0.03, 0.0).into(),
+            shell_casing: Default::default(),
+            shell_ejection_point: Default::default(),
+            shell_ejection_speed: 2.5.into(),
+            shell_casing_lifetime: 5.0.into(),
+            melee_range: 1.5.into(),
+            melee_damage: 35.0.into(),
+            rounds_in_magazine: 0,
+            reload_timer: 0.0,
+            low_ammo_threshold: 0.25.into(),
+            holster_time: 0.25.into(),
+            draw_time: 0.35.into(),
+            attachments: Default::default(),
+            suppressor_noise_factor: 0.2.into(),
+            suppressor_flash_factor: 0.2.into(),
+            scope_ads_fov: 20.0.into(),
+            scope_ads_spread_factor: 0.1.into(),
+            extended_magazine_rounds: 15.into(),
+            charge_time_min: 0.2.into(),
+            charge_time_max: 0.0.into(),
+            charge_damage_scale: 3.0.into(),
+            charge_speed_scale: 1.5.into(),
+            drop_half_extents: Vector3::new(0.05, 0.1, 0.3).into(),
+            charge_timer: None,
+            reported_ammo: None,
         }
     }
 }
@@ -149,8 +507,325 @@ impl Weapon {
         self.owner = owner;
     }
 
+    pub fn is_melee(&self) -> bool {
+        self.weapon_type == CombatWeaponKind::Melee
+    }
+
+    pub fn attachments(&self) -> &[WeaponAttachment] {
+        &self.attachments
+    }
+
+    pub fn has_attachment(&self, attachment: WeaponAttachment) -> bool {
+        self.attachments.contains(&attachment)
+    }
+
+    /// Installs the attachment on the weapon. Returns `false` if the attachment is already
+    /// installed or if the weapon cannot have attachments (melee weapons).
+    pub fn attach(&mut self, attachment: WeaponAttachment) -> bool {
+        if self.is_melee() || self.has_attachment(attachment) {
+            return false;
+        }
+
+        self.attachments
+            .get_value_mut_and_mark_modified()
+            .push(attachment);
+        // Make sure that the ammo counters will be updated with the new magazine capacity.
+        self.reported_ammo = None;
+
+        true
+    }
+
+    /// Removes the attachment from the weapon. Returns `false` if the attachment was not
+    /// installed. Rounds that do not fit into a smaller magazine are kept until they are shot.
+    pub fn detach(&mut self, attachment: WeaponAttachment) -> bool {
+        if !self.has_attachment(attachment) {
+            return false;
+        }
+
+        self.attachments
+            .get_value_mut_and_mark_modified()
+            .retain(|a| *a != attachment);
+        self.reported_ammo = None;
+
+        true
+    }
+
+    /// Maximum amount of rounds in the magazine, including the rounds of an extended magazine.
+    pub fn magazine_capacity(&self) -> u32 {
+        if self.has_attachment(WeaponAttachment::ExtendedMagazine) {
+            *self.magazine_capacity + *self.extended_magazine_rounds
+        } else {
+            *self.magazine_capacity
+        }
+    }
+
+    /// Radius (in meters) in which bots can hear shots of the weapon. A suppressor makes shots
+    /// much quieter.
+    pub fn shot_noise_radius(&self) -> f32 {
+        if self.has_attachment(WeaponAttachment::Suppressor) {
+            *self.shot_noise_radius * *self.suppressor_noise_factor
+        } else {
+            *self.shot_noise_radius
+        }
+    }
+
+    /// Initial intensity of the muzzle flash light. A suppressor makes the flash dimmer.
+    pub fn muzzle_flash_intensity(&self) -> f32 {
+        if self.has_attachment(WeaponAttachment::Suppressor) {
+            *self.muzzle_flash_intensity * *self.suppressor_flash_factor
+        } else {
+            *self.muzzle_flash_intensity
+        }
+    }
+
+    /// Field of view (in degrees) of the camera while aiming down the sights.
+    pub fn ads_fov(&self) -> f32 {
+        if self.has_attachment(WeaponAttachment::Scope) {
+            *self.scope_ads_fov
+        } else {
+            *self.ads_fov
+        }
+    }
+
+    /// Spread multiplier while aiming down the sights.
+    pub fn ads_spread_factor(&self) -> f32 {
+        if self.has_attachment(WeaponAttachment::Scope) {
+            *self.scope_ads_spread_factor
+        } else {
+            *self.ads_spread_factor
+        }
+    }
+
+    /// Calculates a spread multiplier from the state of the owner. `ads_spread_factor` is the
+    /// spread multiplier of aiming down the sights, `speed` is the horizontal speed (in m/s) of the
+    /// owner and `recoil_angle` is the current recoil angle (in degrees).
+    pub fn spread_factor(&self, ads_spread_factor: f32, speed: f32, recoil_angle: f32) -> f32 {
+        ads_spread_factor
+            * (1.0
+                + *self.movement_spread_penalty * speed
+                + *self.recoil_spread_penalty * recoil_angle.abs())
+    }
+
+    /// Sets the spread multiplier that is used by [`Self::current_spread`].
+    pub fn set_spread_factor(&mut self, spread_factor: f32) {
+        self.spread_factor = spread_factor;
+    }
+
+    /// Returns current effective spread angle (in degrees) of the weapon.
+    pub fn current_spread(&self) -> f32 {
+        *self.spread * self.spread_factor
+    }
+
+    /// Returns `true` if the weapon fires charged shots. Such weapons must be charged first (see
+    /// [`Self::begin_charge`]) and they fire when the trigger is released.
+    pub fn is_chargeable(&self) -> bool {
+        *self.charge_time_max > 0.0
+    }
+
+    pub fn is_charging(&self) -> bool {
+        self.charge_timer.is_some()
+    }
+
+    /// Starts charging a shot. The charged shot is fired by the next shoot message.
+    pub fn begin_charge(&mut self) {
+        if self.is_chargeable() && self.charge_timer.is_none() {
+            self.charge_timer = Some(0.0);
+        }
+    }
+
+    pub fn cancel_charge(&mut self) {
+        self.charge_timer = None;
+    }
+
+    fn charge_level_at(&self, charge_time: f32) -> f32 {
+        let range = *self.charge_time_max - *self.charge_time_min;
+        if range <= f32::EPSILON {
+            1.0
+        } else {
+            ((charge_time - *self.charge_time_min) / range).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Returns charge level of the current shot in `[0; 1]` range.
+    pub fn charge_level(&self) -> f32 {
+        self.charge_timer
+            .map_or(0.0, |charge_time| self.charge_level_at(charge_time))
+    }
+
+    fn update_charge(&mut self, graph: &Graph, dt: f32) {
+        if self.charge_timer.is_none() {
+            return;
+        }
+
+        // Reloading or holstering the weapon drops the charge.
+        if self.is_reloading()
+            || try_get_character_ref(self.owner, graph)
+                .is_none_or(|owner| owner.is_switching_weapon())
+        {
+            self.charge_timer = None;
+            return;
+        }
+
+        if let Some(charge_timer) = self.charge_timer.as_mut() {
+            *charge_timer = (*charge_timer + dt).min(*self.charge_time_max);
+        }
+    }
+
+    /// Time (in seconds) between two consecutive shots.
+    pub fn shot_interval(&self) -> f32 {
+        if *self.fire_rate > 0.0 {
+            60.0 / *self.fire_rate
+        } else {
+            *self.shoot_interval
+        }
+    }
+
+    fn is_fire_rate_exceeded(&self, elapsed_time: f32) -> bool {
+        elapsed_time - self.last_shot_time < self.shot_interval()
+    }
+
     pub fn can_shoot(&self, elapsed_time: f32) -> bool {
-        elapsed_time - self.last_shot_time >= *self.shoot_interval
+        !self.is_reloading()
+            && (self.is_melee() || self.rounds_in_magazine > 0)
+            && !self.is_fire_rate_exceeded(elapsed_time)
+    }
+
+    pub fn rounds_in_magazine(&self) -> u32 {
+        self.rounds_in_magazine
+    }
+
+    pub fn is_reloading(&self) -> bool {
+        self.reload_timer > 0.0
+    }
+
+    /// Stops reloading, the rounds are not moved to the magazine.
+    pub fn cancel_reload(&mut self) {
+        self.reload_timer = 0.0;
+    }
+
+    pub fn is_magazine_empty(&self) -> bool {
+        !self.is_melee() && self.rounds_in_magazine == 0
+    }
+
+    /// Checks whether the weapon could be loaded with the given item. It is either the ammo item
+    /// of the weapon, or any other ammo item of the same caliber.
+    pub fn is_compatible_ammo(&self, item: &ModelResource) -> bool {
+        self.ammo_item.as_ref() == Some(item)
+            || (!self.caliber.is_empty()
+                && Item::from_resource(item, |item| {
+                    item.is_some_and(|item| *item.caliber == *self.caliber)
+                }))
+    }
+
+    fn uses_ammo(&self) -> bool {
+        self.ammo_item.is_some() || !self.caliber.is_empty()
+    }
+
+    /// Amount of rounds that could be loaded from the given inventory.
+    pub fn reserve_rounds(&self, inventory: &Inventory) -> u32 {
+        if self.uses_ammo() {
+            inventory.item_count_by(|item| self.is_compatible_ammo(item))
+                / (*self.ammo_consumption_per_shot).max(1)
+        } else {
+            u32::MAX
+        }
+    }
+
+    /// Starts reloading of the weapon. Rounds will be moved from the owner's inventory to the
+    /// magazine when reloading is finished, the weapon cannot shoot until then. Returns `true`
+    /// if reloading has started.
+    pub fn reload(&mut self, inventory: &Inventory) -> bool {
+        if self.is_melee()
+            || self.is_reloading()
+            || self.rounds_in_magazine >= self.magazine_capacity()
+            || self.reserve_rounds(inventory) == 0
+        {
+            return false;
+        }
+
+        // Make sure that the reloading will finish even if the reload time is zero.
+        self.reload_timer = self.reload_time.max(f32::EPSILON);
+
+        true
+    }
+
+    fn is_low_on_ammo(&self, rounds_in_magazine: u32) -> bool {
+        (rounds_in_magazine as f32) < self.magazine_capacity() as f32 * *self.low_ammo_threshold
+    }
+
+    /// Sends ammo events if the amount of ammo has changed since the last report.
+    fn report_ammo(&mut self, graph: &Graph, sender: &ScriptMessageSender) {
+        if self.is_melee() || self.owner.is_none() {
+            return;
+        }
+
+        let reserve_rounds = try_get_character_ref(self.owner, graph)
+            .map(|owner| self.reserve_rounds(owner.inventory()))
+            .unwrap_or_default();
+        let ammo = (self.rounds_in_magazine, reserve_rounds);
+        if self.reported_ammo == Some(ammo) {
+            return;
+        }
+
+        // Do not warn on the first report, only when the threshold is actually crossed.
+        let was_low_on_ammo = match self.reported_ammo {
+            Some((rounds, _)) => self.is_low_on_ammo(rounds),
+            None => true,
+        };
+        self.reported_ammo = Some(ammo);
+
+        sender.send_global(AmmoEvent {
+            weapon: self.self_handle,
+            owner: self.owner,
+            weapon_kind: self.weapon_type,
+            rounds_in_magazine: self.rounds_in_magazine,
+            magazine_capacity: self.magazine_capacity(),
+            reserve_rounds,
+        });
+
+        if !was_low_on_ammo && self.is_low_on_ammo(self.rounds_in_magazine) {
+            sender.send_global(LowAmmoWarning {
+                weapon: self.self_handle,
+                owner: self.owner,
+                weapon_kind: self.weapon_type,
+                rounds_in_magazine: self.rounds_in_magazine,
+            });
+        }
+    }
+
+    fn finish_reload(&mut self, inventory: &mut Inventory) {
+        let required = self
+            .magazine_capacity()
+            .saturating_sub(self.rounds_in_magazine);
+        let rounds = required.min(self.reserve_rounds(inventory));
+
+        if self.uses_ammo() {
+            let ammo = rounds * *self.ammo_consumption_per_shot;
+            if ammo > 0
+                && inventory.try_extract_exact_items_by(|item| self.is_compatible_ammo(item), ammo)
+                    != ammo
+            {
+                return;
+            }
+        }
+
+        self.rounds_in_magazine += rounds;
+    }
+
+    fn update_reload(&mut self, graph: &mut Graph, dt: f32) {
+        if !self.is_reloading() {
+            return;
+        }
+
+        self.reload_timer -= dt;
+
+        if self.reload_timer <= 0.0 {
+            self.reload_timer = 0.0;
+
+            if let Some(owner) = try_get_character_mut(self.owner, graph) {
+                self.finish_reload(owner.inventory_mut());
+            }
+        }
     }
 
     pub fn gen_v_recoil_angle(&self) -> f32 {
@@ -163,13 +838,136 @@ impl Weapon {
             .gen_range(self.h_recoil.x.to_radians()..self.h_recoil.y.to_radians())
     }
 
+    /// Finds the first hit box in front of the weapon (within its melee range) that does not
+    /// belong to the owner and damages it.
+    fn swing(
+        &self,
+        self_handle: Handle<Node>,
+        scene: &Scene,
+        direction: Vector3<f32>,
+        script_message_sender: &ScriptMessageSender,
+    ) {
+        let graph = &scene.graph;
+        let owner = try_get_character_ref(self.owner, graph);
+
+        // Start the ray a bit behind the weapon, so the targets that are too close won't be
+        // missed.
+        let origin = self.shot_position(graph) - direction.scale(*self.melee_range * 0.5);
+        let ray_direction = direction.scale(*self.melee_range * 1.5);
+
+        let mut query_buffer = ArrayVec::<Intersection, 64>::new();
+        graph.physics.cast_ray(
+            RayCastOptions {
+                ray_origin: Point3::from(origin),
+                ray_direction,
+                max_len: ray_direction.norm(),
+                groups: Default::default(),
+                sort_results: true,
+            },
+            &mut query_buffer,
+        );
+
+        for intersection in query_buffer {
+            if let Some(owner) = owner {
+                if intersection.collider == owner.capsule_collider
+                    || owner.hit_boxes.contains(&intersection.collider)
+                {
+                    continue;
+                }
+            }
+
+            let collider =
+                some_or_continue!(graph.try_get_of_type::<Collider>(intersection.collider));
+
+            if graph
+                .try_get_script_of::<HitBox>(intersection.collider)
+                .is_some()
+            {
+                script_message_sender.send_hierarchical(
+                    intersection.collider,
+                    RoutingStrategy::Up,
+                    HitBoxMessage::Damage(HitBoxDamage {
+                        hit_box: intersection.collider,
+                        damage: *self.melee_damage,
+                        dealer: DamageDealer {
+                            entity: self_handle,
+                        },
+                        position: Some(DamagePosition {
+                            point: intersection.position.coords,
+                            direction,
+                        }),
+                        is_melee: true,
+                        is_critical: false,
+                    }),
+                );
+                break;
+            } else if !matches!(collider.shape(), ColliderShape::Capsule(_))
+                && !collider.is_sensor()
+            {
+                // Something solid is in the way.
+                break;
+            }
+        }
+    }
+
+    fn eject_shell_casing(&self, scene: &mut Scene) {
+        let shell_casing = some_or_return!(self.shell_casing.as_ref());
+
+        let ejection_node = if self.shell_ejection_point.is_some() {
+            *self.shell_ejection_point
+        } else {
+            // Fallback
+            self.self_handle
+        };
+        let (rotation, position) = scene.graph.global_rotation_position_no_scale(ejection_node);
+        let side = scene.graph[ejection_node]
+            .side_vector()
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::x);
+
+        let casing = shell_casing.instantiate_at(scene, position, rotation);
+
+        let mut rng = fyrox::rand::thread_rng();
+        let velocity = (side + Vector3::new(0.0, rng.gen_range(0.5..1.0), 0.0))
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_default()
+            .scale(*self.shell_ejection_speed * rng.gen_range(0.8..1.2));
+
+        let casing_ref = &mut scene.graph[casing];
+        casing_ref.set_lifetime(Some(*self.shell_casing_lifetime));
+        if let Some(rigid_body) = casing_ref.cast_mut::<RigidBody>() {
+            rigid_body.set_lin_vel(velocity);
+            rigid_body.set_ang_vel(Vector3::new(
+                rng.gen_range(-20.0..20.0),
+                rng.gen_range(-20.0..20.0),
+                rng.gen_range(-20.0..20.0),
+            ));
+        }
+    }
+
     fn shoot(
         &mut self,
         self_handle: Handle<Node>,
         scene: &mut Scene,
         elapsed_time: f32,
         direction: Option<Vector3<f32>>,
+        spread_factor: f32,
+        script_message_sender: &ScriptMessageSender,
+        level: &mut Level,
     ) {
+        // Damage and speed multipliers of the projectile.
+        let (damage_scale, speed_scale) = match self.charge_timer.take() {
+            Some(charge_time) if charge_time < *self.charge_time_min => return,
+            Some(charge_time) => {
+                let k = self.charge_level_at(charge_time);
+                (
+                    1.0 + (*self.charge_damage_scale - 1.0) * k,
+                    1.0 + (*self.charge_speed_scale - 1.0) * k,
+                )
+            }
+            None => (1.0, 1.0),
+        };
+
         self.last_shot_time = elapsed_time;
 
         let shot_position = self.shot_position(&scene.graph);
@@ -177,6 +975,52 @@ impl Weapon {
             .unwrap_or_else(|| self.shot_direction(&scene.graph))
             .try_normalize(f32::EPSILON)
             .unwrap_or_else(Vector3::z);
+        let direction = if self.is_melee() {
+            direction
+        } else {
+            utils::random_direction_in_cone(direction, (*self.spread * spread_factor).to_radians())
+        };
+
+        if self.is_melee() {
+            self.swing(self_handle, scene, direction, script_message_sender);
+        } else {
+            self.rounds_in_magazine = self.rounds_in_magazine.saturating_sub(1);
+            self.report_ammo(&scene.graph, script_message_sender);
+
+            self.eject_shell_casing(scene);
+
+            let muzzle_flash_intensity = self.muzzle_flash_intensity();
+            if *self.muzzle_flash_enabled
+                && muzzle_flash_intensity > 0.0
+                && *self.muzzle_flash_duration > 0.0
+            {
+                MuzzleFlash::spawn(
+                    &mut scene.graph,
+                    shot_position,
+                    *self.muzzle_flash_color,
+                    muzzle_flash_intensity,
+                    *self.muzzle_flash_radius,
+                    *self.muzzle_flash_duration,
+                );
+            }
+
+            let shot_noise_radius = self.shot_noise_radius();
+            if shot_noise_radius > 0.0 {
+                for listener in level
+                    .actor_grid
+                    .query_radius(shot_position, shot_noise_radius)
+                {
+                    script_message_sender.send_to_target(
+                        listener,
+                        NoiseMessage {
+                            source: self.owner,
+                            position: shot_position,
+                            radius: shot_noise_radius,
+                        },
+                    );
+                }
+            }
+        }
 
         if let Some(vfx) = self
             .shot_vfx
@@ -187,14 +1031,19 @@ impl Weapon {
         }
 
         if let Some(model) = self.projectile.as_ref() {
-            Projectile::spawn(
+            let projectile = Projectile::spawn(
                 model,
                 scene,
                 direction,
                 shot_position,
                 self_handle,
                 Default::default(),
+                Some(&mut level.projectile_pool),
             );
+
+            if let Some(projectile) = scene.graph[projectile].try_get_script_mut::<Projectile>() {
+                projectile.set_charge_scales(damage_scale, speed_scale);
+            }
         }
     }
 }
@@ -202,6 +1051,9 @@ impl Weapon {
 impl ScriptTrait for Weapon {
     fn on_init(&mut self, ctx: &mut ScriptContext) {
         self.item.on_init(ctx);
+
+        // New weapons come with a full magazine.
+        self.rounds_in_magazine = self.magazine_capacity();
     }
 
     fn on_start(&mut self, ctx: &mut ScriptContext) {
@@ -225,6 +1077,11 @@ impl ScriptTrait for Weapon {
     fn on_update(&mut self, ctx: &mut ScriptContext) {
         self.item.enabled = self.owner.is_none();
         self.item.on_update(ctx);
+
+        self.update_reload(&mut ctx.scene.graph, ctx.dt);
+        self.update_charge(&ctx.scene.graph, ctx.dt);
+        // Catches the rest of the changes - finished reloading, ammo pickups, etc.
+        self.report_ammo(&ctx.scene.graph, ctx.message_sender);
     }
 
     fn on_message(
@@ -239,8 +1096,39 @@ impl ScriptTrait for Weapon {
                 return;
             }
 
-            if let WeaponMessageData::Shoot { direction } = msg.data {
-                self.shoot(ctx.handle, ctx.scene, ctx.elapsed_time, direction);
+            if let WeaponMessageData::Shoot {
+                direction,
+                spread_factor,
+            } = msg.data
+            {
+                // The weapon is being drawn or holstered.
+                if try_get_character_ref(self.owner, &ctx.scene.graph)
+                    .is_some_and(|owner| owner.is_switching_weapon())
+                {
+                    return;
+                }
+
+                // Too early for the next shot, the weapon cannot shoot faster than its fire rate.
+                if self.is_fire_rate_exceeded(ctx.elapsed_time) {
+                    return;
+                }
+
+                let level = ctx
+                    .plugins
+                    .get_mut::<Game>()
+                    .level
+                    .as_mut()
+                    .expect("Level must exist!");
+
+                self.shoot(
+                    ctx.handle,
+                    ctx.scene,
+                    ctx.elapsed_time,
+                    direction,
+                    spread_factor,
+                    ctx.message_sender,
+                    level,
+                );
             }
         }
     }
@@ -255,3 +1143,108 @@ pub fn weapon_mut(handle: Handle<Node>, graph: &mut Graph) -> &mut Weapon {
 pub fn weapon_ref(handle: Handle<Node>, graph: &Graph) -> &Weapon {
     graph.try_get_script_component_of::<Weapon>(handle).unwrap()
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{inventory::Inventory, level::item::Item, weapon::Weapon};
+    use fyrox::{
+        graph::BaseSceneGraph,
+        resource::model::{Model, ModelResource, NodeMapping},
+        scene::Scene,
+    };
+
+    fn loaded_weapon(fire_rate: f32) -> Weapon {
+        let mut weapon = Weapon {
+            rounds_in_magazine: 10,
+            last_shot_time: 1.0,
+            ..Default::default()
+        };
+        weapon.fire_rate.set_value_and_mark_modified(fire_rate);
+        weapon
+    }
+
+    #[test]
+    fn test_fire_rate_limits_shots() {
+        // 600 RPM means one shot every 0.1 seconds.
+        let weapon = loaded_weapon(600.0);
+        assert!((weapon.shot_interval() - 0.1).abs() < 1.0e-6);
+
+        assert!(!weapon.can_shoot(1.0));
+        assert!(!weapon.can_shoot(1.05));
+        assert!(weapon.can_shoot(1.11));
+    }
+
+    #[test]
+    fn test_zero_fire_rate_falls_back_to_shoot_interval() {
+        let mut weapon = loaded_weapon(0.0);
+        weapon.shoot_interval.set_value_and_mark_modified(0.5);
+        assert_eq!(weapon.shot_interval(), 0.5);
+
+        assert!(!weapon.can_shoot(1.4));
+        assert!(weapon.can_shoot(1.6));
+    }
+
+    fn ammo(caliber: &str) -> ModelResource {
+        let mut item = Item::default();
+        item.caliber
+            .set_value_and_mark_modified(caliber.to_string());
+
+        let mut scene = Scene::new();
+        let root = scene.graph.get_root();
+        scene.graph[root].add_script(item);
+
+        ModelResource::new_embedded(Model::new(NodeMapping::UseNames, scene))
+    }
+
+    fn weapon(ammo_item: Option<&ModelResource>, caliber: &str) -> Weapon {
+        let mut weapon = Weapon::default();
+        weapon
+            .ammo_item
+            .set_value_and_mark_modified(ammo_item.cloned());
+        weapon
+            .caliber
+            .set_value_and_mark_modified(caliber.to_string());
+        weapon
+            .ammo_consumption_per_shot
+            .set_value_and_mark_modified(1);
+        weapon
+    }
+
+    #[test]
+    fn test_m4_ammo_tops_up_weapons_of_same_caliber() {
+        let m4_ammo = ammo("5.56");
+        let ak47_ammo = ammo("7.62");
+
+        let m4 = weapon(Some(&m4_ammo), "5.56");
+        let rifle = weapon(None, "5.56");
+        let ak47 = weapon(Some(&ak47_ammo), "7.62");
+
+        let mut inventory = Inventory::new();
+        inventory.add_item(&ak47_ammo, 5);
+        assert_eq!(rifle.reserve_rounds(&inventory), 0);
+
+        inventory.add_item(&m4_ammo, 60);
+        assert_eq!(m4.reserve_rounds(&inventory), 60);
+        assert_eq!(rifle.reserve_rounds(&inventory), 60);
+        assert_eq!(ak47.reserve_rounds(&inventory), 5);
+        assert_eq!(
+            inventory.item_count_by(|item| rifle.is_compatible_ammo(item)),
+            60
+        );
+
+        // Ammo taken by one weapon is no longer available to the others of the same caliber.
+        assert_eq!(
+            inventory.try_extract_exact_items_by(|item| rifle.is_compatible_ammo(item), 45),
+            45
+        );
+        assert_eq!(m4.reserve_rounds(&inventory), 15);
+        assert_eq!(ak47.reserve_rounds(&inventory), 5);
+
+        // Nothing is extracted if there is not enough ammo.
+        assert_eq!(
+            inventory.try_extract_exact_items_by(|item| m4.is_compatible_ammo(item), 20),
+            0
+        );
+        assert_eq!(inventory.item_count(&m4_ammo), 15);
+    }
+}
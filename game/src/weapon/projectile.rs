@@ -1,23 +1,27 @@
 use crate::level::hit_box::HitBoxDamage;
 use crate::{
-    character::{Character, DamageDealer, DamagePosition},
+    character::{try_get_character_ref, Character, DamageDealer, DamagePosition},
+    effects::trail::Trail,
     level::{
         decal::Decal,
         hit_box::{HitBox, HitBoxMessage},
+        Level,
     },
-    CollisionGroups, Game, Weapon,
+    sound::{MaterialType, SoundKind},
+    utils, CollisionGroups, Game, Weapon,
 };
 use fyrox::{
     core::{
-        algebra::{Point3, Vector3},
+        algebra::{Point3, UnitQuaternion, Vector3},
         color::Color,
         math::{ray::Ray, vector_to_quat, Vector3Ext},
         pool::Handle,
         reflect::prelude::*,
-        stub_uuid_provider,
+        some_or_continue, some_or_return, stub_uuid_provider,
         type_traits::prelude::*,
         visitor::prelude::*,
     },
+    fxhash::FxHashMap,
     graph::{BaseSceneGraph, SceneGraph, SceneGraphNode},
     rand::seq::SliceRandom,
     resource::model::{ModelResource, ModelResourceExtension},
@@ -31,12 +35,62 @@ use fyrox::{
         rigidbody::RigidBody,
         Scene,
     },
-    script::{RoutingStrategy, ScriptContext, ScriptTrait},
+    script::{
+        RoutingStrategy, ScriptContext, ScriptDeinitContext, ScriptMessageSender, ScriptTrait,
+    },
 };
 use serde::Deserialize;
-use std::hash::{Hash, Hasher};
+use std::{
+    fmt::{Display, Formatter},
+    hash::{Hash, Hasher},
+};
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 
+/// Checks whether splash damage of the dealer could hurt an owner of the given hit box. Allies of
+/// the dealer are not affected, unless friendly fire is enabled.
+fn can_splash_damage(
+    dealer: DamageDealer,
+    hit_box: Handle<Node>,
+    graph: &Graph,
+    friendly_fire: bool,
+) -> bool {
+    friendly_fire || !dealer.is_ally_of_owner(hit_box, graph)
+}
+
+/// Damages every hit box in the given radius around the center.
+pub fn deal_splash_damage(
+    game: &Game,
+    graph: &Graph,
+    message_sender: &ScriptMessageSender,
+    center: Vector3<f32>,
+    radius: f32,
+    amount: f32,
+    dealer: DamageDealer,
+    position: DamagePosition,
+) {
+    let level = some_or_return!(game.level.as_ref());
+    let friendly_fire = game.is_friendly_fire_enabled();
+
+    for hit_box in level.hit_box_grid.query_radius(center, radius) {
+        if !can_splash_damage(dealer, hit_box, graph, friendly_fire) {
+            continue;
+        }
+
+        message_sender.send_hierarchical(
+            hit_box,
+            RoutingStrategy::Up,
+            HitBoxMessage::Damage(HitBoxDamage {
+                hit_box,
+                damage: amount,
+                dealer,
+                position: Some(position),
+                is_melee: false,
+                is_critical: false,
+            }),
+        );
+    }
+}
+
 #[derive(Deserialize, Copy, Clone, Debug, Visit, Reflect, AsRefStr, EnumString, VariantNames)]
 pub enum Damage {
     Splash { radius: f32, amount: f32 },
@@ -71,6 +125,15 @@ impl Damage {
     }
 }
 
+/// An impact effect that is used when a projectile hits a surface of the specific material.
+#[derive(Default, Debug, Clone, Visit, Reflect)]
+pub struct MaterialImpactEffect {
+    pub material: MaterialType,
+    pub effect: Option<ModelResource>,
+}
+
+stub_uuid_provider!(MaterialImpactEffect);
+
 #[derive(Clone, Debug)]
 pub struct Hit {
     pub shooter_actor: Handle<Node>,
@@ -123,9 +186,49 @@ pub struct Projectile {
 
     speed: Option<f32>,
 
+    #[reflect(
+        description = "Defines how strong gravity affects kinematic projectiles. Zero means that \
+        the projectile moves in a straight line, any positive value makes it follow a ballistic arc."
+    )]
+    gravity_scale: f32,
+
+    #[reflect(
+        description = "Defines how fast the projectile steers towards the nearest actor. Zero \
+        disables homing."
+    )]
+    homing_strength: f32,
+
+    #[reflect(
+        description = "Maximum turn rate (in degrees per second) of homing projectiles. Limited \
+        turn rate allows targets to dodge homing projectiles."
+    )]
+    homing_max_turn_rate: f32,
+
+    // Accumulated downward velocity (in m/s) of kinematic projectiles.
+    #[reflect(hidden)]
+    gravity_velocity: Vector3<f32>,
+
     #[visit(rename = "ImpactEffect")]
     environment_impact_effect: Option<ModelResource>,
 
+    #[reflect(
+        description = "Impact effects for specific materials of the environment (sparks for \
+        metal, dust for stone, etc.). The environment impact effect is used for materials that \
+        are not in the list."
+    )]
+    material_impact_effects: Vec<MaterialImpactEffect>,
+
+    #[reflect(
+        description = "Whether the projectile plays material-specific impact sounds when it hits \
+        the environment."
+    )]
+    play_impact_sounds: bool,
+
+    #[reflect(
+        description = "A prefab that will be instantiated when the projectile hits a hit box \
+        (usually a blood spray). The environment impact effect is used instead when gore is \
+        disabled in the settings."
+    )]
     flesh_impact_effect: Option<ModelResource>,
 
     #[reflect(
@@ -148,11 +251,214 @@ pub struct Projectile {
     #[reflect(min_value = 0.0, max_value = 1.0)]
     critical_hit_probability: f32,
 
+    #[reflect(
+        min_value = 0.0,
+        description = "Damage multiplier that will be used for critical hits."
+    )]
+    critical_hit_damage_multiplier: f32,
+
+    #[reflect(
+        description = "Whether the kinematic projectile should leave a fading trail behind it. \
+        Physical projectiles do not leave trails."
+    )]
+    trail_enabled: bool,
+
+    #[reflect(description = "Color of the trail of the projectile.")]
+    trail_color: Color,
+
+    #[reflect(min_value = 0.0, description = "Width of the trail (in meters).")]
+    trail_width: f32,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) in which each point of the trail fades out."
+    )]
+    trail_lifetime: f32,
+
+    #[reflect(
+        description = "Whether the projectile collides with other interceptable projectiles. Both \
+        projectiles are destroyed on collision, so a plasma bolt could detonate an incoming rocket, \
+        for example."
+    )]
+    can_intercept: bool,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Radius (in meters) of the projectile that is used for interception."
+    )]
+    interception_radius: f32,
+
+    #[reflect(
+        description = "A prefab that will be instantiated when the projectile intercepts another \
+        projectile."
+    )]
+    interception_effect: Option<ModelResource>,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) after which the projectile detonates by itself. Zero \
+        means that there is no fuse and the projectile detonates on impact."
+    )]
+    fuse_time: f32,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Min speed (in m/s) at which a projectile with a fuse detonates on impact \
+        with environment. Slower projectiles just bounce off. Zero means that any impact \
+        detonates the projectile. The default is faster than a thrown grenade, so thrown grenades \
+        wait for their fuse."
+    )]
+    impact_detonation_speed: f32,
+
+    #[reflect(
+        description = "Amount of times the projectile bounces off environment before it detonates \
+        on impact. Hits on characters are not affected."
+    )]
+    bounce_count: u32,
+
+    #[reflect(
+        min_value = 0.0,
+        max_value = 1.0,
+        description = "Fraction of speed that the projectile keeps after a bounce."
+    )]
+    bounce_restitution: f32,
+
+    #[reflect(hidden)]
+    bounces_left: u32,
+
+    // Time (in seconds) after a bounce during which contacts with environment are ignored, so the
+    // projectile won't bounce multiple times off the same surface.
+    #[reflect(hidden)]
+    bounce_timer: f32,
+
+    // Time (in seconds) the projectile is flying.
+    #[reflect(hidden)]
+    flight_time: f32,
+
+    // Time (in seconds) the fuse was burning before the projectile was launched.
+    #[reflect(hidden)]
+    cook_time: f32,
+
+    #[reflect(
+        description = "Distance (in meters) along the shot direction at which the projectile \
+        is spawned in front of the shot point."
+    )]
+    spawn_offset: f32,
+
+    #[reflect(
+        min_value = 0.0,
+        description = "Time (in seconds) after the launch during which the projectile ignores \
+        colliders of its owner, so it does not hit the owner at point-blank range."
+    )]
+    owner_collision_grace_time: f32,
+
+    // Multipliers of damage and speed of a charged shot.
+    #[reflect(hidden)]
+    damage_scale: f32,
+    #[reflect(hidden)]
+    speed_scale: f32,
+
+    // Actual velocity (in m/s) of the projectile, calculated from its displacement over the last
+    // frame.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    velocity: Vector3<f32>,
+
+    // Position of the projectile at the end of the last frame.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    prev_frame_position: Vector3<f32>,
+
+    // Whether the projectile was intercepted by another projectile and must be despawned.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    intercepted: bool,
+
     // A handle to collider of the projectile. It is used as a cache to prevent searching for it
     // every frame.
     #[visit(skip)]
     #[reflect(hidden)]
     collider: Handle<Node>,
+
+    // Whether the projectile should be returned to the projectile pool of the level instead of
+    // being destroyed.
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pooled: bool,
+
+    // A handle to the trail that is left by the projectile.
+    #[reflect(hidden)]
+    trail: Handle<Node>,
+}
+
+/// Amount of projectiles that were instantiated and reused by a [`ProjectilePool`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ProjectilePoolStats {
+    pub instantiated: usize,
+    pub reused: usize,
+    pub free: usize,
+}
+
+impl Display for ProjectilePoolStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} instantiated, {} reused, {} free",
+            self.instantiated, self.reused, self.free
+        )
+    }
+}
+
+/// A storage of disabled projectiles that could be reused instead of instantiating new ones.
+/// Instantiation of a prefab is quite expensive and rapid-fire weapons spawn lots of projectiles.
+#[derive(Default, Debug)]
+pub struct ProjectilePool {
+    free: FxHashMap<ModelResource, Vec<Handle<Node>>>,
+    instantiated: usize,
+    reused: usize,
+}
+
+impl ProjectilePool {
+    fn acquire(&mut self, resource: &ModelResource, graph: &Graph) -> Option<Handle<Node>> {
+        let free = self.free.get_mut(resource)?;
+        while let Some(handle) = free.pop() {
+            // The projectile could be deleted together with its parent node.
+            if graph.try_get(handle).is_some() {
+                self.reused += 1;
+                return Some(handle);
+            }
+        }
+        None
+    }
+
+    /// Disables the projectile and puts it in the pool. Returns `false` if the projectile is not
+    /// an instance of a prefab and cannot be pooled.
+    fn release(&mut self, handle: Handle<Node>, graph: &mut Graph) -> bool {
+        let Some(node) = graph.try_get_mut(handle) else {
+            return false;
+        };
+        let Some(resource) = node.root_resource() else {
+            return false;
+        };
+
+        node.set_enabled(false);
+        if let Some(rigid_body) = node.cast_mut::<RigidBody>() {
+            rigid_body.set_lin_vel(Default::default());
+            rigid_body.set_ang_vel(Default::default());
+        }
+
+        self.free.entry(resource).or_default().push(handle);
+
+        true
+    }
+
+    pub fn stats(&self) -> ProjectilePoolStats {
+        ProjectilePoolStats {
+            instantiated: self.instantiated,
+            reused: self.reused,
+            free: self.free.values().map(|free| free.len()).sum(),
+        }
+    }
 }
 
 impl Default for Projectile {
@@ -164,19 +470,52 @@ impl Default for Projectile {
             last_position: Default::default(),
             use_ray_casting: true,
             speed: Some(1.0),
+            gravity_scale: 0.0,
+            gravity_velocity: Default::default(),
+            homing_strength: 0.0,
+            homing_max_turn_rate: 90.0,
             environment_impact_effect: None,
+            material_impact_effects: Default::default(),
+            play_impact_sounds: false,
             flesh_impact_effect: None,
             appear_effect: None,
             random_appear_effects: Default::default(),
             one_frame: false,
             damage: Default::default(),
             critical_hit_probability: 0.025,
+            critical_hit_damage_multiplier: 2.0,
+            trail_enabled: false,
+            trail_color: Color::from_rgba(255, 200, 120, 160),
+            trail_width: 0.015,
+            trail_lifetime: 0.2,
+            can_intercept: false,
+            interception_radius: 0.25,
+            interception_effect: None,
+            fuse_time: 0.0,
+            impact_detonation_speed: 20.0,
+            bounce_count: 0,
+            bounce_restitution: 0.5,
+            bounces_left: 0,
+            bounce_timer: 0.0,
+            flight_time: 0.0,
+            cook_time: 0.0,
+            spawn_offset: 0.0,
+            owner_collision_grace_time: 0.1,
+            damage_scale: 1.0,
+            speed_scale: 1.0,
+            velocity: Default::default(),
+            prev_frame_position: Default::default(),
+            intercepted: false,
             collider: Default::default(),
+            pooled: false,
+            trail: Default::default(),
         }
     }
 }
 
 impl Projectile {
+    /// Spawns a new projectile. If the pool is specified, a previously despawned projectile will
+    /// be reused (if any) and the projectile will be returned to the pool when it is despawned.
     pub fn spawn(
         resource: &ModelResource,
         scene: &mut Scene,
@@ -184,17 +523,438 @@ impl Projectile {
         position: Vector3<f32>,
         owner: Handle<Node>,
         initial_velocity: Vector3<f32>,
+        pool: Option<&mut ProjectilePool>,
     ) -> Handle<Node> {
-        let instance_handle = resource.instantiate_at(scene, position, vector_to_quat(dir));
+        let pooled = pool.is_some();
+
+        let mut reused = None;
+        if let Some(pool) = pool {
+            reused = pool.acquire(resource, &scene.graph);
+            if reused.is_none() {
+                pool.instantiated += 1;
+            }
+        }
+
+        let instance_handle = if let Some(handle) = reused {
+            let node = &mut scene.graph[handle];
+            node.set_enabled(true);
+            node.set_lifetime(None);
+            node.local_transform_mut()
+                .set_position(position)
+                .set_rotation(vector_to_quat(dir));
+            scene.graph.update_hierarchical_data_for_descendants(handle);
+            handle
+        } else {
+            resource.instantiate_at(scene, position, vector_to_quat(dir))
+        };
+
+        let spawn_offset = scene.graph[instance_handle]
+            .try_get_script::<Projectile>()
+            .map_or(0.0, |projectile| projectile.spawn_offset);
+        let position = if spawn_offset != 0.0 {
+            let position = position
+                + dir
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_default()
+                    .scale(spawn_offset);
+            scene.graph[instance_handle]
+                .local_transform_mut()
+                .set_position(position);
+            scene
+                .graph
+                .update_hierarchical_data_for_descendants(instance_handle);
+            position
+        } else {
+            position
+        };
 
+        let mut launch_params = None;
         if let Some(projectile) = scene.graph[instance_handle].try_get_script_mut::<Projectile>() {
             projectile.initial_velocity = initial_velocity;
             projectile.dir = dir.try_normalize(f32::EPSILON).unwrap_or_else(Vector3::y);
             projectile.owner = owner;
+            projectile.pooled = pooled;
+            projectile.cook_time = 0.0;
+            projectile.damage_scale = 1.0;
+            projectile.speed_scale = 1.0;
+
+            // Reused projectiles are already initialized, so they must be prepared for the
+            // flight manually.
+            if reused.is_some() {
+                launch_params = Some((projectile.dir, projectile.begin_flight(position)));
+            }
+        }
+
+        if let Some((dir, effects)) = launch_params {
+            Self::launch(
+                instance_handle,
+                scene,
+                position,
+                dir,
+                initial_velocity,
+                effects,
+            );
         }
 
         instance_handle
     }
+
+    /// Resets the flight state of the projectile and returns a list of effects that should be
+    /// spawned at the starting point.
+    fn begin_flight(&mut self, position: Vector3<f32>) -> Vec<ModelResource> {
+        self.last_position = position;
+        self.gravity_velocity = Default::default();
+        self.flight_time = 0.0;
+        self.velocity = Default::default();
+        self.prev_frame_position = position;
+        self.intercepted = false;
+        self.bounces_left = self.bounce_count;
+        self.bounce_timer = 0.0;
+        // A reused projectile must not continue the trail of its previous flight.
+        self.trail = Handle::NONE;
+
+        self.appear_effect
+            .iter()
+            .cloned()
+            .chain(
+                self.random_appear_effects
+                    .choose(&mut fyrox::rand::thread_rng())
+                    .cloned()
+                    .flatten(),
+            )
+            .collect()
+    }
+
+    /// Reflects the velocity of the projectile about the normal of the surface it has hit. Some
+    /// energy is lost on every bounce.
+    fn bounce(
+        &mut self,
+        handle: Handle<Node>,
+        normal: Vector3<f32>,
+        position: Vector3<f32>,
+        graph: &mut Graph,
+        dt: f32,
+    ) {
+        const BOUNCE_COOLDOWN: f32 = 0.1;
+
+        let normal = normal
+            .try_normalize(f32::EPSILON)
+            .unwrap_or_else(Vector3::y);
+        let restitution = self.bounce_restitution;
+        let reflect = |v: Vector3<f32>| (v - normal.scale(2.0 * v.dot(&normal))).scale(restitution);
+
+        if let Some(rigid_body) = graph[handle].cast_mut::<RigidBody>() {
+            let lin_vel = rigid_body.lin_vel();
+            rigid_body.set_lin_vel(reflect(lin_vel));
+        }
+
+        // Kinematic projectiles move by a fixed offset every frame.
+        if let Some(speed) = self.speed.filter(|speed| *speed > 0.0) {
+            let offset =
+                reflect(self.dir.scale(speed * self.speed_scale) + self.gravity_velocity.scale(dt));
+            self.gravity_velocity = Default::default();
+            if let Some(dir) = offset.try_normalize(f32::EPSILON) {
+                self.dir = dir;
+            }
+            self.speed_scale = offset.norm() / speed;
+
+            // Move the projectile back to the surface, otherwise it could end up behind it.
+            graph[handle].local_transform_mut().set_position(position);
+            graph.update_hierarchical_data_for_descendants(handle);
+            self.last_position = position;
+            self.prev_frame_position = position;
+        }
+
+        self.bounces_left -= 1;
+        self.bounce_timer = BOUNCE_COOLDOWN;
+    }
+
+    fn launch(
+        handle: Handle<Node>,
+        scene: &mut Scene,
+        position: Vector3<f32>,
+        dir: Vector3<f32>,
+        initial_velocity: Vector3<f32>,
+        effects: Vec<ModelResource>,
+    ) {
+        if let Some(rigid_body) = scene.graph[handle].cast_mut::<RigidBody>() {
+            rigid_body.set_lin_vel(initial_velocity);
+            rigid_body.set_ang_vel(Default::default());
+        }
+
+        for effect in effects {
+            effect.instantiate_at(scene, position, vector_to_quat(dir));
+        }
+    }
+
+    /// Returns the projectile to the projectile pool of the level, or destroys it, if it does not
+    /// belong to the pool.
+    fn despawn(&self, ctx: &mut ScriptContext) {
+        if self.pooled {
+            if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+                if level
+                    .projectile_pool
+                    .release(ctx.handle, &mut ctx.scene.graph)
+                {
+                    return;
+                }
+            }
+        }
+
+        // Defer destruction.
+        ctx.scene.graph[ctx.handle].set_lifetime(Some(0.0));
+    }
+
+    pub fn from_resource<F, R>(model_resource: &ModelResource, func: F) -> R
+    where
+        F: FnOnce(Option<&Projectile>) -> R,
+    {
+        let data = model_resource.data_ref();
+        let graph = &data.get_scene().graph;
+        func(graph.try_get_script_component_of::<Projectile>(graph.get_root()))
+    }
+
+    pub fn fuse_time(&self) -> f32 {
+        self.fuse_time
+    }
+
+    /// Sets the time (in seconds) after which the projectile detonates by itself. Zero removes
+    /// the fuse.
+    pub fn set_fuse_time(&mut self, fuse_time: f32) {
+        self.fuse_time = fuse_time.max(0.0);
+    }
+
+    /// Shortens the fuse of the projectile by the given time (in seconds). Has no effect on
+    /// projectiles without a fuse.
+    pub fn cook(&mut self, time: f32) {
+        self.cook_time = time;
+    }
+
+    /// Sets damage and speed multipliers of the projectile, used by charged shots. The speed
+    /// multiplier affects only kinematic projectiles.
+    pub fn set_charge_scales(&mut self, damage_scale: f32, speed_scale: f32) {
+        self.damage_scale = damage_scale;
+        self.speed_scale = speed_scale;
+    }
+
+    fn is_fuse_burnt(&self) -> bool {
+        self.fuse_time > 0.0 && self.flight_time + self.cook_time >= self.fuse_time
+    }
+
+    /// Returns the actual velocity (in m/s) of the projectile.
+    pub fn velocity(&self) -> Vector3<f32> {
+        self.velocity
+    }
+
+    /// Returns a character that has shot the projectile (if any).
+    pub fn owner_character(&self, graph: &Graph) -> Handle<Node> {
+        graph
+            .try_get(self.owner)
+            .map_or(Default::default(), |owner_node| {
+                if let Some(weapon) = owner_node.try_get_script::<Weapon>() {
+                    weapon.owner
+                } else if owner_node.try_get_script_component::<Character>().is_some() {
+                    self.owner
+                } else {
+                    Default::default()
+                }
+            })
+    }
+
+    /// Returns a node, whose colliders are ignored by the projectile right after the launch. It is
+    /// either a character or any other shooter (a turret, for example).
+    fn ignored_owner(&self, graph: &Graph) -> Handle<Node> {
+        if self.flight_time > self.owner_collision_grace_time {
+            return Handle::NONE;
+        }
+
+        let owner_character = self.owner_character(graph);
+        if owner_character.is_some() {
+            owner_character
+        } else {
+            self.owner
+        }
+    }
+
+    /// Searches for an interceptable projectile of someone else, that is close enough to be
+    /// intercepted by this projectile.
+    fn find_intercepted_projectile(
+        &self,
+        self_handle: Handle<Node>,
+        position: Vector3<f32>,
+        level: &Level,
+        graph: &Graph,
+    ) -> Option<Handle<Node>> {
+        let owner_character = self.owner_character(graph);
+
+        level.projectiles.iter().copied().find(|&other| {
+            if other == self_handle {
+                return false;
+            }
+
+            let Some(other_node) = graph.try_get(other) else {
+                return false;
+            };
+
+            // Pooled projectiles are disabled.
+            if !other_node.is_globally_enabled() {
+                return false;
+            }
+
+            let Some(other_projectile) = other_node.try_get_script::<Projectile>() else {
+                return false;
+            };
+
+            other_projectile.can_intercept
+                && !other_projectile.intercepted
+                && other_projectile.owner_character(graph) != owner_character
+                && other_node.global_position().metric_distance(&position)
+                    <= self.interception_radius + other_projectile.interception_radius
+        })
+    }
+
+    /// Checks whether the projectile collides with any other interceptable projectile and destroys
+    /// both projectiles on collision. Returns `true` if the projectile was despawned.
+    fn update_interception(&mut self, ctx: &mut ScriptContext) -> bool {
+        if self.intercepted {
+            // The projectile was intercepted by another one, which already spawned the effect.
+            self.despawn(ctx);
+            return true;
+        }
+
+        if !self.can_intercept {
+            return false;
+        }
+
+        let position = ctx.scene.graph[ctx.handle].global_position();
+
+        let Some(other) = ctx.plugins.get::<Game>().level.as_ref().and_then(|level| {
+            self.find_intercepted_projectile(ctx.handle, position, level, &ctx.scene.graph)
+        }) else {
+            return false;
+        };
+
+        let other_position = ctx.scene.graph[other].global_position();
+        if let Some(other_projectile) = ctx.scene.graph.try_get_script_of_mut::<Projectile>(other) {
+            other_projectile.intercepted = true;
+        }
+
+        if let Some(effect) = self.interception_effect.as_ref() {
+            effect.instantiate_at(
+                ctx.scene,
+                position.lerp(&other_position, 0.5),
+                UnitQuaternion::identity(),
+            );
+        }
+
+        self.despawn(ctx);
+
+        true
+    }
+
+    fn find_homing_target(
+        &self,
+        actors: &[Handle<Node>],
+        position: Vector3<f32>,
+        graph: &Graph,
+    ) -> Option<Vector3<f32>> {
+        let owner_character = self.owner_character(graph);
+
+        let mut closest_distance = f32::MAX;
+        let mut target = None;
+        for &actor in actors {
+            if actor == owner_character {
+                continue;
+            }
+
+            let character = some_or_continue!(try_get_character_ref(actor, graph));
+            if character.is_dead(graph) {
+                continue;
+            }
+
+            let point = character.most_vulnerable_point(graph);
+            let to_target = point - position;
+
+            // Ignore everything behind the projectile.
+            if to_target.dot(&self.dir) <= 0.0 {
+                continue;
+            }
+
+            let distance = to_target.norm();
+            if distance < closest_distance {
+                closest_distance = distance;
+                target = Some(point);
+            }
+        }
+
+        target
+    }
+
+    fn steer(&mut self, target: Vector3<f32>, position: Vector3<f32>, dt: f32) {
+        let desired_dir = some_or_return!((target - position).try_normalize(f32::EPSILON));
+        let angle = self.dir.angle(&desired_dir);
+        if angle <= f32::EPSILON {
+            return;
+        }
+
+        let step = (angle * self.homing_strength * dt)
+            .min(self.homing_max_turn_rate.to_radians() * dt)
+            .min(angle);
+
+        if let Some(rotation) = UnitQuaternion::rotation_between(&self.dir, &desired_dir) {
+            self.dir = rotation
+                .powf(step / angle)
+                .transform_vector(&self.dir)
+                .try_normalize(f32::EPSILON)
+                .unwrap_or(self.dir);
+        }
+    }
+
+    fn update_homing(&mut self, ctx: &mut ScriptContext) {
+        if self.homing_strength == 0.0 {
+            return;
+        }
+
+        let game = ctx.plugins.get::<Game>();
+        let level = some_or_return!(game.level.as_ref());
+
+        let node = &ctx.scene.graph[ctx.handle];
+        let position = node.global_position();
+        if let Some(target) = self.find_homing_target(&level.actors, position, &ctx.scene.graph) {
+            if self.speed.is_none() {
+                // Physical projectiles are moved by the physics engine, so the direction must be
+                // taken from the actual velocity of the body.
+                if let Some(rigid_body) = node.cast::<RigidBody>() {
+                    if let Some(dir) = rigid_body.lin_vel().try_normalize(f32::EPSILON) {
+                        self.dir = dir;
+                    }
+                }
+            }
+
+            self.steer(target, position, ctx.dt);
+
+            let node = &mut ctx.scene.graph[ctx.handle];
+            node.local_transform_mut()
+                .set_rotation(vector_to_quat(self.dir));
+            if let Some(rigid_body) = node.cast_mut::<RigidBody>() {
+                let speed = rigid_body.lin_vel().norm();
+                rigid_body.set_lin_vel(self.dir.scale(speed));
+            }
+        }
+    }
+}
+
+/// Checks whether the projectile should fly through the given collider. Colliders of the owner are
+/// ignored right after the launch (see [`Projectile::ignored_owner`]), as well as colliders of
+/// allies of the shooter if friendly fire is disabled.
+fn is_ignored_collider(
+    graph: &Graph,
+    collider: Handle<Node>,
+    ignored_owner: Handle<Node>,
+    ignored_allies_of: Option<DamageDealer>,
+) -> bool {
+    utils::is_descendant_of(graph, collider, ignored_owner)
+        || ignored_allies_of.is_some_and(|dealer| dealer.is_ally_of_owner(collider, graph))
 }
 
 fn ray_hit(
@@ -203,6 +963,8 @@ fn ray_hit(
     shooter: Handle<Node>,
     graph: &mut Graph,
     ignored_collider: Handle<Node>,
+    ignored_owner: Handle<Node>,
+    ignored_allies_of: Option<DamageDealer>,
 ) -> Option<Hit> {
     if begin == end {
         return None;
@@ -229,7 +991,10 @@ fn ray_hit(
     );
 
     // List of hits sorted by distance from ray origin.
-    if let Some(hit) = query_buffer.iter().find(|i| i.collider != ignored_collider) {
+    if let Some(hit) = query_buffer.iter().find(|i| {
+        i.collider != ignored_collider
+            && !is_ignored_collider(graph, i.collider, ignored_owner, ignored_allies_of)
+    }) {
         if graph.try_get_script_of::<HitBox>(hit.collider).is_some() {
             return Some(Hit {
                 shooter_actor: shooter,
@@ -258,27 +1023,18 @@ fn ray_hit(
 
 impl ScriptTrait for Projectile {
     fn on_init(&mut self, ctx: &mut ScriptContext) {
-        let node = &mut ctx.scene.graph[ctx.handle];
-
-        let current_position = node.global_position();
-
-        self.last_position = current_position;
-
-        if let Some(rigid_body) = node.cast_mut::<RigidBody>() {
-            rigid_body.set_lin_vel(self.initial_velocity);
-        }
+        let current_position = ctx.scene.graph[ctx.handle].global_position();
 
-        if let Some(appear_effect) = self.appear_effect.as_ref() {
-            appear_effect.instantiate_at(ctx.scene, current_position, vector_to_quat(self.dir));
-        }
+        let effects = self.begin_flight(current_position);
 
-        if let Some(vfx) = self
-            .random_appear_effects
-            .choose(&mut fyrox::rand::thread_rng())
-            .and_then(|vfx| vfx.as_ref())
-        {
-            vfx.instantiate_at(ctx.scene, current_position, vector_to_quat(self.dir));
-        }
+        Self::launch(
+            ctx.handle,
+            ctx.scene,
+            current_position,
+            self.dir,
+            self.initial_velocity,
+            effects,
+        );
     }
 
     fn on_start(&mut self, ctx: &mut ScriptContext) {
@@ -288,15 +1044,39 @@ impl ScriptTrait for Projectile {
             .find(ctx.handle, &mut |n| n.component_ref::<Collider>().is_some())
             .map(|(h, _)| h)
             .unwrap_or_default();
+
+        if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+            level.projectiles.insert(ctx.handle);
+        }
+    }
+
+    fn on_deinit(&mut self, ctx: &mut ScriptDeinitContext) {
+        if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+            level.projectiles.remove(&ctx.node_handle);
+        }
     }
 
     fn on_update(&mut self, ctx: &mut ScriptContext) {
+        if self.update_interception(ctx) {
+            return;
+        }
+
+        self.update_homing(ctx);
+
         let game = ctx.plugins.get::<Game>();
 
         // Movement of kinematic projectiles is controlled explicitly.
         if let Some(speed) = self.speed {
-            if speed != 0.0 {
-                let total_velocity = self.dir.scale(speed);
+            let speed = speed * self.speed_scale;
+
+            if self.gravity_scale != 0.0 {
+                self.gravity_velocity.y -= 9.81 * self.gravity_scale * ctx.dt;
+            }
+
+            if speed != 0.0 || self.gravity_velocity != Vector3::default() {
+                let previous_position = ctx.scene.graph[ctx.handle].global_position();
+
+                let total_velocity = self.dir.scale(speed) + self.gravity_velocity.scale(ctx.dt);
                 ctx.scene.graph[ctx.handle]
                     .local_transform_mut()
                     .offset(total_velocity);
@@ -304,6 +1084,20 @@ impl ScriptTrait for Projectile {
                 ctx.scene
                     .graph
                     .update_hierarchical_data_for_descendants(ctx.handle);
+
+                if self.trail_enabled {
+                    let position = ctx.scene.graph[ctx.handle].global_position();
+                    if !Trail::add_point(&mut ctx.scene.graph, self.trail, position) {
+                        self.trail = Trail::spawn(
+                            &mut ctx.scene.graph,
+                            previous_position,
+                            position,
+                            self.trail_color,
+                            self.trail_width,
+                            self.trail_lifetime,
+                        );
+                    }
+                }
             }
         }
 
@@ -313,10 +1107,23 @@ impl ScriptTrait for Projectile {
 
         let position = ctx.scene.graph[ctx.handle].global_position();
 
+        if ctx.dt > 0.0 {
+            self.velocity = (position - self.prev_frame_position).scale(1.0 / ctx.dt);
+        }
+        self.prev_frame_position = position;
+
         let direction = position - self.last_position;
 
+        self.flight_time += ctx.dt;
+
         let mut hit = None;
 
+        let ignored_owner = self.ignored_owner(&ctx.scene.graph);
+        // Projectiles fly through allies of the shooter, unless friendly fire is enabled.
+        let ignored_allies_of = (!game.is_friendly_fire_enabled()).then(|| DamageDealer {
+            entity: self.owner_character(&ctx.scene.graph),
+        });
+
         if self.use_ray_casting {
             hit = ray_hit(
                 self.last_position,
@@ -325,6 +1132,8 @@ impl ScriptTrait for Projectile {
                 &mut ctx.scene.graph,
                 // Ignore self collider.
                 self.collider,
+                ignored_owner,
+                ignored_allies_of,
             );
             self.last_position = position;
         }
@@ -332,19 +1141,7 @@ impl ScriptTrait for Projectile {
         if hit.is_none() {
             // Collect hits from self collider.
             if let Some(collider) = ctx.scene.graph.try_get_of_type::<Collider>(self.collider) {
-                let owner_character =
-                    ctx.scene
-                        .graph
-                        .try_get(self.owner)
-                        .map_or(Default::default(), |owner_node| {
-                            if let Some(weapon) = owner_node.try_get_script::<Weapon>() {
-                                weapon.owner
-                            } else if owner_node.try_get_script_component::<Character>().is_some() {
-                                self.owner
-                            } else {
-                                Default::default()
-                            }
-                        });
+                let owner_character = self.owner_character(&ctx.scene.graph);
 
                 'contact_loop: for contact in collider.contacts(&ctx.scene.graph.physics) {
                     let other_collider = if self.collider == contact.collider1 {
@@ -352,6 +1149,14 @@ impl ScriptTrait for Projectile {
                     } else {
                         contact.collider1
                     };
+                    if is_ignored_collider(
+                        &ctx.scene.graph,
+                        other_collider,
+                        ignored_owner,
+                        ignored_allies_of,
+                    ) {
+                        continue 'contact_loop;
+                    }
                     for manifold in contact.manifolds {
                         for point in manifold.points {
                             let contact_world_position = position
@@ -396,41 +1201,80 @@ impl ScriptTrait for Projectile {
             }
         }
 
+        if self.fuse_time > 0.0 {
+            if self.is_fuse_burnt() {
+                // Detonate in the air, there's nothing to attach a decal to.
+                hit = Some(Hit {
+                    shooter_actor: self.owner_character(&ctx.scene.graph),
+                    position,
+                    normal: Vector3::y(),
+                    collider: Default::default(),
+                    feature: FeatureId::Unknown,
+                    hit_box: None,
+                    query_buffer: vec![],
+                });
+            } else if hit.as_ref().is_some_and(|hit| {
+                hit.hit_box.is_none() && self.velocity.norm() < self.impact_detonation_speed
+            }) {
+                // Weak impact, let the projectile bounce off.
+                hit = None;
+            }
+        }
+
+        self.bounce_timer -= ctx.dt;
+        if let Some((normal, hit_position)) = hit
+            .as_ref()
+            .filter(|hit| hit.hit_box.is_none() && hit.collider.is_some())
+            .map(|hit| (hit.normal, hit.position))
+        {
+            if self.bounce_timer > 0.0 {
+                // Still touching the surface it has just bounced off.
+                hit = None;
+            } else if self.bounces_left > 0 {
+                self.bounce(
+                    ctx.handle,
+                    normal,
+                    hit_position,
+                    &mut ctx.scene.graph,
+                    ctx.dt,
+                );
+                hit = None;
+            }
+        }
+
         if let Some(hit) = hit {
-            match self.damage {
+            match self.damage.scale(self.damage_scale) {
                 Damage::Splash { radius, amount } => {
-                    let level = game.level.as_ref().unwrap();
-
-                    for &hit_box in level.hit_boxes.iter() {
-                        let hit_box_ref = &ctx.scene.graph[hit_box];
-                        if hit_box_ref.global_position().metric_distance(&position) <= radius {
-                            ctx.message_sender.send_hierarchical(
-                                hit_box,
-                                RoutingStrategy::Up,
-                                HitBoxMessage::Damage(HitBoxDamage {
-                                    hit_box,
-                                    damage: amount,
-                                    dealer: DamageDealer {
-                                        entity: hit.shooter_actor,
-                                    },
-                                    position: Some(DamagePosition {
-                                        point: hit.position,
-                                        direction,
-                                    }),
-                                    is_melee: false,
-                                }),
-                            );
-                        }
-                    }
+                    deal_splash_damage(
+                        game,
+                        &ctx.scene.graph,
+                        ctx.message_sender,
+                        position,
+                        radius,
+                        amount,
+                        DamageDealer {
+                            entity: hit.shooter_actor,
+                        },
+                        DamagePosition {
+                            point: hit.position,
+                            direction,
+                        },
+                    );
                 }
                 Damage::Point(amount) => {
                     if let Some(hit_box) = hit.hit_box {
+                        let is_critical =
+                            utils::is_probability_event_occurred(self.critical_hit_probability);
                         ctx.message_sender.send_hierarchical(
                             hit_box,
                             RoutingStrategy::Up,
                             HitBoxMessage::Damage(HitBoxDamage {
                                 hit_box,
-                                damage: amount,
+                                damage: if is_critical {
+                                    amount * self.critical_hit_damage_multiplier
+                                } else {
+                                    amount
+                                },
                                 dealer: DamageDealer {
                                     entity: hit.shooter_actor,
                                 },
@@ -439,29 +1283,89 @@ impl ScriptTrait for Projectile {
                                     direction,
                                 }),
                                 is_melee: false,
+                                is_critical,
                             }),
                         );
                     }
                 }
             }
 
-            if hit.hit_box.is_none() {
-                if let Some(effect_prefab) = self.environment_impact_effect.as_ref() {
+            if hit.hit_box.is_some() {
+                let effect_prefab = if ctx.plugins.get::<Game>().is_gore_enabled() {
+                    self.flesh_impact_effect.as_ref()
+                } else {
+                    self.environment_impact_effect.as_ref()
+                };
+                if let Some(effect_prefab) = effect_prefab {
                     effect_prefab.instantiate_at(
                         ctx.scene,
                         hit.position,
                         vector_to_quat(hit.normal),
                     );
                 }
+            } else {
+                let level = ctx.plugins.get::<Game>().level.as_ref();
 
-                Decal::new_bullet_hole(
-                    ctx.resource_manager,
-                    &mut ctx.scene.graph,
-                    hit.position,
-                    hit.normal,
-                    hit.collider,
-                    Color::opaque(20, 20, 20),
-                );
+                let material = level
+                    .and_then(|level| level.sound_manager.material_of(hit.collider, hit.feature));
+                let effect_prefab = material
+                    .and_then(|material| {
+                        self.material_impact_effects
+                            .iter()
+                            .find(|entry| entry.material == material)
+                    })
+                    .and_then(|entry| entry.effect.as_ref())
+                    .or(self.environment_impact_effect.as_ref());
+                if let Some(effect_prefab) = effect_prefab {
+                    effect_prefab.instantiate_at(
+                        ctx.scene,
+                        hit.position,
+                        vector_to_quat(hit.normal),
+                    );
+                }
+
+                if self.play_impact_sounds && material.is_some() {
+                    if let Some(level) = level {
+                        level.sound_manager.play_environment_sound(
+                            &mut ctx.scene.graph,
+                            hit.collider,
+                            hit.feature,
+                            hit.position,
+                            ctx.handle,
+                            SoundKind::Impact,
+                            1.0,
+                            1.0,
+                            0.5,
+                        );
+                    }
+                }
+
+                let decal = if hit.collider.is_none() {
+                    Handle::NONE
+                } else {
+                    match self.damage {
+                        Damage::Splash { radius, .. } => Decal::new_scorch_mark(
+                            ctx.resource_manager,
+                            &mut ctx.scene.graph,
+                            hit.position,
+                            hit.normal,
+                            hit.collider,
+                            radius,
+                        ),
+                        Damage::Point(_) => Decal::new_bullet_hole(
+                            ctx.resource_manager,
+                            &mut ctx.scene.graph,
+                            hit.position,
+                            hit.normal,
+                            hit.collider,
+                            Color::opaque(20, 20, 20),
+                        ),
+                    }
+                };
+
+                if let Some(level) = ctx.plugins.get_mut::<Game>().level.as_mut() {
+                    level.add_decal(decal, &mut ctx.scene.graph);
+                }
             }
 
             if let Some(collider) = ctx.scene.graph.try_get(hit.collider) {
@@ -476,12 +1380,143 @@ impl ScriptTrait for Projectile {
                 }
             }
 
-            // Defer destruction.
-            ctx.scene.graph[ctx.handle].set_lifetime(Some(0.0));
+            self.despawn(ctx);
+        } else if self.one_frame {
+            self.despawn(ctx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        bot::Bot,
+        character::DamageDealer,
+        weapon::{
+            projectile::{can_splash_damage, is_ignored_collider, Projectile},
+            Weapon,
+        },
+    };
+    use fyrox::{
+        core::pool::Handle,
+        scene::{base::BaseBuilder, graph::Graph, node::Node, pivot::PivotBuilder},
+    };
+
+    struct Actor {
+        body: Handle<Node>,
+        hit_box: Handle<Node>,
+        weapon: Handle<Node>,
+    }
+
+    fn add_actor(graph: &mut Graph, team: u32) -> Actor {
+        let hit_box = PivotBuilder::new(BaseBuilder::new()).build(graph);
+
+        let mut bot = Bot::default();
+        bot.team.set_value_and_mark_modified(team);
+        let body = PivotBuilder::new(
+            BaseBuilder::new()
+                .with_children(&[hit_box])
+                .with_script(bot),
+        )
+        .build(graph);
+
+        let mut weapon = Weapon::default();
+        weapon.set_owner(body);
+        let weapon = PivotBuilder::new(BaseBuilder::new().with_script(weapon)).build(graph);
+
+        Actor {
+            body,
+            hit_box,
+            weapon,
         }
+    }
 
-        if self.one_frame {
-            ctx.scene.graph[ctx.handle].set_lifetime(Some(0.0));
+    #[test]
+    fn test_is_ally_of_owner() {
+        let mut graph = Graph::new();
+        let shooter = add_actor(&mut graph, 1);
+        let ally = add_actor(&mut graph, 1);
+        let enemy = add_actor(&mut graph, 2);
+
+        for dealer in [shooter.body, shooter.weapon] {
+            let dealer = DamageDealer { entity: dealer };
+            assert!(dealer.is_ally_of_owner(ally.hit_box, &graph));
+            assert!(!dealer.is_ally_of_owner(enemy.hit_box, &graph));
+            // The dealer is not an ally of itself.
+            assert!(!dealer.is_ally_of_owner(shooter.hit_box, &graph));
         }
+
+        // Unknown dealers (the environment) have no allies.
+        assert!(!DamageDealer::default().is_ally_of_owner(ally.hit_box, &graph));
+    }
+
+    #[test]
+    fn test_neutral_characters_have_no_allies() {
+        let mut graph = Graph::new();
+        let first = add_actor(&mut graph, 0);
+        let second = add_actor(&mut graph, 0);
+
+        let dealer = DamageDealer {
+            entity: first.weapon,
+        };
+        assert!(!dealer.is_ally_of_owner(second.hit_box, &graph));
+    }
+
+    #[test]
+    fn test_splash_damage_spares_allies() {
+        let mut graph = Graph::new();
+        let shooter = add_actor(&mut graph, 1);
+        let ally = add_actor(&mut graph, 1);
+        let enemy = add_actor(&mut graph, 2);
+
+        let dealer = DamageDealer {
+            entity: shooter.weapon,
+        };
+
+        assert!(!can_splash_damage(dealer, ally.hit_box, &graph, false));
+        assert!(can_splash_damage(dealer, enemy.hit_box, &graph, false));
+        assert!(can_splash_damage(dealer, shooter.hit_box, &graph, false));
+
+        // Everyone is hurt when friendly fire is enabled.
+        assert!(can_splash_damage(dealer, ally.hit_box, &graph, true));
+    }
+
+    #[test]
+    fn test_point_blank_projectile_ignores_owner() {
+        let mut graph = Graph::new();
+        let shooter = add_actor(&mut graph, 1);
+        let enemy = add_actor(&mut graph, 2);
+
+        let mut projectile = Projectile {
+            owner: shooter.weapon,
+            ..Default::default()
+        };
+
+        // Right after the launch the projectile flies through the shooter, but not through
+        // anyone else.
+        let ignored_owner = projectile.ignored_owner(&graph);
+        assert_eq!(ignored_owner, shooter.body);
+        assert!(is_ignored_collider(
+            &graph,
+            shooter.hit_box,
+            ignored_owner,
+            None
+        ));
+        assert!(!is_ignored_collider(
+            &graph,
+            enemy.hit_box,
+            ignored_owner,
+            None
+        ));
+
+        // Once the grace time is over, the shooter could be hit (by a ricochet, for example).
+        projectile.flight_time = projectile.owner_collision_grace_time + 0.01;
+        let ignored_owner = projectile.ignored_owner(&graph);
+        assert!(!is_ignored_collider(
+            &graph,
+            shooter.hit_box,
+            ignored_owner,
+            None
+        ));
     }
 }